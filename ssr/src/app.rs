@@ -6,17 +6,25 @@ use page::pumpdump::{withdrawal, PndProfilePage};
 use state::app_type::AppType;
 // use crate::page::wallet::TestIndex;
 use crate::error_template::{AppError, ErrorTemplate};
-use component::{base_route::BaseRoute, nav::NavBar};
+use component::{base_route::CtxProvider, consent_banner::ConsentBanner, nav::NavBar};
 use page::{
+    bet_outcomes::{BetOutcomeWatcher, BetOutcomesCtx},
+    blocking::HiddenCreatorsCtx,
     err::ServerErrorPage,
+    following::FollowingFeedPage,
+    hashtag::HashtagPage,
     leaderboard::Leaderboard,
     logout::Logout,
     menu::Menu,
     post_view::{single_post::SinglePost, PostView, PostViewCtx},
     privacy::PrivacyPolicy,
-    profile::{profile_post::ProfilePost, ProfilePostsContext, ProfileView},
+    profile::{
+        bet_stats::BetStatsCache, profile_post::ProfilePost, ProfilePostsContext, ProfileView,
+    },
     refer_earn::ReferEarn,
+    reports::MyReports,
     root::RootPage,
+    search::SearchPage,
     settings::Settings,
     terms::TermsOfService,
     token::{
@@ -36,6 +44,7 @@ use leptos_router::hooks::use_location;
 use leptos_router::{components::*, path, MatchNestedRoutes};
 use page::terms_ios::TermsIos;
 use state::{audio_state::AudioState, content_seed_client::ContentSeedClient};
+use utils::consent::{ConsentCategory, ConsentCtx};
 use utils::event_streaming::events::HistoryCtx;
 use utils::event_streaming::EventHistory;
 use yral_canisters_common::Canisters;
@@ -87,6 +96,17 @@ fn GoogleAuthRedirectorRoute() -> impl MatchNestedRoutes + Clone {
     }
 }
 
+/// Routes rendered with the auth contexts, along with app wide watchers
+#[component]
+fn AppBaseRoute() -> impl IntoView {
+    view! {
+        <CtxProvider>
+            <Outlet/>
+            <BetOutcomeWatcher/>
+        </CtxProvider>
+    }
+}
+
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
         <!DOCTYPE html>
@@ -94,19 +114,6 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
             <head>
                 <meta charset="utf-8"/>
                 <meta name="viewport" content="width=device-width, initial-scale=1"/>
-                <Script async_="true">
-                    {r#"
-                    (function(w,d,s,l,i){
-                        w[l]=w[l]||[];
-                        w[l].push({'gtm.start': new Date().getTime(),event:'gtm.js'});
-                        var f=d.getElementsByTagName(s)[0], 
-                        j=d.createElement(s),dl=l!='dataLayer'?'&l='+l:'';
-                        j.async=true;
-                        j.src='https://www.googletagmanager.com/gtm.js?id='+i+dl;
-                        f.parentNode.insertBefore(j,f);
-                    })(window,document,'script','dataLayer','GTM-MNBWSPVJ');
-                    "#}
-                </Script>
                 <AutoReload options=options.clone() />
                 <HashedStylesheet id="leptos" options=options.clone()/>
                 <HydrationScripts options/>
@@ -136,6 +143,11 @@ pub fn App() -> impl IntoView {
     provide_context(AudioState::default());
     provide_context(CreateTokenCtx::default());
     provide_context(PostDetailsCacheCtx::default());
+    provide_context(BetOutcomesCtx::default());
+    provide_context(BetStatsCache::default());
+    provide_context(HiddenCreatorsCtx::for_logged_in_user());
+    let consent = ConsentCtx::from_cookie();
+    provide_context(consent);
 
     // History Tracking
     let history_ctx = HistoryCtx::default();
@@ -172,9 +184,26 @@ pub fn App() -> impl IntoView {
             // App manifest
             <Link rel="manifest" href=format!("/{}/manifest.json", app_state.asset_path())/>
 
+            // Google Tag Manager
+            <Show when=move || consent.allows(ConsentCategory::Analytics)>
+                <Script async_="true">
+                    {r#"
+                    (function(w,d,s,l,i){
+                        w[l]=w[l]||[];
+                        w[l].push({'gtm.start': new Date().getTime(),event:'gtm.js'});
+                        var f=d.getElementsByTagName(s)[0], 
+                        j=d.createElement(s),dl=l!='dataLayer'?'&l='+l:'';
+                        j.async=true;
+                        j.src='https://www.googletagmanager.com/gtm.js?id='+i+dl;
+                        f.parentNode.insertBefore(j,f);
+                    })(window,document,'script','dataLayer','GTM-MNBWSPVJ');
+                    "#}
+                </Script>
+            </Show>
+
             // GA4 Global Site Tag (gtag.js) - Google Analytics
             // G-6W5Q2MRX0E to test locally | G-PLNNETMSLM
-            <Show when=enable_ga4_script>
+            <Show when=move || enable_ga4_script() && consent.allows(ConsentCategory::Analytics)>
                 <Script
                     async_="true"
                     src=concat!("https://www.googletagmanager.com/gtag/js?id=", "G-PLNNETMSLM")
@@ -188,11 +217,13 @@ pub fn App() -> impl IntoView {
                 "#}
                 </Script>
             </Show>
-            <Script
-            async_="true"
-            src="https://sentry.yral.com/js-sdk-loader/3f7d672f8461961bd7b6bec57acf7f18.min.js"
-            crossorigin="anonymous"
-            ></Script>
+            <Show when=move || consent.allows(ConsentCategory::ErrorReporting)>
+                <Script
+                async_="true"
+                src="https://sentry.yral.com/js-sdk-loader/3f7d672f8461961bd7b6bec57acf7f18.min.js"
+                crossorigin="anonymous"
+                ></Script>
+            </Show>
 
             <Router>
             <main class="bg-black" id="body">
@@ -200,16 +231,20 @@ pub fn App() -> impl IntoView {
                     // auth redirect routes exist outside main context
                     <GoogleAuthRedirectHandlerRoute/>
                     <GoogleAuthRedirectorRoute/>
-                    <ParentRoute path=path!("") view=BaseRoute>
+                    <ParentRoute path=path!("") view=AppBaseRoute>
                         <Route path=path!("/") view=RootPage/>
                         <Route path=path!("/hot-or-not/:canister_id/:post_id") view=PostView/>
                         <Route path=path!("/post/:canister_id/:post_id") view=SinglePost/>
+                        <Route path=path!("/following") view=FollowingFeedPage/>
+                        <Route path=path!("/tag/:hashtag") view=HashtagPage/>
+                        <Route path=path!("/search") view=SearchPage/>
                         <Route path=path!("/profile/:canister_id/post/:post_id") view=ProfilePost/>
                         <Route path=path!("/pnd/profile") view=PndProfilePage/>
                         <Route path=path!("/upload") view=UploadPostPage/>
                         <Route path=path!("/error") view=ServerErrorPage/>
                         <Route path=path!("/menu") view=Menu/>
                         <Route path=path!("/settings") view=Settings/>
+                        <Route path=path!("/reports") view=MyReports/>
                         <Route path=path!("/refer-earn") view=ReferEarn/>
                         <Route path=path!("/profile/:id/:tab") view=ProfileView/>
                         <Route path=path!("/profile/:tab") view=ProfileView/>
@@ -242,6 +277,7 @@ pub fn App() -> impl IntoView {
                 </Routes>
 
             </main>
+            <ConsentBanner/>
             <nav>
                 <NavBar/>
             </nav>
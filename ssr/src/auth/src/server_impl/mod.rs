@@ -14,6 +14,7 @@ use k256::elliptic_curve::JwkEcKey;
 use leptos::prelude::*;
 use leptos_axum::{extract_with_state, ResponseOptions};
 use rand_chacha::rand_core::OsRng;
+use web_time::Duration;
 use yral_canisters_common::utils::time::current_epoch;
 
 use consts::auth::{REFRESH_MAX_AGE, REFRESH_TOKEN_COOKIE};
@@ -21,7 +22,7 @@ use consts::auth::{REFRESH_MAX_AGE, REFRESH_TOKEN_COOKIE};
 use self::store::{KVStore, KVStoreImpl};
 use yral_types::delegated_identity::DelegatedIdentityWire;
 
-use super::{delegate_identity, RefreshToken};
+use super::{delegate_identity, delegate_identity_with_max_age, RefreshToken};
 
 fn set_cookies(resp: &ResponseOptions, jar: impl IntoResponse) {
     let resp_jar = jar.into_response();
@@ -48,6 +49,41 @@ pub fn extract_principal_from_cookie(
     Ok(Some(token.principal))
 }
 
+pub async fn extract_principal_impl() -> Result<Option<Principal>, ServerFnError> {
+    let key: Key = expect_context();
+    let jar: SignedCookieJar = extract_with_state(&key).await?;
+    extract_principal_from_cookie(&jar)
+}
+
+fn registered_at_key(principal: Principal) -> String {
+    format!("registered-at:{}", principal.to_text())
+}
+
+/// When `principal` registered, in ms since the unix epoch, if it's known
+pub async fn registered_at_ms(
+    kv: &KVStoreImpl,
+    principal: Principal,
+) -> Result<Option<u64>, ServerFnError> {
+    Ok(kv.read_json(registered_at_key(principal)).await?)
+}
+
+/// Records when `principal` registered, an earlier record is kept
+/// returns the registration time now on record
+pub async fn record_registered_at(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    at_ms: u64,
+) -> Result<u64, ServerFnError> {
+    let key = registered_at_key(principal);
+    if let Some(existing) = kv.read_json::<u64>(key.clone()).await? {
+        if existing <= at_ms {
+            return Ok(existing);
+        }
+    }
+    kv.write_json(key, &at_ms).await?;
+    Ok(at_ms)
+}
+
 async fn fetch_identity_from_kv(
     kv: &KVStoreImpl,
     principal: Principal,
@@ -143,6 +179,23 @@ pub async fn extract_identity_impl() -> Result<Option<DelegatedIdentityWire>, Se
     Ok(Some(delegate_identity(&base_identity)))
 }
 
+/// A delegation of `principal`'s stored identity valid for `max_age`
+/// lets the server act for a user without a request from them, e.g. in scheduled jobs
+pub async fn delegated_identity_for(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    max_age: Duration,
+) -> Result<Option<DelegatedIdentityWire>, ServerFnError> {
+    let Some(identity) = fetch_identity_from_kv(kv, principal).await? else {
+        return Ok(None);
+    };
+    let base_identity = Secp256k1Identity::from_private_key(identity);
+    Ok(Some(delegate_identity_with_max_age(
+        &base_identity,
+        max_age,
+    )))
+}
+
 pub async fn logout_identity_impl() -> Result<DelegatedIdentityWire, ServerFnError> {
     let key: Key = expect_context();
     let kv: KVStoreImpl = expect_context();
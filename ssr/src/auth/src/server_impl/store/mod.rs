@@ -3,6 +3,7 @@ pub mod redis_kv;
 
 use enum_dispatch::enum_dispatch;
 use redis::RedisError;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -21,6 +22,33 @@ pub enum KVError {
 pub(crate) trait KVStore: Send {
     async fn read(&self, key: String) -> Result<Option<String>, KVError>;
     async fn write(&self, key: String, value: String) -> Result<(), KVError>;
+    /// Removes whatever is stored at `key`, values, counters, sets and claims alike
+    async fn delete(&self, key: String) -> Result<(), KVError>;
+    /// Atomically adds `by` to the counter at `key`, returns the new count
+    async fn incr(&self, key: String, by: i64) -> Result<i64, KVError>;
+    /// Atomically takes `key` if nobody holds it, the claim lapses after `ttl_ms`
+    async fn claim(&self, key: String, ttl_ms: u64) -> Result<bool, KVError>;
+    /// Adds `member` to the sorted set at `key` or updates its score
+    /// returns whether `member` is new
+    async fn set_add(&self, key: String, member: String, score: f64) -> Result<bool, KVError>;
+    /// Returns whether `member` was in the set
+    async fn set_remove(&self, key: String, member: String) -> Result<bool, KVError>;
+    async fn set_score(&self, key: String, member: String) -> Result<Option<f64>, KVError>;
+    async fn set_len(&self, key: String) -> Result<usize, KVError>;
+    /// Members from highest to lowest score, `end` is exclusive
+    async fn set_range(
+        &self,
+        key: String,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<String>, KVError>;
+    /// Up to `limit` members scored at most `max`, lowest score first
+    async fn set_range_until(
+        &self,
+        key: String,
+        max: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, KVError>;
 }
 
 #[derive(Clone)]
@@ -29,3 +57,64 @@ pub enum KVStoreImpl {
     ReDB(redb_kv::ReDBKV),
     Redis(redis_kv::RedisKV),
 }
+
+impl KVStoreImpl {
+    pub async fn read_json<T: DeserializeOwned>(&self, key: String) -> Result<Option<T>, KVError> {
+        let Some(value) = self.read(key).await? else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_str(&value)?))
+    }
+
+    pub async fn write_json<T: Serialize>(&self, key: String, value: &T) -> Result<(), KVError> {
+        self.write(key, serde_json::to_string(value)?).await
+    }
+
+    // the trait is crate private, these expose it to other crates
+
+    pub async fn delete(&self, key: String) -> Result<(), KVError> {
+        KVStore::delete(self, key).await
+    }
+
+    pub async fn incr(&self, key: String, by: i64) -> Result<i64, KVError> {
+        KVStore::incr(self, key, by).await
+    }
+
+    pub async fn claim(&self, key: String, ttl_ms: u64) -> Result<bool, KVError> {
+        KVStore::claim(self, key, ttl_ms).await
+    }
+
+    pub async fn set_add(&self, key: String, member: String, score: f64) -> Result<bool, KVError> {
+        KVStore::set_add(self, key, member, score).await
+    }
+
+    pub async fn set_remove(&self, key: String, member: String) -> Result<bool, KVError> {
+        KVStore::set_remove(self, key, member).await
+    }
+
+    pub async fn set_contains(&self, key: String, member: String) -> Result<bool, KVError> {
+        Ok(KVStore::set_score(self, key, member).await?.is_some())
+    }
+
+    pub async fn set_len(&self, key: String) -> Result<usize, KVError> {
+        KVStore::set_len(self, key).await
+    }
+
+    pub async fn set_range(
+        &self,
+        key: String,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<String>, KVError> {
+        KVStore::set_range(self, key, start, end).await
+    }
+
+    pub async fn set_range_until(
+        &self,
+        key: String,
+        max: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, KVError> {
+        KVStore::set_range_until(self, key, max, limit).await
+    }
+}
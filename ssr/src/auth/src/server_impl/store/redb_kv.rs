@@ -1,23 +1,36 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, path::Path, sync::Arc};
 
-use redb::{Database, TableDefinition};
+use redb::{Database, ReadableTable, TableDefinition, WriteTransaction};
 use tokio::task::spawn_blocking;
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use super::{KVError, KVStore};
 
 const TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv");
 const RAW_METADATA_TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv-meta");
+/// Sorted sets, each stored as a json map of member to score
+const SET_TABLE: TableDefinition<&str, &str> = TableDefinition::new("kv-sets");
+/// Claims and when they lapse, in ms since the unix epoch
+const CLAIM_TABLE: TableDefinition<&str, u64> = TableDefinition::new("kv-claims");
+
+type SortedSet = BTreeMap<String, f64>;
 
 #[derive(Clone)]
 pub struct ReDBKV(Arc<Database>);
 
 impl ReDBKV {
     pub fn new() -> Result<Self, redb::Error> {
-        let db = Database::create("./redb-kv.db")?;
+        Self::open("./redb-kv.db")
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, redb::Error> {
+        let db = Database::create(path)?;
         let write_txn = db.begin_write()?;
         {
             write_txn.open_table(TABLE)?;
             write_txn.open_table(RAW_METADATA_TABLE)?;
+            write_txn.open_table(SET_TABLE)?;
+            write_txn.open_table(CLAIM_TABLE)?;
         }
         write_txn.commit()?;
         Ok(Self(Arc::new(db)))
@@ -31,6 +44,81 @@ impl ReDBKV {
         let db = self.0.clone();
         spawn_blocking(move || f(&db).map_err(|e| e.into()))
     }
+
+    /// Runs `f` in a single write transaction
+    /// redb serializes write transactions, so `f` is atomic
+    async fn atomically<F, R>(&self, f: F) -> Result<R, KVError>
+    where
+        F: FnOnce(&WriteTransaction) -> Result<R, KVError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let db = self.0.clone();
+        spawn_blocking(move || {
+            let write_txn = db.begin_write().map_err(redb::Error::from)?;
+            let res = f(&write_txn)?;
+            write_txn.commit().map_err(redb::Error::from)?;
+            Ok(res)
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Reads a sorted set, then writes back whatever `f` leaves in it
+    /// the whole set is (de)serialized on every write, so each update is O(n) in its size,
+    /// fine for local use but large sets like busy search index words belong in redis
+    async fn update_set<F, R>(&self, key: String, f: F) -> Result<R, KVError>
+    where
+        F: FnOnce(&mut SortedSet) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.atomically(move |txn| {
+            let mut table = txn.open_table(SET_TABLE).map_err(redb::Error::from)?;
+            let mut set: SortedSet = match table.get(key.as_str()).map_err(redb::Error::from)? {
+                Some(v) => serde_json::from_str(v.value())?,
+                None => SortedSet::new(),
+            };
+            let res = f(&mut set);
+            if set.is_empty() {
+                table.remove(key.as_str()).map_err(redb::Error::from)?;
+            } else {
+                table
+                    .insert(key.as_str(), serde_json::to_string(&set)?.as_str())
+                    .map_err(redb::Error::from)?;
+            }
+            Ok(res)
+        })
+        .await
+    }
+
+    async fn read_set(&self, key: String) -> Result<SortedSet, KVError> {
+        let raw = self
+            .spawn_blocking(move |db| {
+                let read_txn = db.begin_read()?;
+                let table = read_txn.open_table(SET_TABLE)?;
+                let v = table.get(key.as_str())?;
+                Ok(v.map(|ag| ag.value().to_string()))
+            })
+            .await
+            .unwrap()?;
+        Ok(match raw {
+            Some(raw) => serde_json::from_str(&raw)?,
+            None => SortedSet::new(),
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Members ordered by score, ties broken by member
+fn by_score(set: SortedSet) -> Vec<(String, f64)> {
+    let mut members = set.into_iter().collect::<Vec<_>>();
+    members.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    members
 }
 
 impl KVStore for ReDBKV {
@@ -61,4 +149,182 @@ impl KVStore for ReDBKV {
         .await
         .unwrap()
     }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        self.atomically(move |txn| {
+            txn.open_table(TABLE)
+                .map_err(redb::Error::from)?
+                .remove(key.as_str())
+                .map_err(redb::Error::from)?;
+            txn.open_table(SET_TABLE)
+                .map_err(redb::Error::from)?
+                .remove(key.as_str())
+                .map_err(redb::Error::from)?;
+            txn.open_table(CLAIM_TABLE)
+                .map_err(redb::Error::from)?
+                .remove(key.as_str())
+                .map_err(redb::Error::from)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn incr(&self, key: String, by: i64) -> Result<i64, KVError> {
+        self.atomically(move |txn| {
+            let mut table = txn.open_table(TABLE).map_err(redb::Error::from)?;
+            let count = match table.get(key.as_str()).map_err(redb::Error::from)? {
+                Some(v) => serde_json::from_str::<i64>(v.value())?,
+                None => 0,
+            } + by;
+            table
+                .insert(key.as_str(), count.to_string().as_str())
+                .map_err(redb::Error::from)?;
+            Ok(count)
+        })
+        .await
+    }
+
+    async fn claim(&self, key: String, ttl_ms: u64) -> Result<bool, KVError> {
+        self.atomically(move |txn| {
+            let mut table = txn.open_table(CLAIM_TABLE).map_err(redb::Error::from)?;
+            let now = now_ms();
+            let held = table
+                .get(key.as_str())
+                .map_err(redb::Error::from)?
+                .is_some_and(|lapses_at| lapses_at.value() > now);
+            if held {
+                return Ok(false);
+            }
+            table
+                .insert(key.as_str(), now + ttl_ms)
+                .map_err(redb::Error::from)?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn set_add(&self, key: String, member: String, score: f64) -> Result<bool, KVError> {
+        self.update_set(key, move |set| set.insert(member, score).is_none())
+            .await
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<bool, KVError> {
+        self.update_set(key, move |set| set.remove(&member).is_some())
+            .await
+    }
+
+    async fn set_score(&self, key: String, member: String) -> Result<Option<f64>, KVError> {
+        Ok(self.read_set(key).await?.get(&member).copied())
+    }
+
+    async fn set_len(&self, key: String) -> Result<usize, KVError> {
+        Ok(self.read_set(key).await?.len())
+    }
+
+    async fn set_range(
+        &self,
+        key: String,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<String>, KVError> {
+        let set = self.read_set(key).await?;
+        Ok(by_score(set)
+            .into_iter()
+            .rev()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .map(|(member, _)| member)
+            .collect())
+    }
+
+    async fn set_range_until(
+        &self,
+        key: String,
+        max: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, KVError> {
+        let set = self.read_set(key).await?;
+        Ok(by_score(set)
+            .into_iter()
+            .take_while(|(_, score)| *score <= max)
+            .take(limit)
+            .map(|(member, _)| member)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_kv(name: &str) -> ReDBKV {
+        let path = std::env::temp_dir().join(format!("redb-kv-{name}-{}.db", std::process::id()));
+        _ = std::fs::remove_file(&path);
+        ReDBKV::open(path).unwrap()
+    }
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_multi_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    #[test]
+    fn concurrent_increments_are_not_lost() {
+        let kv = test_kv("incr");
+        block_on(async {
+            let handles = (0..20)
+                .map(|_| {
+                    let kv = kv.clone();
+                    tokio::spawn(async move { kv.incr("count".into(), 1).await.unwrap() })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                handle.await.unwrap();
+            }
+            assert_eq!(kv.incr("count".into(), 0).await.unwrap(), 20);
+            assert_eq!(
+                kv.read("count".into()).await.unwrap().as_deref(),
+                Some("20")
+            );
+        });
+    }
+
+    #[test]
+    fn sorted_sets_page_newest_first() {
+        let kv = test_kv("sets");
+        block_on(async {
+            let key = || "set".to_string();
+            assert!(kv.set_add(key(), "a".into(), 1.0).await.unwrap());
+            assert!(kv.set_add(key(), "b".into(), 3.0).await.unwrap());
+            assert!(kv.set_add(key(), "c".into(), 2.0).await.unwrap());
+            assert!(!kv.set_add(key(), "a".into(), 4.0).await.unwrap());
+
+            assert_eq!(kv.set_len(key()).await.unwrap(), 3);
+            assert_eq!(kv.set_range(key(), 0, 2).await.unwrap(), ["a", "b"]);
+            assert_eq!(kv.set_range(key(), 2, 10).await.unwrap(), ["c"]);
+            assert_eq!(
+                kv.set_range_until(key(), 3.0, 10).await.unwrap(),
+                ["c", "b"]
+            );
+
+            assert!(kv.set_remove(key(), "b".into()).await.unwrap());
+            assert!(!kv.set_remove(key(), "b".into()).await.unwrap());
+            assert_eq!(kv.set_score(key(), "b".into()).await.unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn claims_are_exclusive_until_released() {
+        let kv = test_kv("claims");
+        block_on(async {
+            assert!(kv.claim("job".into(), 60_000).await.unwrap());
+            assert!(!kv.claim("job".into(), 60_000).await.unwrap());
+            kv.delete("job".into()).await.unwrap();
+            assert!(kv.claim("job".into(), 0).await.unwrap());
+            // a lapsed claim can be taken again
+            assert!(kv.claim("job".into(), 60_000).await.unwrap());
+        });
+    }
 }
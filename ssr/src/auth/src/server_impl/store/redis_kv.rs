@@ -27,4 +27,76 @@ impl KVStore for RedisKV {
         con.hset::<_, _, _, ()>(key, AUTH_FIELD, value).await?;
         Ok(())
     }
+
+    async fn delete(&self, key: String) -> Result<(), KVError> {
+        let mut con = self.0.get().await?;
+        con.del::<_, ()>(key).await?;
+        Ok(())
+    }
+
+    async fn incr(&self, key: String, by: i64) -> Result<i64, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(con.hincr(key, AUTH_FIELD, by).await?)
+    }
+
+    async fn claim(&self, key: String, ttl_ms: u64) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        let res: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(1)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut *con)
+            .await?;
+        Ok(res.is_some())
+    }
+
+    async fn set_add(&self, key: String, member: String, score: f64) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        let added: usize = con.zadd(key, member, score).await?;
+        Ok(added > 0)
+    }
+
+    async fn set_remove(&self, key: String, member: String) -> Result<bool, KVError> {
+        let mut con = self.0.get().await?;
+        let removed: usize = con.zrem(key, member).await?;
+        Ok(removed > 0)
+    }
+
+    async fn set_score(&self, key: String, member: String) -> Result<Option<f64>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(con.zscore(key, member).await?)
+    }
+
+    async fn set_len(&self, key: String) -> Result<usize, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(con.zcard(key).await?)
+    }
+
+    async fn set_range(
+        &self,
+        key: String,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<String>, KVError> {
+        if start >= end {
+            return Ok(vec![]);
+        }
+        let mut con = self.0.get().await?;
+        let stop = end.min(isize::MAX as usize) as isize - 1;
+        Ok(con.zrevrange(key, start as isize, stop).await?)
+    }
+
+    async fn set_range_until(
+        &self,
+        key: String,
+        max: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, KVError> {
+        let mut con = self.0.get().await?;
+        Ok(con
+            .zrangebyscore_limit(key, "-inf", max, 0, limit as isize)
+            .await?)
+    }
 }
@@ -26,7 +26,9 @@ async fn issue_referral_rewards(referee_canister: Principal) -> Result<(), Serve
 #[server]
 async fn mark_user_registered(user_principal: Principal) -> Result<bool, ServerFnError> {
     use self::server_fn_impl::mark_user_registered_impl;
+    use auth::server_impl::{record_registered_at, store::KVStoreImpl};
     use state::canisters::unauth_canisters;
+    use yral_canisters_common::utils::time::current_epoch;
 
     // TODO: verify that user principal is registered
     let canisters = unauth_canisters();
@@ -34,7 +36,16 @@ async fn mark_user_registered(user_principal: Principal) -> Result<bool, ServerF
         .get_individual_canister_by_user_principal(user_principal)
         .await?
         .ok_or_else(|| ServerFnError::new("User not found"))?;
-    mark_user_registered_impl(user_canister).await
+    let first_time_login = mark_user_registered_impl(user_canister).await?;
+
+    // account age gates some creator features
+    // older accounts are backfilled from their posts when their age is first checked
+    if first_time_login {
+        let kv: KVStoreImpl = expect_context();
+        record_registered_at(&kv, user_principal, current_epoch().as_millis() as u64).await?;
+    }
+
+    Ok(first_time_login)
 }
 
 pub async fn handle_user_login(
@@ -1,7 +1,6 @@
 use candid::Principal;
 use ic_agent::identity::Secp256k1Identity;
 use leptos::prelude::*;
-use leptos_router::components::Outlet;
 use leptos_router::hooks::use_query;
 use leptos_use::use_cookie;
 
@@ -28,8 +27,9 @@ struct Referrer {
     user_refer: String,
 }
 
+/// Auth and canister contexts shared by all routes
 #[component]
-fn CtxProvider(children: ChildrenFn) -> impl IntoView {
+pub fn CtxProvider(children: ChildrenFn) -> impl IntoView {
     let auth = AuthState::default();
     provide_context(auth);
 
@@ -168,12 +168,3 @@ fn CtxProvider(children: ChildrenFn) -> impl IntoView {
         </Suspense>
     }
 }
-
-#[component]
-pub fn BaseRoute() -> impl IntoView {
-    view! {
-        <CtxProvider>
-            <Outlet/>
-        </CtxProvider>
-    }
-}
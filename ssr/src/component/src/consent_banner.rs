@@ -0,0 +1,38 @@
+use leptos::prelude::*;
+use utils::consent::{Consent, ConsentCtx};
+
+/// Asks for consent until the user makes a choice
+#[component]
+pub fn ConsentBanner() -> impl IntoView {
+    let ctx: ConsentCtx = expect_context();
+
+    view! {
+        <Show when=move || ctx.consent.with(|c| c.is_none())>
+            <div class="fixed bottom-0 inset-x-0 z-[60] flex flex-col gap-3 p-4 pb-20 bg-neutral-900 text-white text-sm">
+                <span>
+                    "We use cookies for analytics, error reporting and to personalise your feed. "
+                    <a href="/privacy-policy" class="text-primary-600">
+                        "Privacy Policy"
+                    </a>
+                </span>
+                <div class="flex flex-row gap-2 justify-end">
+                    <a href="/settings" class="px-4 py-2 rounded-lg text-white/70">
+                        "Manage"
+                    </a>
+                    <button
+                        class="px-4 py-2 rounded-lg bg-white/10"
+                        on:click=move |_| ctx.set(Consent::ESSENTIAL)
+                    >
+                        "Essential only"
+                    </button>
+                    <button
+                        class="px-4 py-2 rounded-lg bg-primary-600"
+                        on:click=move |_| ctx.set(Consent::ALL)
+                    >
+                        "Accept all"
+                    </button>
+                </div>
+            </div>
+        </Show>
+    }
+}
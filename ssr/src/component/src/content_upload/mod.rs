@@ -0,0 +1,250 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use super::spinner::Spinner;
+use leptos_use::use_interval_fn;
+use serde::{Deserialize, Serialize};
+#[derive(Default, Clone, Copy)]
+pub struct AuthorizedUserToSeedContent(pub RwSignal<Option<(bool, Principal)>>);
+use candid::Principal;
+use leptos::prelude::*;
+
+#[cfg(feature = "ssr")]
+pub use server_impl::run_import_worker;
+
+/// Links accepted in one submission
+pub const MAX_IMPORT_BATCH: usize = 20;
+/// Imports remembered per creator, the oldest finished ones are dropped first
+pub const MAX_IMPORT_JOBS: usize = 100;
+const POLL_INTERVAL_MS: u64 = 5000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ImportStatus {
+    Queued,
+    /// Sent to the download-upload service
+    Downloading,
+    /// Uploaded, waiting for the post to show up
+    Processing,
+    Published {
+        post_id: u64,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl ImportStatus {
+    pub fn failed(error: impl ToString) -> Self {
+        Self::Failed {
+            error: error.to_string(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Published { .. } | Self::Failed { .. })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Queued => "Queued".into(),
+            Self::Downloading => "Downloading".into(),
+            Self::Processing => "Processing".into(),
+            Self::Published { .. } => "Published".into(),
+            Self::Failed { error } => format!("Failed: {error}"),
+        }
+    }
+}
+
+/// A link being imported as a post of the creator
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportJob {
+    pub id: String,
+    pub url: String,
+    pub status: ImportStatus,
+    pub created_at_ms: u64,
+    pub updated_at_ms: u64,
+    /// Newest post of the creator before the import started
+    #[serde(default)]
+    pub latest_post_id: Option<u64>,
+}
+
+impl ImportJob {
+    pub fn new(id: String, url: String, now_ms: u64) -> Self {
+        Self {
+            id,
+            url,
+            status: ImportStatus::Queued,
+            created_at_ms: now_ms,
+            updated_at_ms: now_ms,
+            latest_post_id: None,
+        }
+    }
+
+    pub fn set_status(&mut self, status: ImportStatus, now_ms: u64) {
+        self.status = status;
+        self.updated_at_ms = now_ms;
+    }
+}
+
+/// Queues each link for import, the posts are published as the caller
+/// returns every import of the caller, newest first
+#[server]
+pub async fn submit_imports(urls: Vec<String>) -> Result<Vec<ImportJob>, ServerFnError> {
+    server_impl::submit_imports(urls).await
+}
+
+/// Every import of the caller, newest first
+#[server]
+pub async fn list_imports() -> Result<Vec<ImportJob>, ServerFnError> {
+    server_impl::list_imports().await
+}
+
+#[server]
+pub async fn retry_import(id: String) -> Result<Vec<ImportJob>, ServerFnError> {
+    server_impl::retry_import(id).await
+}
+
+#[component]
+fn ImportJobItem(job: ImportJob, retry: Action<String, (), LocalStorage>) -> impl IntoView {
+    let failed = matches!(job.status, ImportStatus::Failed { .. });
+    let id = job.id.clone();
+
+    view! {
+        <div class="flex flex-row w-full items-center gap-2 text-sm text-white">
+            <div class="flex flex-col grow min-w-0">
+                <span class="truncate">{job.url}</span>
+                <span class="text-xs text-white/60">{job.status.label()}</span>
+            </div>
+            <Show when=move || failed>
+                <button
+                    class="border border-solid px-2 hover:bg-white hover:text-black"
+                    disabled=move || retry.pending().get()
+                    on:click={
+                        let id = id.clone();
+                        move |_| {
+                            retry.dispatch(id.clone());
+                        }
+                    }
+                >
+                    Retry
+                </button>
+            </Show>
+        </div>
+    }
+}
+
+#[component]
+fn YoutubeUploadInner(#[prop(optional)] url: String) -> impl IntoView {
+    let url_value = RwSignal::new(url);
+    let jobs = RwSignal::new(Vec::<ImportJob>::new());
+    let error = RwSignal::new(String::new());
+    let set_jobs = move |res: Result<Vec<ImportJob>, ServerFnError>| match res {
+        Ok(res) => {
+            jobs.set(res);
+            error.set(String::new());
+        }
+        Err(e) => error.set(e.to_string()),
+    };
+
+    let on_submit: Action<(), (), LocalStorage> = Action::new_unsync(move |_| {
+        let urls = url_value
+            .get_untracked()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        async move {
+            let res = submit_imports(urls).await;
+            if res.is_ok() {
+                url_value.set(String::new());
+            }
+            set_jobs(res);
+        }
+    });
+    let retry: Action<String, (), LocalStorage> = Action::new_unsync(move |id: &String| {
+        let id = id.clone();
+        async move { set_jobs(retry_import(id).await) }
+    });
+
+    // the worker moves jobs along in the background
+    let refresh: Action<(), (), LocalStorage> =
+        Action::new_unsync(move |_| async move { set_jobs(list_imports().await) });
+    Effect::new(move |_| {
+        refresh.dispatch(());
+    });
+    _ = use_interval_fn(
+        move || {
+            let pending = jobs.with_untracked(|jobs| jobs.iter().any(|j| !j.status.is_finished()));
+            if pending && !refresh.pending().get_untracked() {
+                refresh.dispatch(());
+            }
+        },
+        POLL_INTERVAL_MS,
+    );
+
+    view! {
+        <div data-hk="1-0-0-3" class="flex h-full items-center justify-around p-4">
+            <div data-hk="1-0-0-4" class="flex flex-col items-center justify-center">
+                <div class="flex h-full flex-col justify-around gap-6">
+                    <div class="flex basis-9/12 flex-col items-center justify-center">
+                        <h1 data-hk="1-0-0-5" class="text-2xl md:text-3xl text-white">
+                            VIDEO IMPORTER
+                        </h1>
+                    </div>
+                    <div class="flex basis-3/12 flex-col justify-around items-center gap-4">
+                        <textarea
+                            rows=4
+                            prop:value=move || url_value.get()
+                            on:input=move |ev| {
+                                let val = event_target_value(&ev);
+                                url_value.set(val);
+                            }
+
+                            placeholder=" Paste your links here, one per line"
+                            class="p-1 md:text-xl w-full"
+                        ></textarea>
+                        <button
+                            type="submit"
+                            class="border border-solid px-4 text-xl md:text-2xl w-fit text-white hover:bg-white hover:text-black"
+                            disabled=move || on_submit.pending().get()
+                            on:click=move |_| {on_submit.dispatch(());}
+                        >
+
+                            Submit
+                        </button>
+                        <p class="text-base md:text-lg text-red-500">{error}</p>
+                        <div class="flex flex-col w-full gap-2 max-h-64 overflow-y-auto">
+                            <For
+                                each=move || jobs.get()
+                                key=|job| (job.id.clone(), job.updated_at_ms)
+                                let:job
+                            >
+                                <ImportJobItem job retry />
+                            </For>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn YoutubeUpload(#[prop(optional)] url: String, user_principal: Principal) -> impl IntoView {
+    let url_s = StoredValue::new(url);
+
+    let authorized_ctx: AuthorizedUserToSeedContent = expect_context();
+    let authorized = authorized_ctx.0;
+    let loaded = move || {
+        authorized()
+            .map(|(_, principal)| principal == user_principal)
+            .unwrap_or_default()
+    };
+
+    view! {
+        <Show when=loaded fallback=Spinner>
+            <Show when=move || authorized().map(|(a, _)| a).unwrap_or_default()>
+                <YoutubeUploadInner url=url_s.get_value() />
+            </Show>
+        </Show>
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use auth::server_impl::{delegated_identity_for, extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use reqwest::Url;
+use state::content_seed_client::ContentSeedClient;
+use web_time::{Duration, SystemTime};
+use yral_canisters_client::individual_user_template::Result13;
+use yral_canisters_common::Canisters;
+use yral_types::delegated_identity::DelegatedIdentityWire;
+
+use super::{ImportJob, ImportStatus, MAX_IMPORT_BATCH, MAX_IMPORT_JOBS};
+
+/// Creators with unfinished imports, so the worker doesn't scan every creator
+const ACTIVE_IMPORTERS_KEY: &str = "content-import-creators";
+const WORKER_INTERVAL: Duration = Duration::from_secs(10);
+/// Imports whose post doesn't show up within this are marked failed
+const PROCESSING_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+/// The download-upload service publishes with the delegation, so it has to outlive processing
+const IMPORT_DELEGATION_MAX_AGE: Duration = Duration::from_millis(PROCESSING_TIMEOUT_MS);
+/// How long the worker waits on the download-upload service before failing the import
+const UPLOAD_REQUEST_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+/// Ids of the creator's imports, scored by when they were submitted
+fn job_ids_key(creator: Principal) -> String {
+    format!("content-import-ids:{}", creator.to_text())
+}
+
+/// Each import is stored on its own, so the worker and new submissions can't overwrite each other
+fn job_key(creator: Principal, id: &str) -> String {
+    format!("content-import:{}:{id}", creator.to_text())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Newest first
+async fn read_jobs(kv: &KVStoreImpl, creator: Principal) -> Result<Vec<ImportJob>, ServerFnError> {
+    let ids = kv.set_range(job_ids_key(creator), 0, usize::MAX).await?;
+    let mut jobs = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(job) = read_job(kv, creator, &id).await? {
+            jobs.push(job);
+        }
+    }
+    // ids start with the submission time and the zero padded position in the batch
+    jobs.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(jobs)
+}
+
+async fn read_job(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    id: &str,
+) -> Result<Option<ImportJob>, ServerFnError> {
+    Ok(kv.read_json(job_key(creator, id)).await?)
+}
+
+async fn write_job(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    job: &ImportJob,
+) -> Result<(), ServerFnError> {
+    kv.write_json(job_key(creator, &job.id), job).await?;
+    kv.set_add(
+        job_ids_key(creator),
+        job.id.clone(),
+        job.created_at_ms as f64,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn mark_active(kv: &KVStoreImpl, creator: Principal) -> Result<(), ServerFnError> {
+    kv.set_add(
+        ACTIVE_IMPORTERS_KEY.into(),
+        creator.to_text(),
+        now_ms() as f64,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Drops the creator from the worker's list once every import finished
+/// removed before checking, so imports submitted meanwhile add them back or are seen here
+async fn mark_done(kv: &KVStoreImpl, creator: Principal) -> Result<(), ServerFnError> {
+    kv.set_remove(ACTIVE_IMPORTERS_KEY.into(), creator.to_text())
+        .await?;
+    let jobs = read_jobs(kv, creator).await?;
+    if jobs.iter().any(|j| !j.status.is_finished()) {
+        mark_active(kv, creator).await?;
+    }
+    Ok(())
+}
+
+/// A delegation of the creator's identity, for the worker acting on their behalf
+async fn importer_identity(
+    kv: &KVStoreImpl,
+    creator: Principal,
+) -> Result<DelegatedIdentityWire, ServerFnError> {
+    delegated_identity_for(kv, creator, IMPORT_DELEGATION_MAX_AGE)
+        .await?
+        .ok_or_else(|| ServerFnError::new("creator's identity not found"))
+}
+
+/// The caller, if they may seed content
+async fn authorized_importer() -> Result<Principal, ServerFnError> {
+    let caller = extract_principal_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+
+    // checked here as well, the menu only hides the importer
+    let allowed = ContentSeedClient::default()
+        .check_if_authorized(caller)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    if !allowed {
+        return Err(ServerFnError::new("not allowed to import content"));
+    }
+
+    Ok(caller)
+}
+
+fn parse_import_url(url: &str) -> Result<String, ServerFnError> {
+    let parsed =
+        Url::parse(url.trim()).map_err(|_| ServerFnError::Args(format!("invalid URL {url}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ServerFnError::Args(format!("invalid URL {url}")));
+    }
+    Ok(parsed.to_string())
+}
+
+/// Keeps the newest jobs, unfinished ones are never dropped
+async fn trim_jobs(kv: &KVStoreImpl, creator: Principal) -> Result<(), ServerFnError> {
+    let jobs = read_jobs(kv, creator).await?;
+    let mut excess = jobs.len().saturating_sub(MAX_IMPORT_JOBS);
+    // oldest first
+    for job in jobs.iter().rev().filter(|j| j.status.is_finished()) {
+        if excess == 0 {
+            break;
+        }
+        kv.set_remove(job_ids_key(creator), job.id.clone()).await?;
+        kv.delete(job_key(creator, &job.id)).await?;
+        excess -= 1;
+    }
+    Ok(())
+}
+
+pub async fn submit_imports(urls: Vec<String>) -> Result<Vec<ImportJob>, ServerFnError> {
+    let creator = authorized_importer().await?;
+    let mut urls = urls
+        .iter()
+        .filter(|u| !u.trim().is_empty())
+        .map(|u| parse_import_url(u))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut seen = HashSet::new();
+    urls.retain(|u| seen.insert(u.clone()));
+    if urls.is_empty() {
+        return Err(ServerFnError::Args("Paste at least one link".into()));
+    }
+    if urls.len() > MAX_IMPORT_BATCH {
+        return Err(ServerFnError::Args(format!(
+            "At most {MAX_IMPORT_BATCH} links can be imported at once"
+        )));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    let now = now_ms();
+    // the last link of a batch is the newest, like submitting them one by one
+    for (idx, url) in urls.into_iter().enumerate() {
+        write_job(
+            &kv,
+            creator,
+            &ImportJob::new(format!("{now}-{idx:02}"), url, now),
+        )
+        .await?;
+    }
+    trim_jobs(&kv, creator).await?;
+    mark_active(&kv, creator).await?;
+
+    read_jobs(&kv, creator).await
+}
+
+pub async fn list_imports() -> Result<Vec<ImportJob>, ServerFnError> {
+    let creator = extract_principal_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+    let kv: KVStoreImpl = expect_context();
+    read_jobs(&kv, creator).await
+}
+
+pub async fn retry_import(id: String) -> Result<Vec<ImportJob>, ServerFnError> {
+    let creator = authorized_importer().await?;
+    let kv: KVStoreImpl = expect_context();
+    let mut job = read_job(&kv, creator, &id)
+        .await?
+        .ok_or_else(|| ServerFnError::new("import not found"))?;
+    if !matches!(job.status, ImportStatus::Failed { .. }) {
+        return Err(ServerFnError::new("only failed imports can be retried"));
+    }
+    job.set_status(ImportStatus::Queued, now_ms());
+
+    write_job(&kv, creator, &job).await?;
+    mark_active(&kv, creator).await?;
+
+    read_jobs(&kv, creator).await
+}
+
+/// Id of the creator's newest post, the import is done once a newer one shows up
+async fn latest_post_id(canisters: &Canisters<true>) -> Result<Option<u64>, ServerFnError> {
+    let user = canisters.authenticated_user().await;
+    let posts = user
+        .get_posts_of_this_user_profile_with_pagination_cursor(0, 1)
+        .await?;
+    Ok(match posts {
+        Result13::Ok(posts) => posts.first().map(|p| p.id),
+        Result13::Err(_) => None,
+    })
+}
+
+/// Moves the creator's current import one step forward
+/// imports run one at a time per creator, so a new post can only belong to the current one
+/// only the worker changes unfinished jobs, so the job is written back as is
+async fn advance_imports(
+    kv: &KVStoreImpl,
+    content_seed_client: &ContentSeedClient,
+    creator: Principal,
+) -> Result<bool, ServerFnError> {
+    // oldest first
+    let Some(mut job) = read_jobs(kv, creator)
+        .await?
+        .into_iter()
+        .rfind(|j| !j.status.is_finished())
+    else {
+        return Ok(false);
+    };
+    let now = now_ms();
+    let identity = match importer_identity(kv, creator).await {
+        Ok(identity) => identity,
+        Err(e) => {
+            job.set_status(ImportStatus::failed(e), now);
+            write_job(kv, creator, &job).await?;
+            return Ok(true);
+        }
+    };
+
+    match job.status {
+        ImportStatus::Queued => {
+            let canisters = Canisters::authenticate_with_network(identity.clone(), None).await;
+            let baseline = match canisters {
+                Ok(canisters) => latest_post_id(&canisters).await,
+                Err(e) => Err(ServerFnError::new(e)),
+            };
+            let baseline = match baseline {
+                Ok(baseline) => baseline,
+                Err(e) => {
+                    job.set_status(ImportStatus::failed(e), now);
+                    write_job(kv, creator, &job).await?;
+                    return Ok(true);
+                }
+            };
+            job.latest_post_id = baseline;
+            job.set_status(ImportStatus::Downloading, now);
+            write_job(kv, creator, &job).await?;
+
+            // the worker serves every creator, a stuck request can't hold it up
+            let res = tokio::time::timeout(
+                UPLOAD_REQUEST_TIMEOUT,
+                content_seed_client.upload_content(job.url.clone(), identity),
+            )
+            .await;
+            let status = match res {
+                Ok(Ok(())) => ImportStatus::Processing,
+                Ok(Err(e)) => ImportStatus::failed(e),
+                Err(_) => ImportStatus::failed("the download-upload service timed out"),
+            };
+            job.set_status(status, now_ms());
+            write_job(kv, creator, &job).await?;
+        }
+        // the worker never leaves a job downloading across ticks, unless it was restarted
+        ImportStatus::Downloading => {
+            job.set_status(ImportStatus::failed("import was interrupted"), now);
+            write_job(kv, creator, &job).await?;
+        }
+        ImportStatus::Processing => {
+            let canisters = Canisters::authenticate_with_network(identity, None).await?;
+            let latest = latest_post_id(&canisters).await?;
+            if latest > job.latest_post_id {
+                let post_id = latest.unwrap_or_default();
+                job.set_status(ImportStatus::Published { post_id }, now);
+            } else if now > job.updated_at_ms + PROCESSING_TIMEOUT_MS {
+                job.set_status(ImportStatus::failed("timed out waiting for the post"), now);
+            } else {
+                return Ok(true);
+            }
+            write_job(kv, creator, &job).await?;
+        }
+        ImportStatus::Published { .. } | ImportStatus::Failed { .. } => (),
+    }
+
+    Ok(true)
+}
+
+async fn run_imports(
+    kv: &KVStoreImpl,
+    content_seed_client: &ContentSeedClient,
+) -> Result<(), ServerFnError> {
+    let active = kv
+        .set_range(ACTIVE_IMPORTERS_KEY.into(), 0, usize::MAX)
+        .await?;
+    for creator in active {
+        let creator = match Principal::from_text(&creator) {
+            Ok(creator) => creator,
+            Err(e) => {
+                log::warn!("invalid content importer {creator}: {e}");
+                continue;
+            }
+        };
+        let res = match advance_imports(kv, content_seed_client, creator).await {
+            Ok(true) => Ok(()),
+            Ok(false) => mark_done(kv, creator).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            log::warn!("failed to advance imports of {creator}: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Works through queued content imports in the background
+/// jobs are kept in the KV store, so a restart picks up where it left off
+pub async fn run_import_worker(kv: KVStoreImpl) {
+    let content_seed_client = ContentSeedClient::default();
+    let mut interval = tokio::time::interval(WORKER_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = run_imports(&kv, &content_seed_client).await {
+            log::warn!("failed to run content imports: {e}");
+        }
+    }
+}
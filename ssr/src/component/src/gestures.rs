@@ -0,0 +1,189 @@
+use leptos::{ev, html, prelude::*};
+use leptos_use::{use_timeout_fn, UseTimeoutFnReturn};
+use state::app_state::GestureConfig;
+use web_time::Instant;
+
+/// Taps closer than this are considered a double tap
+const DOUBLE_TAP_MS: f64 = 300.0;
+const LONG_PRESS_MS: f64 = 500.0;
+/// Movement in px after which a press is no longer a tap
+const TAP_SLOP_PX: f64 = 12.0;
+const SWIPE_MIN_PX: f64 = 60.0;
+/// Fraction of the width on either side that seeks on tap
+const SIDE_ZONE: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap,
+    /// position relative to the gesture layer
+    DoubleTap {
+        x: f64,
+        y: f64,
+    },
+    SideTap(Side),
+    LongPressStart,
+    LongPressEnd,
+    SwipeLeft,
+    SwipeRight,
+}
+
+#[derive(Clone, Copy)]
+struct Press {
+    x: f64,
+    y: f64,
+    at: Instant,
+}
+
+/// Transparent layer that recognizes the gestures enabled in `config`
+/// vertical movement is left to the browser for scrolling
+#[component]
+pub fn GestureLayer(config: GestureConfig, on_gesture: Callback<Gesture>) -> impl IntoView {
+    let layer_ref = NodeRef::<html::Div>::new();
+    let press = StoredValue::new(None::<Press>);
+    let last_tap = StoredValue::new(None::<Press>);
+    let long_pressing = StoredValue::new(false);
+
+    let UseTimeoutFnReturn {
+        start: start_long_press,
+        stop: stop_long_press,
+        ..
+    } = use_timeout_fn(
+        move |()| {
+            long_pressing.set_value(true);
+            on_gesture.run(Gesture::LongPressStart);
+        },
+        LONG_PRESS_MS,
+    );
+
+    let UseTimeoutFnReturn {
+        start: start_single_tap,
+        stop: stop_single_tap,
+        ..
+    } = use_timeout_fn(
+        move |gesture: Gesture| on_gesture.run(gesture),
+        DOUBLE_TAP_MS,
+    );
+
+    let relative_pos = move |ev: &ev::PointerEvent| {
+        let Some(layer) = layer_ref.get_untracked() else {
+            return (ev.client_x() as f64, ev.client_y() as f64, 1.0);
+        };
+        let rect = layer.get_bounding_client_rect();
+        (
+            ev.client_x() as f64 - rect.left(),
+            ev.client_y() as f64 - rect.top(),
+            rect.width(),
+        )
+    };
+
+    let stop_press = stop_long_press.clone();
+    let on_pointerdown = move |ev: ev::PointerEvent| {
+        let (x, y, _) = relative_pos(&ev);
+        press.set_value(Some(Press {
+            x,
+            y,
+            at: Instant::now(),
+        }));
+        long_pressing.set_value(false);
+        if config.long_press_pause {
+            stop_press();
+            start_long_press(());
+        }
+    };
+
+    let stop_press = stop_long_press.clone();
+    let on_pointermove = move |ev: ev::PointerEvent| {
+        let Some(start) = press.get_value() else {
+            return;
+        };
+        let (x, y, _) = relative_pos(&ev);
+        if (x - start.x).hypot(y - start.y) > TAP_SLOP_PX {
+            stop_press();
+        }
+    };
+
+    let stop_press = stop_long_press.clone();
+    let on_pointercancel = move |_: ev::PointerEvent| {
+        stop_press();
+        press.set_value(None);
+        if long_pressing.get_value() {
+            long_pressing.set_value(false);
+            on_gesture.run(Gesture::LongPressEnd);
+        }
+    };
+
+    let on_pointerup = move |ev: ev::PointerEvent| {
+        stop_long_press();
+        let Some(start) = press.get_value() else {
+            return;
+        };
+        press.set_value(None);
+        if long_pressing.get_value() {
+            long_pressing.set_value(false);
+            on_gesture.run(Gesture::LongPressEnd);
+            return;
+        }
+
+        let (x, y, width) = relative_pos(&ev);
+        let (dx, dy) = (x - start.x, y - start.y);
+        if config.swipe_to_profile && dx.abs() >= SWIPE_MIN_PX && dx.abs() > 2.0 * dy.abs() {
+            on_gesture.run(if dx < 0.0 {
+                Gesture::SwipeLeft
+            } else {
+                Gesture::SwipeRight
+            });
+            return;
+        }
+        if dx.hypot(dy) > TAP_SLOP_PX {
+            return;
+        }
+
+        let tap = if config.side_tap_seek_secs > 0.0 && x < width * SIDE_ZONE {
+            Gesture::SideTap(Side::Left)
+        } else if config.side_tap_seek_secs > 0.0 && x > width * (1.0 - SIDE_ZONE) {
+            Gesture::SideTap(Side::Right)
+        } else {
+            Gesture::Tap
+        };
+        if !config.double_tap_like {
+            on_gesture.run(tap);
+            return;
+        }
+
+        let is_double_tap = last_tap.get_value().is_some_and(|prev| {
+            prev.at.elapsed().as_millis() as f64 <= DOUBLE_TAP_MS
+                && (x - prev.x).hypot(y - prev.y) <= 4.0 * TAP_SLOP_PX
+        });
+        if is_double_tap {
+            stop_single_tap();
+            last_tap.set_value(None);
+            on_gesture.run(Gesture::DoubleTap { x, y });
+        } else {
+            last_tap.set_value(Some(Press {
+                x,
+                y,
+                at: Instant::now(),
+            }));
+            start_single_tap(tap);
+        }
+    };
+
+    view! {
+        <div
+            node_ref=layer_ref
+            class="absolute inset-0 cursor-pointer select-none"
+            style:touch-action="pan-y"
+            on:pointerdown=on_pointerdown
+            on:pointermove=on_pointermove
+            on:pointerup=on_pointerup
+            on:pointercancel=on_pointercancel
+            on:contextmenu=|ev| ev.prevent_default()
+        ></div>
+    }
+}
@@ -7,9 +7,11 @@ pub mod buttons;
 pub mod canisters_prov;
 pub mod coming_soon;
 pub mod connect;
+pub mod consent_banner;
 pub mod content_upload;
 pub mod dashbox;
 pub mod feed_popup;
+pub mod gestures;
 pub mod hn_icons;
 pub mod ic_symbol;
 pub mod icons;
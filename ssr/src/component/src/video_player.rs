@@ -1,34 +1,55 @@
 use leptos::html::Video;
 use leptos::prelude::*;
 
-use state::audio_state::AudioState;
+use state::{app_state::AppState, audio_state::AudioState};
+
+use crate::gestures::{Gesture, GestureLayer};
 
 #[component]
 pub fn VideoPlayer(
     #[prop(optional)] node_ref: NodeRef<Video>,
     #[prop(into)] view_bg_url: Signal<Option<String>>,
     #[prop(into)] view_video_url: Signal<Option<String>>,
+    /// Replaces tap to mute with the gestures configured for the app
+    #[prop(optional)]
+    on_gesture: Option<Callback<Gesture>>,
 ) -> impl IntoView {
+    let video = view! {
+        <video
+            node_ref=node_ref
+            class="object-contain h-dvh max-h-dvh cursor-pointer"
+            poster=view_bg_url
+            src=view_video_url
+            loop
+            muted
+            playsinline
+            disablepictureinpicture
+            disableremoteplayback
+            preload="auto"
+        ></video>
+    };
+
+    let Some(on_gesture) = on_gesture else {
+        return view! {
+            <label class="h-full w-full absolute top-0 left-0 grid grid-cols-1 justify-items-center items-center cursor-pointer z-[3]">
+                <input
+                    on:change=move |_| AudioState::toggle_mute()
+                    type="checkbox"
+                    value=""
+                    class="sr-only"
+                />
+                {video}
+            </label>
+        }
+        .into_any();
+    };
+
+    let config = expect_context::<AppState>().gestures;
     view! {
-        <label class="h-full w-full absolute top-0 left-0 grid grid-cols-1 justify-items-center items-center cursor-pointer z-[3]">
-            <input
-                on:change=move |_| AudioState::toggle_mute()
-                type="checkbox"
-                value=""
-                class="sr-only"
-            />
-            <video
-                node_ref=node_ref
-                class="object-contain h-dvh max-h-dvh cursor-pointer"
-                poster=view_bg_url
-                src=view_video_url
-                loop
-                muted
-                playsinline
-                disablepictureinpicture
-                disableremoteplayback
-                preload="auto"
-            ></video>
-        </label>
+        <div class="h-full w-full absolute top-0 left-0 grid grid-cols-1 justify-items-center items-center z-[3]">
+            {video}
+            <GestureLayer config on_gesture />
+        </div>
     }
+    .into_any()
 }
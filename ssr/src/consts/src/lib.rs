@@ -12,21 +12,25 @@ pub use remote::*;
 use once_cell::sync::Lazy;
 use reqwest::Url;
 
-pub const CF_STREAM_BASE: &str = "https://customer-2p3jflss4r4hmpnz.cloudflarestream.com";
 pub const FALLBACK_PROPIC_BASE: &str = "https://api.dicebear.com/7.x/big-smile/svg";
 // an example URL is "https://imagedelivery.net/abXI9nS4DYYtyR1yFFtziA/gob.5/public";
 pub const GOBGOB_PROPIC_URL: &str = "https://imagedelivery.net/abXI9nS4DYYtyR1yFFtziA/gob.";
 pub const GOBGOB_TOTAL_COUNT: u32 = 18557;
 pub const CF_WATERMARK_UID: &str = "b5588fa1516ca33a08ebfef06c8edb33";
 pub const ACCOUNT_CONNECTED_STORE: &str = "account-connected-1";
-pub static CF_BASE_URL: Lazy<Url> =
-    Lazy::new(|| Url::parse("https://api.cloudflare.com/client/v4/").unwrap());
 pub const NOTIFICATIONS_ENABLED_STORE: &str = "yral-notifications-enabled";
 pub const NSFW_TOGGLE_STORE: &str = "nsfw-enabled";
 pub const REFERRER_STORE: &str = "referrer";
 pub const USER_CANISTER_ID_STORE: &str = "user-canister-id";
 pub const USER_PRINCIPAL_STORE: &str = "user-principal";
 pub const USER_ONBOARDING_STORE: &str = "user-onboarding";
+pub const FEED_STRATEGY_STORE: &str = "feed-strategy";
+pub const DATA_SAVER_STORE: &str = "data-saver-enabled";
+pub const MY_REPORTS_STORE: &str = "my-reports";
+pub const ANALYTICS_QUEUE_STORE: &str = "analytics-queue";
+pub const CONSENT_STORE: &str = "consent";
+pub const GA4_CLIENT_ID_STORE: &str = "ga4-client-id";
+pub const PENDING_UPLOAD_STORE: &str = "pending-upload";
 
 pub static OFF_CHAIN_AGENT_URL: Lazy<Url> =
     Lazy::new(|| Url::parse("https://icp-off-chain-agent.fly.dev").unwrap());
@@ -10,3 +10,8 @@ pub const YRAL_BACKEND_CONTAINER_TAG: &str = "04b53277579d9370c13312a2833ca0b855
 pub const YRAL_METADATA_CONTAINER_TAG: &str = "a4879e2e711c17beeb12ed6987ba315c110be9e5";
 pub static PUMP_AND_DUMP_WORKER_URL: Lazy<Url> =
     Lazy::new(|| Url::parse("http://localhost:8787/").unwrap());
+
+// served by the mock Stream API the local server mounts, see `mock_stream`
+pub const CF_STREAM_BASE: &str = "http://localhost:3000/mock-cf/stream";
+pub static CF_BASE_URL: Lazy<Url> =
+    Lazy::new(|| Url::parse("http://localhost:3000/mock-cf/client/v4/").unwrap());
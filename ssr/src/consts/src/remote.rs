@@ -8,3 +8,7 @@ pub const AGENT_URL: &str = "https://ic0.app";
 
 pub static PUMP_AND_DUMP_WORKER_URL: Lazy<Url> =
     Lazy::new(|| Url::parse("https://yral-pump-n-dump.go-bazzinga.workers.dev/").unwrap());
+
+pub const CF_STREAM_BASE: &str = "https://customer-2p3jflss4r4hmpnz.cloudflarestream.com";
+pub static CF_BASE_URL: Lazy<Url> =
+    Lazy::new(|| Url::parse("https://api.cloudflare.com/client/v4/").unwrap());
@@ -11,6 +11,8 @@ use auth::server_impl::store::KVStoreImpl;
 use axum_extra::extract::cookie::Key;
 use leptos::prelude::*;
 use leptos_axum::AxumRouteListing;
+use state::comments::CommentStoreImpl;
+use state::search::SearchIndexImpl;
 use state::server::AppState;
 use utils::token::{icpump::ICPumpSearchGrpcChannel, nsfw::ICPumpNSFWGrpcChannel};
 use yral_canisters_common::Canisters;
@@ -233,6 +235,36 @@ fn init_qstash_client() -> utils::qstash::QStashClient {
     QStashClient::new(&auth_token)
 }
 
+#[cfg_attr(feature = "local-bin", allow(unused_variables))]
+fn init_search_index(kv: &KVStoreImpl) -> SearchIndexImpl {
+    #[cfg(feature = "local-bin")]
+    {
+        use state::search::in_memory::InMemorySearchIndex;
+        SearchIndexImpl::InMemory(InMemorySearchIndex::default())
+    }
+
+    #[cfg(not(feature = "local-bin"))]
+    {
+        use state::search::kv_index::KVSearchIndex;
+        SearchIndexImpl::KV(KVSearchIndex(kv.clone()))
+    }
+}
+
+#[cfg_attr(feature = "local-bin", allow(unused_variables))]
+fn init_comment_store(kv: &KVStoreImpl) -> CommentStoreImpl {
+    #[cfg(feature = "local-bin")]
+    {
+        use state::comments::in_memory::InMemoryCommentStore;
+        CommentStoreImpl::InMemory(InMemoryCommentStore::default())
+    }
+
+    #[cfg(not(feature = "local-bin"))]
+    {
+        use state::comments::kv_store::KVCommentStore;
+        CommentStoreImpl::KV(KVCommentStore(kv.clone()))
+    }
+}
+
 pub struct AppStateRes {
     pub app_state: AppState,
     #[cfg(feature = "local-bin")]
@@ -282,6 +314,8 @@ impl AppStateBuilder {
 
     pub async fn build(mut self) -> AppStateRes {
         let kv = self.init_kv().await;
+        let search_index = init_search_index(&kv);
+        let comment_store = init_comment_store(&kv);
         #[cfg(feature = "local-bin")]
         {
             self.containers.start_backend().await;
@@ -297,6 +331,8 @@ impl AppStateBuilder {
             #[cfg(feature = "cloudflare")]
             cloudflare: init_cf(),
             kv,
+            search_index,
+            comment_store,
             cookie_key: init_cookie_key(),
             #[cfg(feature = "oauth-ssr")]
             google_oauth_clients: init_google_oauth(),
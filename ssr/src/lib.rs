@@ -7,6 +7,8 @@ pub mod error_template;
 pub mod fallback;
 #[cfg(feature = "ssr")]
 pub mod init;
+#[cfg(feature = "local-bin")]
+pub mod mock_stream;
 
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
@@ -39,6 +39,8 @@ pub async fn server_fn_handler(
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
             provide_context(app_state.kv.clone());
+            provide_context(app_state.search_index.clone());
+            provide_context(app_state.comment_store.clone());
             provide_context(app_state.cookie_key.clone());
             #[cfg(feature = "oauth-ssr")]
             provide_context(app_state.google_oauth_clients.clone());
@@ -72,6 +74,8 @@ pub async fn leptos_routes_handler(state: State<AppState>, req: Request<AxumBody
             #[cfg(feature = "cloudflare")]
             provide_context(app_state.cloudflare.clone());
             provide_context(app_state.kv.clone());
+            provide_context(app_state.search_index.clone());
+            provide_context(app_state.comment_store.clone());
             provide_context(app_state.cookie_key.clone());
             #[cfg(feature = "oauth-ssr")]
             provide_context(app_state.google_oauth_clients.clone());
@@ -109,6 +113,19 @@ async fn main_impl() {
     let res = AppStateBuilder::new(leptos_options, routes.clone())
         .build()
         .await;
+    tokio::spawn(page::upload::drafts::run_publish_scheduler(
+        res.app_state.kv.clone(),
+        res.app_state.search_index.clone(),
+    ));
+    // walks every known creator against the backend, local builds have nothing to backfill
+    #[cfg(not(feature = "local-bin"))]
+    tokio::spawn(page::search::run_search_backfill(
+        res.app_state.kv.clone(),
+        res.app_state.search_index.clone(),
+    ));
+    tokio::spawn(component::content_upload::run_import_worker(
+        res.app_state.kv.clone(),
+    ));
     let terminate = {
         use tokio::signal;
 
@@ -170,6 +187,10 @@ async fn main_impl() {
         .layer(sentry_tower_layer)
         .with_state(res.app_state);
 
+    // stands in for Cloudflare Stream, uploads and playback go through it
+    #[cfg(feature = "local-bin")]
+    let app = app.nest("/mock-cf", hot_or_not_web_leptos_ssr::mock_stream::router());
+
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
     log::info!("listening on http://{}", &addr);
@@ -0,0 +1,440 @@
+//! In-process stand-in for Cloudflare Stream, mounted at `/mock-cf` by local builds
+//! speaks enough of the API for the upload flow, the feed and the moderation checks
+//! videos are kept in memory, so they're gone after a restart
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, head, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use tower_http::cors::CorsLayer;
+use web_time::{Duration, Instant, SystemTime};
+
+const TUS_VERSION: &str = "1.0.0";
+/// How long a fully uploaded video stays `inprogress` before it's `ready`
+const PROCESSING_DELAY: Duration = Duration::from_secs(5);
+/// Used when the upload isn't an mp4 the duration can be read from
+const DEFAULT_DURATION_SECS: f64 = 10.0;
+/// Stands in for every thumbnail, the mock doesn't decode video
+const PLACEHOLDER_THUMBNAIL: &str = "/img/yral/android-chrome-384x384.png";
+
+struct MockVideo {
+    upload_length: u64,
+    data: Vec<u8>,
+    /// Anything set through the API, returned as is in the details
+    settings: serde_json::Map<String, Value>,
+    created_at: SystemTime,
+    uploaded_at: Option<Instant>,
+}
+
+impl MockVideo {
+    fn state(&self) -> &'static str {
+        match self.uploaded_at {
+            None => "pendingupload",
+            Some(at) if at.elapsed() < PROCESSING_DELAY => "inprogress",
+            Some(_) => "ready",
+        }
+    }
+
+    fn duration(&self) -> f64 {
+        if self.state() != "ready" {
+            return -1.0;
+        }
+        mp4_duration(&self.data).unwrap_or(DEFAULT_DURATION_SECS)
+    }
+
+    fn details(&self, uid: &str) -> Value {
+        let state = self.state();
+        let mut details = json!({
+            "uid": uid,
+            "readyToStream": state == "ready",
+            "status": {
+                "state": state,
+                "pctComplete": if state == "ready" { "100.000000" } else { "0.000000" },
+                "errorReasonCode": "",
+                "errorReasonText": "",
+            },
+            "meta": {},
+            "created": rfc3339(self.created_at),
+            "modified": rfc3339(self.created_at),
+            "size": self.upload_length,
+            "duration": self.duration(),
+            "requireSignedURLs": false,
+            "allowedOrigins": [],
+            "thumbnailTimestampPct": 0,
+            "thumbnail": format!("{}/{uid}/thumbnails/thumbnail.jpg", consts::CF_STREAM_BASE),
+            "playback": {
+                "hls": utils::stream_url(uid),
+            },
+        });
+        details
+            .as_object_mut()
+            .unwrap()
+            .extend(self.settings.clone());
+        details
+    }
+}
+
+#[derive(Clone, Default)]
+struct MockStream {
+    videos: Arc<RwLock<HashMap<String, MockVideo>>>,
+}
+
+/// The envelope every API response comes in
+fn api_result(result: Value) -> Json<Value> {
+    Json(json!({
+        "success": true,
+        "errors": [],
+        "messages": [],
+        "result": result,
+    }))
+}
+
+fn not_found() -> Response {
+    (StatusCode::NOT_FOUND, "no such video").into_response()
+}
+
+fn rfc3339(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    // civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60
+    )
+}
+
+/// Duration from the `mvhd` box of an mp4, in seconds
+fn mp4_duration(data: &[u8]) -> Option<f64> {
+    let pos = data.windows(4).position(|w| w == b"mvhd")?;
+    let body = data.get(pos + 4..)?;
+    let be_u32 =
+        |range: std::ops::Range<usize>| Some(u32::from_be_bytes(body.get(range)?.try_into().ok()?));
+    let (timescale, duration) = if *body.first()? == 1 {
+        let duration = u64::from_be_bytes(body.get(24..32)?.try_into().ok()?);
+        (be_u32(20..24)?, duration)
+    } else {
+        (be_u32(12..16)?, be_u32(16..20)? as u64)
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+fn tus_headers(video: &MockVideo) -> [(header::HeaderName, String); 3] {
+    [
+        (
+            header::HeaderName::from_static("tus-resumable"),
+            TUS_VERSION.into(),
+        ),
+        (
+            header::HeaderName::from_static("upload-offset"),
+            video.data.len().to_string(),
+        ),
+        (
+            header::HeaderName::from_static("upload-length"),
+            video.upload_length.to_string(),
+        ),
+    ]
+}
+
+/// Direct creator upload, the tus creation request
+async fn create_upload(State(stream): State<MockStream>, headers: HeaderMap) -> Response {
+    let Some(upload_length) = headers
+        .get("Upload-Length")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+    else {
+        return (StatusCode::BAD_REQUEST, "missing Upload-Length").into_response();
+    };
+    let uid = uuid::Uuid::new_v4().simple().to_string();
+    stream.videos.write().unwrap().insert(
+        uid.clone(),
+        MockVideo {
+            upload_length,
+            data: vec![],
+            settings: Default::default(),
+            created_at: SystemTime::now(),
+            uploaded_at: None,
+        },
+    );
+
+    // relative, so the browser uploads to whichever host it loaded the app from
+    let location = format!("/mock-cf/tus/{uid}");
+    (
+        StatusCode::CREATED,
+        [
+            (header::LOCATION, location),
+            (header::HeaderName::from_static("stream-media-id"), uid),
+            (
+                header::HeaderName::from_static("tus-resumable"),
+                TUS_VERSION.into(),
+            ),
+        ],
+    )
+        .into_response()
+}
+
+async fn upload_offset(State(stream): State<MockStream>, Path(uid): Path<String>) -> Response {
+    let videos = stream.videos.read().unwrap();
+    let Some(video) = videos.get(&uid) else {
+        return not_found();
+    };
+    (
+        StatusCode::OK,
+        [(header::CACHE_CONTROL, "no-store".to_string())],
+        tus_headers(video),
+    )
+        .into_response()
+}
+
+async fn upload_chunk(
+    State(stream): State<MockStream>,
+    Path(uid): Path<String>,
+    headers: HeaderMap,
+    chunk: Bytes,
+) -> Response {
+    let mut videos = stream.videos.write().unwrap();
+    let Some(video) = videos.get_mut(&uid) else {
+        return not_found();
+    };
+    let offset = headers
+        .get("Upload-Offset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if offset != Some(video.data.len()) {
+        return (StatusCode::CONFLICT, "Upload-Offset doesn't match").into_response();
+    }
+    if (video.data.len() + chunk.len()) as u64 > video.upload_length {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "more than Upload-Length").into_response();
+    }
+
+    video.data.extend_from_slice(&chunk);
+    if video.data.len() as u64 == video.upload_length {
+        video.uploaded_at = Some(Instant::now());
+    }
+    (StatusCode::NO_CONTENT, tus_headers(video)).into_response()
+}
+
+async fn video_details(
+    State(stream): State<MockStream>,
+    Path((_account, uid)): Path<(String, String)>,
+) -> Response {
+    let videos = stream.videos.read().unwrap();
+    let Some(video) = videos.get(&uid) else {
+        return not_found();
+    };
+    api_result(video.details(&uid)).into_response()
+}
+
+/// Sets meta, the thumbnail timestamp, signed URLs, etc.
+async fn update_video(
+    State(stream): State<MockStream>,
+    Path((_account, uid)): Path<(String, String)>,
+    Json(update): Json<serde_json::Map<String, Value>>,
+) -> Response {
+    let mut videos = stream.videos.write().unwrap();
+    let Some(video) = videos.get_mut(&uid) else {
+        return not_found();
+    };
+    video.settings.extend(update);
+    api_result(video.details(&uid)).into_response()
+}
+
+/// The mp4 download is the upload itself, so it's ready as soon as the video is
+async fn create_downloads(
+    State(stream): State<MockStream>,
+    Path((_account, uid)): Path<(String, String)>,
+) -> Response {
+    let videos = stream.videos.read().unwrap();
+    let Some(video) = videos.get(&uid) else {
+        return not_found();
+    };
+    let ready = video.state() == "ready";
+    api_result(json!({
+        "default": {
+            "status": if ready { "ready" } else { "inprogress" },
+            "url": utils::mp4_url(&uid),
+            "percentComplete": if ready { 100.0 } else { 0.0 },
+        }
+    }))
+    .into_response()
+}
+
+/// Parses a single `bytes=start-end` range, clamped to `len`
+fn parse_range(headers: &HeaderMap, len: usize) -> Option<(usize, usize)> {
+    let range = headers.get(header::RANGE)?.to_str().ok()?;
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+    let start: usize = start.parse().ok()?;
+    let end = match end {
+        "" => len.checked_sub(1)?,
+        end => end.parse::<usize>().ok()?.min(len.checked_sub(1)?),
+    };
+    (start <= end).then_some((start, end))
+}
+
+async fn download(
+    State(stream): State<MockStream>,
+    Path(uid): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    let videos = stream.videos.read().unwrap();
+    let Some(video) = videos.get(&uid).filter(|v| v.state() == "ready") else {
+        return not_found();
+    };
+    let len = video.data.len();
+    let content_type = (header::CONTENT_TYPE, HeaderValue::from_static("video/mp4"));
+    let accept_ranges = (header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    let Some((start, end)) = parse_range(&headers, len) else {
+        return (
+            StatusCode::OK,
+            [content_type, accept_ranges],
+            video.data.clone(),
+        )
+            .into_response();
+    };
+    let content_range = format!("bytes {start}-{end}/{len}");
+    (
+        StatusCode::PARTIAL_CONTENT,
+        [
+            content_type,
+            accept_ranges,
+            (
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&content_range).unwrap(),
+            ),
+        ],
+        video.data[start..=end].to_vec(),
+    )
+        .into_response()
+}
+
+fn m3u8(playlist: String) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/vnd.apple.mpegurl")],
+        playlist,
+    )
+        .into_response()
+}
+
+/// A single rendition, enough for the prefetcher to walk
+async fn master_manifest(State(stream): State<MockStream>, Path(uid): Path<String>) -> Response {
+    if !stream.videos.read().unwrap().contains_key(&uid) {
+        return not_found();
+    }
+    m3u8("#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1000000\nstream.m3u8\n".into())
+}
+
+/// The whole download as the only segment
+/// hls.js can't play it, the feed plays the mp4 download anyway
+async fn media_manifest(State(stream): State<MockStream>, Path(uid): Path<String>) -> Response {
+    let videos = stream.videos.read().unwrap();
+    let Some(video) = videos.get(&uid) else {
+        return not_found();
+    };
+    let duration = video.duration().max(0.0);
+    m3u8(format!(
+        "#EXTM3U\n#EXT-X-VERSION:7\n#EXT-X-TARGETDURATION:{}\n#EXT-X-PLAYLIST-TYPE:VOD\n#EXTINF:{duration:.3},\n../downloads/default.mp4\n#EXT-X-ENDLIST\n",
+        duration.ceil() as u64
+    ))
+}
+
+async fn thumbnail(State(stream): State<MockStream>, Path(uid): Path<String>) -> Response {
+    if !stream.videos.read().unwrap().contains_key(&uid) {
+        return not_found();
+    }
+    Redirect::temporary(PLACEHOLDER_THUMBNAIL).into_response()
+}
+
+/// Routes of the mock, the API lives under `client/v4` like the real one
+pub fn router<S>() -> Router<S> {
+    Router::new()
+        .route("/client/v4/accounts/:account/stream", post(create_upload))
+        .route(
+            "/client/v4/accounts/:account/stream/:uid",
+            get(video_details).post(update_video),
+        )
+        .route(
+            "/client/v4/accounts/:account/stream/:uid/downloads",
+            post(create_downloads),
+        )
+        .route("/tus/:uid", head(upload_offset).patch(upload_chunk))
+        .route("/stream/:uid/downloads/default.mp4", get(download))
+        .route("/stream/:uid/manifest/video.m3u8", get(master_manifest))
+        .route("/stream/:uid/manifest/stream.m3u8", get(media_manifest))
+        .route("/stream/:uid/thumbnails/thumbnail.jpg", get(thumbnail))
+        // tus chunks are several MiB
+        .layer(DefaultBodyLimit::disable())
+        // the app may be served from 127.0.0.1 while these URLs point at localhost
+        .layer(CorsLayer::permissive())
+        .with_state(MockStream::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc3339_formats_utc_timestamps() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_234_567_890, "2009-02-13T23:31:30Z"),
+            (1_709_251_199, "2024-02-29T23:59:59Z"),
+            (1_709_251_200, "2024-03-01T00:00:00Z"),
+        ];
+        for (secs, expected) in cases {
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            assert_eq!(rfc3339(time), expected, "{secs}");
+        }
+    }
+
+    /// An `mvhd` box of the given version, preceded by some other data
+    fn mvhd(version: u8, timescale: u32, duration: u64) -> Vec<u8> {
+        let mut data = b"\0\0\0\x20ftypisom".to_vec();
+        data.extend_from_slice(b"mvhd");
+        data.extend_from_slice(&[version, 0, 0, 0]);
+        if version == 1 {
+            data.extend_from_slice(&[0; 16]);
+            data.extend_from_slice(&timescale.to_be_bytes());
+            data.extend_from_slice(&duration.to_be_bytes());
+        } else {
+            data.extend_from_slice(&[0; 8]);
+            data.extend_from_slice(&timescale.to_be_bytes());
+            data.extend_from_slice(&(duration as u32).to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn mp4_duration_reads_either_mvhd_version() {
+        assert_eq!(mp4_duration(&mvhd(0, 1000, 12_500)), Some(12.5));
+        assert_eq!(mp4_duration(&mvhd(1, 600, 36_000)), Some(60.0));
+        // no timescale, no mvhd or a truncated one
+        assert_eq!(mp4_duration(&mvhd(0, 0, 100)), None);
+        assert_eq!(mp4_duration(b"not an mp4"), None);
+        let truncated = mvhd(1, 600, 36_000);
+        assert_eq!(mp4_duration(&truncated[..truncated.len() - 4]), None);
+    }
+}
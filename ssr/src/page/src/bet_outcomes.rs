@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use candid::Principal;
+use gloo::timers::callback::Timeout;
+use leptos::{prelude::*, task::spawn_local};
+use leptos_icons::*;
+use leptos_use::use_interval_fn;
+use state::canisters::unauth_canisters;
+use utils::{event_streaming::events::auth_canisters_store, types::PostId};
+use web_time::{Duration, SystemTime};
+use yral_canisters_common::{
+    cursored_data::{vote::VotesProvider, CursoredDataProvider},
+    utils::{
+        posts::PostDetails,
+        vote::{VoteDetails, VoteKind, VoteOutcome},
+    },
+    Canisters,
+};
+
+/// How often due bets are checked for an outcome
+const POLL_INTERVAL_MS: u64 = 5000;
+/// Delay before re-checking a bet whose slot closed but isn't settled yet
+const RECHECK_DELAY: Duration = Duration::from_secs(15);
+/// Number of the latest bets scanned for pending ones on start
+const PENDING_SCAN_LIMIT: usize = 10;
+const TOAST_DURATION_MS: u32 = 5000;
+
+#[derive(Clone)]
+struct PendingBet {
+    /// User canister that placed the bet, only polled while that user is logged in
+    voter: Principal,
+    post: PostDetails,
+    details: VoteDetails,
+    next_check: SystemTime,
+}
+
+impl PendingBet {
+    fn key(&self) -> (Principal, PostId) {
+        (self.voter, (self.details.canister_id, self.details.post_id))
+    }
+
+    fn is_due(&self, now: SystemTime) -> bool {
+        self.details.time_remaining(self.post.created_at) == Duration::ZERO
+            && now >= self.next_check
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BetToast {
+    id: u64,
+    won: bool,
+    text: String,
+}
+
+/// Tracks the user's pending bets and their outcomes once their slot closes
+#[derive(Clone, Copy)]
+pub struct BetOutcomesCtx {
+    pending: RwSignal<Vec<PendingBet>>,
+    /// Keyed by the canister of the voter and the post, the watched user can change on login
+    settled: RwSignal<HashMap<(Principal, PostId), VoteDetails>>,
+    toasts: RwSignal<Vec<BetToast>>,
+    /// Bumped whenever a bet settles, token balances depend on it
+    pub balance_version: RwSignal<u64>,
+}
+
+impl Default for BetOutcomesCtx {
+    fn default() -> Self {
+        Self {
+            pending: RwSignal::new(vec![]),
+            settled: RwSignal::new(HashMap::new()),
+            toasts: RwSignal::new(vec![]),
+            balance_version: RwSignal::new(0),
+        }
+    }
+}
+
+impl BetOutcomesCtx {
+    /// Watch a bet `voter` (a user canister) placed that is awaiting its result
+    pub fn watch(&self, voter: Principal, post: PostDetails, details: VoteDetails) {
+        if !matches!(details.outcome, VoteOutcome::AwaitingResult) {
+            return;
+        }
+        let bet = PendingBet {
+            voter,
+            post,
+            details,
+            next_check: SystemTime::UNIX_EPOCH,
+        };
+        if self
+            .settled
+            .with_untracked(|settled| settled.contains_key(&bet.key()))
+        {
+            return;
+        }
+        self.pending.update(|pending| {
+            if pending.iter().all(|p| p.key() != bet.key()) {
+                pending.push(bet);
+            }
+        });
+    }
+
+    /// Outcome of the bet `voter` (a user canister) placed on `post`,
+    /// if it settled since it was watched
+    pub fn settled_outcome(&self, voter: Principal, post: PostId) -> Option<VoteDetails> {
+        self.settled
+            .with(|settled| settled.get(&(voter, post)).cloned())
+    }
+
+    fn settle(&self, voter: Principal, details: VoteDetails) {
+        let key = (voter, (details.canister_id, details.post_id));
+        self.pending
+            .update(|pending| pending.retain(|p| p.key() != key));
+
+        let direction = if matches!(details.vote_kind, VoteKind::Hot) {
+            "Hot"
+        } else {
+            "Not"
+        };
+        let (won, text) = match details.outcome {
+            VoteOutcome::Won(amt) => (true, format!("You won {amt} tokens on {direction}!")),
+            VoteOutcome::Draw(amt) => (
+                false,
+                format!("Your {direction} bet was a draw, {amt} tokens returned"),
+            ),
+            VoteOutcome::Lost => (
+                false,
+                format!("You lost {} tokens on {direction}", details.vote_amount),
+            ),
+            VoteOutcome::AwaitingResult => return,
+        };
+        self.settled.update(|settled| {
+            settled.insert(key, details);
+        });
+        self.balance_version.update(|v| *v += 1);
+        self.push_toast(won, text);
+    }
+
+    fn push_toast(&self, won: bool, text: String) {
+        let toasts = self.toasts;
+        let id = toasts.with_untracked(|t| t.last().map(|t| t.id + 1).unwrap_or_default());
+        toasts.update(|t| t.push(BetToast { id, won, text }));
+        Timeout::new(TOAST_DURATION_MS, move || {
+            toasts.try_update(|t| t.retain(|toast| toast.id != id));
+        })
+        .forget();
+    }
+
+    fn postpone(&self, key: (Principal, PostId)) {
+        let next_check = SystemTime::now() + RECHECK_DELAY;
+        self.pending.update(|pending| {
+            if let Some(bet) = pending.iter_mut().find(|p| p.key() == key) {
+                bet.next_check = next_check;
+            }
+        });
+    }
+}
+
+async fn fetch_outcome(
+    canisters: &Canisters<true>,
+    post: PostId,
+) -> Result<Option<VoteDetails>, ServerFnError> {
+    let user = canisters.authenticated_user().await;
+    let bet = user
+        .get_individual_hot_or_not_bet_placed_by_this_profile(post.0, post.1)
+        .await?;
+    Ok(bet.map(VoteDetails::from))
+}
+
+/// Pending bets among the latest bets of the user
+async fn scan_pending_bets(
+    canisters: &Canisters<true>,
+) -> Result<Vec<(PostDetails, VoteDetails)>, ServerFnError> {
+    let provider = VotesProvider::new(unauth_canisters(), canisters.user_canister());
+    let page = provider
+        .get_by_cursor(0, PENDING_SCAN_LIMIT)
+        .await
+        .map_err(ServerFnError::new)?;
+
+    let mut pending = vec![];
+    for details in page.data {
+        if !matches!(details.outcome, VoteOutcome::AwaitingResult) {
+            continue;
+        }
+        if let Some(post) = canisters
+            .get_post_details(details.canister_id, details.post_id)
+            .await?
+        {
+            pending.push((post, details));
+        }
+    }
+    Ok(pending)
+}
+
+/// Polls the outcome of the user's pending bets once their slot closes
+/// and shows a toast for every settled bet
+#[component]
+pub fn BetOutcomeWatcher() -> impl IntoView {
+    let ctx: BetOutcomesCtx = expect_context();
+    let canisters = auth_canisters_store();
+    let voter = Memo::new(move |_| canisters.with(|c| c.as_ref().map(|c| c.user_canister())));
+
+    // rescanned whenever the user logs in or out
+    Effect::new(move |_| {
+        let voter = voter.get();
+        // bets of the previous user can't be polled with this user's canister
+        ctx.pending
+            .update(|pending| pending.retain(|p| Some(p.voter) == voter));
+        let Some(cans) = canisters.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            match scan_pending_bets(&cans).await {
+                Ok(pending) => {
+                    for (post, details) in pending {
+                        ctx.watch(cans.user_canister(), post, details);
+                    }
+                }
+                Err(e) => log::warn!("failed to scan pending bets: {e}"),
+            }
+        });
+    });
+
+    _ = use_interval_fn(
+        move || {
+            let Some(cans) = canisters.get_untracked() else {
+                return;
+            };
+            let now = SystemTime::now();
+            let voter = cans.user_canister();
+            let due = ctx.pending.with_untracked(|pending| {
+                pending
+                    .iter()
+                    .filter(|p| p.voter == voter && p.is_due(now))
+                    .map(PendingBet::key)
+                    .collect::<Vec<_>>()
+            });
+            for key in due {
+                // avoid checking again while this check is in flight
+                ctx.postpone(key);
+                let cans = cans.clone();
+                spawn_local(async move {
+                    match fetch_outcome(&cans, key.1).await {
+                        Ok(Some(details))
+                            if !matches!(details.outcome, VoteOutcome::AwaitingResult) =>
+                        {
+                            ctx.settle(cans.user_canister(), details)
+                        }
+                        Ok(_) => (),
+                        Err(e) => log::warn!("failed to fetch bet outcome: {e}"),
+                    }
+                });
+            }
+        },
+        POLL_INTERVAL_MS,
+    );
+
+    view! {
+        <div class="fixed top-4 left-1/2 -translate-x-1/2 z-[60] flex flex-col gap-2 w-11/12 max-w-md pointer-events-none">
+            <For
+                each=move || ctx.toasts.get()
+                key=|toast| toast.id
+                children=|toast| {
+                    view! {
+                        <div
+                            class="flex flex-row gap-2 items-center px-4 py-3 rounded-lg shadow-lg text-sm font-semibold"
+                            class=(["bg-primary-600", "text-white"], toast.won)
+                            class=(["bg-white", "text-black"], !toast.won)
+                        >
+                            <Icon icon=icondata::RiTrophyFinanceFill />
+                            <span>{toast.text}</span>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
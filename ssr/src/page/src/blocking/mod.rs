@@ -0,0 +1,222 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use std::collections::HashSet;
+
+use candid::Principal;
+use codee::string::FromToStringCodec;
+use component::modal::Modal;
+use consts::USER_PRINCIPAL_STORE;
+use leptos::prelude::*;
+use leptos_icons::*;
+use leptos_use::use_cookie;
+use serde::{Deserialize, Serialize};
+
+use crate::post_view::PostViewCtx;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HideKind {
+    /// Hides the creator's posts and tokens
+    Block,
+    /// Only hides the creator's posts from feeds
+    Mute,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HiddenCreators {
+    pub blocked: Vec<Principal>,
+    pub muted: Vec<Principal>,
+}
+
+impl HiddenCreators {
+    pub fn is_blocked(&self, creator: Principal) -> bool {
+        self.blocked.contains(&creator)
+    }
+
+    pub fn is_muted(&self, creator: Principal) -> bool {
+        self.muted.contains(&creator)
+    }
+
+    /// Creators whose posts are kept out of feeds
+    pub fn post_filter(&self) -> HashSet<Principal> {
+        self.blocked.iter().chain(&self.muted).copied().collect()
+    }
+
+    fn set(&mut self, creator: Principal, kind: HideKind, hide: bool) {
+        let list = match kind {
+            HideKind::Block => &mut self.blocked,
+            HideKind::Mute => &mut self.muted,
+        };
+        list.retain(|p| *p != creator);
+        if hide {
+            list.push(creator);
+        }
+    }
+}
+
+/// Creators blocked or muted by the logged in user
+#[server]
+pub async fn get_hidden_creators() -> Result<HiddenCreators, ServerFnError> {
+    server_impl::get_hidden_creators().await
+}
+
+#[server]
+pub async fn set_creator_hidden(
+    creator: Principal,
+    kind: HideKind,
+    hide: bool,
+) -> Result<HiddenCreators, ServerFnError> {
+    server_impl::set_creator_hidden(creator, kind, hide).await
+}
+
+/// Session cache of [HiddenCreators], loaded on first use
+#[derive(Clone, Copy, Default)]
+pub struct HiddenCreatorsCtx(RwSignal<Option<HiddenCreators>>);
+
+impl HiddenCreatorsCtx {
+    /// Cache that is dropped whenever the logged in user changes,
+    /// so one account's list never filters another's feed
+    pub fn for_logged_in_user() -> Self {
+        let ctx = Self::default();
+        let (user, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+        Effect::new(move |prev: Option<Option<Principal>>| {
+            let user = user.get();
+            if prev.is_some_and(|prev| prev != user) {
+                ctx.0.set(None);
+            }
+            user
+        });
+        ctx
+    }
+
+    pub async fn load(self) -> HiddenCreators {
+        if let Some(hidden) = self.0.get_untracked() {
+            return hidden;
+        }
+        match get_hidden_creators().await {
+            Ok(hidden) => {
+                self.0.set(Some(hidden.clone()));
+                hidden
+            }
+            Err(e) => {
+                log::warn!("failed to fetch hidden creators: {e}");
+                HiddenCreators::default()
+            }
+        }
+    }
+
+    pub fn with<T>(&self, f: impl FnOnce(&HiddenCreators) -> T) -> Option<T> {
+        self.0.with(|hidden| hidden.as_ref().map(f))
+    }
+
+    async fn set(
+        self,
+        creator: Principal,
+        kind: HideKind,
+        hide: bool,
+    ) -> Result<(), ServerFnError> {
+        let prev = self.0.get_untracked();
+        self.0.update(|h| {
+            h.get_or_insert_with(Default::default)
+                .set(creator, kind, hide)
+        });
+        match set_creator_hidden(creator, kind, hide).await {
+            Ok(hidden) => {
+                self.0.set(Some(hidden));
+                Ok(())
+            }
+            Err(e) => {
+                self.0.set(prev);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Block and mute actions for `creator`
+#[component]
+pub fn CreatorActionsModal(creator: Principal, show: RwSignal<bool>) -> impl IntoView {
+    let ctx: HiddenCreatorsCtx = expect_context();
+    let post_view_ctx = use_context::<PostViewCtx>();
+    Effect::new(move |_| {
+        if show() {
+            leptos::task::spawn_local(async move {
+                ctx.load().await;
+            });
+        }
+    });
+
+    let error = RwSignal::new(None::<String>);
+    let toggle = Action::new(move |&(kind, hide): &(HideKind, bool)| async move {
+        match ctx.set(creator, kind, hide).await {
+            Ok(()) => {
+                error.set(None);
+                if hide {
+                    if let Some(post_view_ctx) = post_view_ctx {
+                        post_view_ctx.hide_creator(creator);
+                    }
+                }
+            }
+            Err(e) => error.set(Some(e.to_string())),
+        }
+    });
+
+    let blocked = move || ctx.with(|h| h.is_blocked(creator)).unwrap_or_default();
+    let muted = move || ctx.with(|h| h.is_muted(creator)).unwrap_or_default();
+
+    view! {
+        <Modal show>
+            <div class="flex flex-col gap-4 w-full text-white">
+                <button
+                    class="flex flex-row gap-4 items-center w-full"
+                    disabled=move || toggle.pending().get()
+                    on:click=move |_| {
+                        toggle.dispatch((HideKind::Mute, !muted()));
+                    }
+                >
+                    <Icon attr:class="text-2xl" icon=icondata::BiVolumeMuteRegular />
+                    <div class="flex flex-col items-start">
+                        <span>{move || if muted() { "Unmute creator" } else { "Mute creator" }}</span>
+                        <span class="text-xs text-white/60">"Hide their posts from your feed"</span>
+                    </div>
+                </button>
+                <button
+                    class="flex flex-row gap-4 items-center w-full text-red-500"
+                    disabled=move || toggle.pending().get()
+                    on:click=move |_| {
+                        toggle.dispatch((HideKind::Block, !blocked()));
+                    }
+                >
+                    <Icon attr:class="text-2xl" icon=icondata::BiBlockRegular />
+                    <div class="flex flex-col items-start">
+                        <span>{move || if blocked() { "Unblock creator" } else { "Block creator" }}</span>
+                        <span class="text-xs text-white/60">"Hide their posts and tokens"</span>
+                    </div>
+                </button>
+                {move || error().map(|e| view! { <span class="text-sm text-red-500">{e}</span> })}
+            </div>
+        </Modal>
+    }
+}
+
+/// Opens [CreatorActionsModal], hidden on the viewer's own content
+#[component]
+pub fn CreatorActionsButton(creator: Principal, #[prop(into)] class: String) -> impl IntoView {
+    let (viewer_principal, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+    let show_actions = RwSignal::new(false);
+
+    view! {
+        <Show when=move || viewer_principal.get().is_some_and(|v| v != creator)>
+            <button
+                class=class.clone()
+                on:click=move |ev| {
+                    ev.prevent_default();
+                    show_actions.set(true);
+                }
+            >
+                <Icon attr:class="drop-shadow-lg" icon=icondata::BsThreeDotsVertical />
+            </button>
+        </Show>
+        <CreatorActionsModal creator show=show_actions />
+    }
+}
@@ -0,0 +1,76 @@
+use auth::server_impl::{extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use super::{HiddenCreators, HideKind};
+
+/// Hidden creators listed for a user, most recently hidden first
+const MAX_LISTED_CREATORS: usize = 1000;
+
+/// Creators `principal` hid with `kind`, scored by when they were hidden
+fn hidden_creators_key(principal: Principal, kind: HideKind) -> String {
+    let kind = match kind {
+        HideKind::Block => "blocked",
+        HideKind::Mute => "muted",
+    };
+    format!("{kind}-creators:{}", principal.to_text())
+}
+
+async fn hidden_of_kind(
+    kv: &KVStoreImpl,
+    principal: Principal,
+    kind: HideKind,
+) -> Result<Vec<Principal>, ServerFnError> {
+    kv.set_range(hidden_creators_key(principal, kind), 0, MAX_LISTED_CREATORS)
+        .await?
+        .into_iter()
+        .map(|p| Principal::from_text(p).map_err(ServerFnError::new))
+        .collect()
+}
+
+async fn hidden_creators_of(
+    kv: &KVStoreImpl,
+    principal: Principal,
+) -> Result<HiddenCreators, ServerFnError> {
+    Ok(HiddenCreators {
+        blocked: hidden_of_kind(kv, principal, HideKind::Block).await?,
+        muted: hidden_of_kind(kv, principal, HideKind::Mute).await?,
+    })
+}
+
+pub async fn get_hidden_creators() -> Result<HiddenCreators, ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Ok(HiddenCreators::default());
+    };
+    let kv: KVStoreImpl = expect_context();
+    hidden_creators_of(&kv, caller).await
+}
+
+/// Each kind is a set, so concurrent changes from several devices can't drop each other
+pub async fn set_creator_hidden(
+    creator: Principal,
+    kind: HideKind,
+    hide: bool,
+) -> Result<HiddenCreators, ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Err(ServerFnError::new("not logged in"));
+    };
+    if caller == creator {
+        return Err(ServerFnError::new("can't block yourself"));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    let key = hidden_creators_key(caller, kind);
+    if hide {
+        let hidden_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as f64;
+        kv.set_add(key, creator.to_text(), hidden_at).await?;
+    } else {
+        kv.set_remove(key, creator.to_text()).await?;
+    }
+
+    hidden_creators_of(&kv, caller).await
+}
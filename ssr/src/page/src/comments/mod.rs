@@ -0,0 +1,588 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use std::collections::HashSet;
+
+use candid::Principal;
+use codee::string::FromToStringCodec;
+use component::{bullet_loader::BulletLoader, infinite_scroller::InfiniteScroller, modal::Modal};
+use consts::USER_PRINCIPAL_STORE;
+use leptos::{html, prelude::*};
+use leptos_icons::*;
+use leptos_use::use_cookie;
+use serde::{Deserialize, Serialize};
+use utils::{report::ReportOption, time::get_day_month, types::PostId};
+use yral_canisters_common::{
+    cursored_data::{CursoredDataProvider, KeyedData, PageEntry},
+    utils::{posts::PostDetails, profile::propic_from_principal},
+};
+
+use crate::reports::{remember_report, submit_report, use_my_reports, ReportRequest};
+
+const COMMENTS_CHUNK_SZ: usize = 15;
+const REPLIES_CHUNK_SZ: usize = 5;
+/// Replies nested deeper than this are rendered at the same indentation
+const MAX_INDENT_DEPTH: usize = 2;
+pub const MAX_COMMENT_LEN: usize = 500;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CommentDetails {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub author: Principal,
+    pub text: String,
+    /// Seconds since UNIX epoch
+    pub created_at: u64,
+    pub likes: u64,
+    pub liked_by_caller: bool,
+    pub reply_count: u64,
+}
+
+impl KeyedData for CommentDetails {
+    type Key = String;
+
+    fn key(&self) -> Self::Key {
+        self.id.clone()
+    }
+}
+
+/// Replies to `parent`, or the top level comments of the post if `parent` is `None`
+/// newest first, returns the comments and whether the end of the list was reached
+#[server]
+pub async fn get_comments(
+    canister_id: Principal,
+    post_id: u64,
+    parent: Option<String>,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<CommentDetails>, bool), ServerFnError> {
+    server_impl::get_comments((canister_id, post_id), parent, start, end).await
+}
+
+#[server]
+pub async fn add_comment(
+    canister_id: Principal,
+    post_id: u64,
+    parent: Option<String>,
+    text: String,
+) -> Result<CommentDetails, ServerFnError> {
+    server_impl::add_comment((canister_id, post_id), parent, text).await
+}
+
+/// Returns whether the caller now likes the comment and the updated like count
+#[server]
+pub async fn toggle_comment_like(
+    canister_id: Principal,
+    post_id: u64,
+    comment_id: String,
+) -> Result<(bool, u64), ServerFnError> {
+    server_impl::toggle_comment_like((canister_id, post_id), comment_id).await
+}
+
+/// Comments can be deleted by their author or by the creator of the post
+#[server]
+pub async fn delete_comment(
+    canister_id: Principal,
+    post_id: u64,
+    comment_id: String,
+) -> Result<(), ServerFnError> {
+    server_impl::delete_comment((canister_id, post_id), comment_id).await
+}
+
+#[derive(Clone)]
+pub struct CommentsProvider {
+    post: PostId,
+    parent: Option<String>,
+}
+
+impl CursoredDataProvider for CommentsProvider {
+    type Data = CommentDetails;
+    type Error = ServerFnError;
+
+    async fn get_by_cursor_inner(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<PageEntry<CommentDetails>, ServerFnError> {
+        let (data, list_end) =
+            get_comments(self.post.0, self.post.1, self.parent.clone(), start, end).await?;
+        Ok(PageEntry {
+            data,
+            end: list_end,
+        })
+    }
+}
+
+/// State shared by all the comments in a sheet
+#[derive(Clone)]
+struct CommentSheetCtx {
+    post: PostDetails,
+    viewer: Signal<Option<Principal>>,
+    replying_to: RwSignal<Option<CommentDetails>>,
+    reporting: RwSignal<Option<CommentDetails>>,
+    /// Comments posted while the sheet is open, not yet part of any fetched page
+    posted: RwSignal<Vec<CommentDetails>>,
+    deleted: RwSignal<HashSet<String>>,
+}
+
+impl CommentSheetCtx {
+    fn post_id(&self) -> PostId {
+        (self.post.canister_id, self.post.post_id)
+    }
+
+    fn can_delete(&self, comment: &CommentDetails) -> bool {
+        let viewer = self.viewer.get_untracked();
+        viewer.is_some()
+            && (viewer == Some(comment.author) || viewer == Some(self.post.poster_principal))
+    }
+
+    fn posted_under(&self, parent: Option<&str>) -> Vec<CommentDetails> {
+        self.posted.with(|posted| {
+            posted
+                .iter()
+                .filter(|c| c.parent_id.as_deref() == parent)
+                .cloned()
+                .collect()
+        })
+    }
+}
+
+fn short_principal(principal: Principal) -> String {
+    let text = principal.to_text();
+    text.split('-').next().unwrap_or(&text).to_string()
+}
+
+#[component]
+fn CommentItem(
+    comment: CommentDetails,
+    #[prop(optional)] _ref: NodeRef<html::Div>,
+    depth: usize,
+) -> impl IntoView {
+    let ctx: CommentSheetCtx = expect_context();
+    let post_id = ctx.post_id();
+    let comment_id = comment.id.clone();
+    let can_delete = ctx.can_delete(&comment);
+
+    let liked = RwSignal::new(comment.liked_by_caller);
+    let likes = RwSignal::new(comment.likes);
+    let toggle_like = Action::new({
+        let comment_id = comment_id.clone();
+        move |&()| {
+            let comment_id = comment_id.clone();
+            let was_liked = liked.get_untracked();
+            let prev_likes = likes.get_untracked();
+            liked.set(!was_liked);
+            likes.set(if was_liked {
+                prev_likes.saturating_sub(1)
+            } else {
+                prev_likes + 1
+            });
+            async move {
+                match toggle_comment_like(post_id.0, post_id.1, comment_id).await {
+                    Ok((now_liked, now_likes)) => {
+                        liked.set(now_liked);
+                        likes.set(now_likes);
+                    }
+                    Err(e) => {
+                        log::warn!("failed to toggle comment like: {e}");
+                        liked.set(was_liked);
+                        likes.set(prev_likes);
+                    }
+                }
+            }
+        }
+    });
+
+    let deleted = ctx.deleted;
+    let delete = Action::new({
+        let comment_id = comment_id.clone();
+        move |&()| {
+            let comment_id = comment_id.clone();
+            async move {
+                match delete_comment(post_id.0, post_id.1, comment_id.clone()).await {
+                    Ok(()) => deleted.update(|d| {
+                        d.insert(comment_id);
+                    }),
+                    Err(e) => log::warn!("failed to delete comment: {e}"),
+                }
+            }
+        }
+    });
+
+    let posted_replies = {
+        let ctx = ctx.clone();
+        let comment_id = comment_id.clone();
+        move || ctx.posted_under(Some(&comment_id))
+    };
+    let show_replies = RwSignal::new(false);
+    let reply_count = comment.reply_count;
+    let indent = depth < MAX_INDENT_DEPTH;
+
+    let replying_to = ctx.replying_to;
+    let reporting = ctx.reporting;
+    let reply_target = comment.clone();
+    let report_target = comment.clone();
+    let is_deleted = {
+        let comment_id = comment_id.clone();
+        move || deleted.with(|d| d.contains(&comment_id))
+    };
+
+    view! {
+        <div node_ref=_ref class="flex flex-col w-full gap-2" class:hidden=is_deleted>
+            <div class="flex flex-row gap-3 w-full">
+                <img
+                    class="w-8 h-8 rounded-full object-cover shrink-0"
+                    src=propic_from_principal(comment.author)
+                />
+                <div class="flex flex-col gap-1 min-w-0 grow">
+                    <div class="flex flex-row gap-2 items-center text-xs text-white/60">
+                        <span class="font-semibold text-white/80">
+                            {short_principal(comment.author)}
+                        </span>
+                        <span>{get_day_month(comment.created_at)}</span>
+                    </div>
+                    <span class="text-sm text-white break-words">{comment.text}</span>
+                    <div class="flex flex-row gap-4 items-center text-xs text-white/60">
+                        <button on:click=move |_| replying_to.set(Some(reply_target.clone()))>
+                            Reply
+                        </button>
+                        <Show when=move || can_delete>
+                            <button
+                                disabled=move || delete.pending().get()
+                                on:click=move |_| {
+                                    delete.dispatch(());
+                                }
+                            >
+                                Delete
+                            </button>
+                        </Show>
+                        <button on:click=move |_| reporting.set(Some(report_target.clone()))>
+                            Report
+                        </button>
+                    </div>
+                </div>
+                <button
+                    class="flex flex-col items-center text-xs text-white/60 shrink-0"
+                    on:click=move |_| {
+                        toggle_like.dispatch(());
+                    }
+                >
+                    <img
+                        class="w-4 h-4"
+                        src=move || {
+                            if liked() {
+                                "/img/heart-icon-liked.svg"
+                            } else {
+                                "/img/heart-icon-white.svg"
+                            }
+                        }
+                    />
+                    <span>{likes}</span>
+                </button>
+            </div>
+            <div class="flex flex-col gap-3" class:pl-11=indent>
+                <For
+                    each=posted_replies
+                    key=|reply| reply.id.clone()
+                    children=move |reply| {
+                        view! { <CommentItem comment=reply depth=depth + 1 /> }
+                    }
+                />
+                <Show
+                    when=show_replies
+                    fallback=move || {
+                        view! {
+                            <Show when=move || reply_count != 0>
+                                <button
+                                    class="text-xs text-white/60 text-left"
+                                    on:click=move |_| show_replies.set(true)
+                                >
+                                    {format!("View {reply_count} replies")}
+                                </button>
+                            </Show>
+                        }
+                    }
+                >
+                    <CommentList
+                        parent=Some(comment_id.clone())
+                        fetch_count=REPLIES_CHUNK_SZ
+                        depth=depth + 1
+                    />
+                </Show>
+            </div>
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+fn CommentList(parent: Option<String>, fetch_count: usize, depth: usize) -> impl IntoView {
+    let ctx: CommentSheetCtx = expect_context();
+    let provider = CommentsProvider {
+        post: ctx.post_id(),
+        parent,
+    };
+
+    view! {
+        <InfiniteScroller
+            provider
+            fetch_count
+            children=move |comment, _ref| {
+                view! { <CommentItem comment _ref=_ref.unwrap_or_default() depth /> }
+            }
+            empty_content=move || {
+                view! {
+                    <Show when=move || depth == 0>
+                        <span class="py-9 text-sm text-white/60 text-center">
+                            "No comments yet, start the conversation"
+                        </span>
+                    </Show>
+                }
+            }
+            custom_loader=move || {
+                view! {
+                    <div class="w-full flex justify-center items-center py-4">
+                        <BulletLoader />
+                    </div>
+                }
+            }
+        />
+    }
+    .into_any()
+}
+
+#[component]
+fn CommentInput() -> impl IntoView {
+    let ctx: CommentSheetCtx = expect_context();
+    let post_id = ctx.post_id();
+    let text = RwSignal::new(String::new());
+    let replying_to = ctx.replying_to;
+    let posted = ctx.posted;
+    let error = RwSignal::new(None::<String>);
+
+    let submit = Action::new(move |&()| {
+        let parent = replying_to.get_untracked().map(|c| c.id);
+        let body = text.get_untracked();
+        async move {
+            match add_comment(post_id.0, post_id.1, parent, body).await {
+                Ok(comment) => {
+                    text.set(String::new());
+                    replying_to.set(None);
+                    error.set(None);
+                    posted.update(|p| p.insert(0, comment));
+                }
+                Err(e) => {
+                    log::warn!("failed to add comment: {e}");
+                    error.set(Some("Couldn't post your comment, please try again".into()));
+                }
+            }
+        }
+    });
+    let can_submit = move || {
+        let len = text.with(|t| t.trim().chars().count());
+        len != 0 && len <= MAX_COMMENT_LEN && !submit.pending().get()
+    };
+
+    view! {
+        <div class="flex flex-col gap-1 w-full pt-2 border-t border-white/10">
+            {move || {
+                replying_to
+                    .get()
+                    .map(|c| {
+                        view! {
+                            <div class="flex flex-row justify-between text-xs text-white/60">
+                                <span>{format!("Replying to {}", short_principal(c.author))}</span>
+                                <button on:click=move |_| replying_to.set(None)>
+                                    <Icon icon=icondata::ChCross />
+                                </button>
+                            </div>
+                        }
+                    })
+            }}
+            {move || error.get().map(|e| view! { <span class="text-xs text-red-500">{e}</span> })}
+            <form
+                class="flex flex-row items-center gap-2 px-4 py-2 rounded-full bg-white/10"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    if can_submit() {
+                        submit.dispatch(());
+                    }
+                }
+            >
+                <input
+                    class="w-full bg-transparent text-white text-sm outline-none placeholder-white/40"
+                    type="text"
+                    maxlength=MAX_COMMENT_LEN
+                    placeholder="Add a comment"
+                    prop:value=text
+                    on:input=move |ev| text.set(event_target_value(&ev))
+                />
+                <button type="submit" class="text-primary-600 text-sm font-semibold" disabled=move || !can_submit()>
+                    Post
+                </button>
+            </form>
+        </div>
+    }
+}
+
+#[component]
+fn CommentReportModal() -> impl IntoView {
+    let ctx: CommentSheetCtx = expect_context();
+    let show = RwSignal::new(false);
+    let reporting = ctx.reporting;
+    Effect::new(move |_| show.set(reporting.with(|r| r.is_some())));
+    Effect::new(move |_| {
+        if !show() {
+            reporting.set(None);
+        }
+    });
+
+    let (my_reports, set_my_reports) = use_my_reports();
+    let post_key = ctx.post_id();
+    let existing = move || {
+        let comment_id = reporting.with(|c| c.as_ref().map(|c| c.id.clone()))?;
+        my_reports.with(|reports| {
+            reports
+                .iter()
+                .find(|r| r.target() == (post_key, Some(comment_id.as_str())))
+                .cloned()
+        })
+    };
+
+    let reason = RwSignal::new(ReportOption::Nudity);
+    let error = RwSignal::new(None::<String>);
+    let post = StoredValue::new(ctx.post.clone());
+    let submit = Action::new(move |()| {
+        let comment = reporting.get_untracked();
+        let request = comment.map(|comment| {
+            post.with_value(|post| ReportRequest {
+                canister_id: post.canister_id,
+                post_id: post.post_id,
+                reason: reason.get_untracked(),
+                description: None,
+                video_timestamp_secs: None,
+                comment_id: Some(comment.id),
+            })
+        });
+        async move {
+            let Some(request) = request else {
+                return;
+            };
+            match submit_report(request).await {
+                Ok(report) => {
+                    error.set(None);
+                    remember_report(set_my_reports, report);
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    view! {
+        <Modal show>
+            <div class="flex flex-col justify-center items-center gap-4 text-white">
+                <span class="text-lg">Report Comment</span>
+                {move || match existing() {
+                    Some(report) => {
+                        view! {
+                            <div class="flex flex-col items-center gap-2 text-center">
+                                <span>Thanks, we have received your report.</span>
+                                <span class="text-sm text-white/60">
+                                    {format!("{} · {}", report.reason.as_str(), report.status.as_str())}
+                                </span>
+                            </div>
+                        }
+                            .into_any()
+                    }
+                    None => {
+                        view! {
+                            <span class="text-lg">Please select a reason:</span>
+                            <div class="max-w-full w-full text-md text-black">
+                                <select
+                                    class="p-2 w-full block rounded-lg text-sm"
+                                    on:change=move |ev| {
+                                        if let Some(opt) = ReportOption::from_label(&event_target_value(&ev)) {
+                                            reason.set(opt);
+                                        }
+                                    }
+                                >
+                                    {ReportOption::ALL
+                                        .into_iter()
+                                        .map(|opt| {
+                                            let label = opt.as_str().to_string();
+                                            view! {
+                                                <option value=label.clone() selected=move || reason() == opt>
+                                                    {label.clone()}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </select>
+                            </div>
+                            {move || error().map(|e| view! { <span class="text-sm text-red-500">{e}</span> })}
+                            <button
+                                disabled=move || submit.pending().get()
+                                on:click=move |_| {
+                                    submit.dispatch(());
+                                }
+                            >
+                                <div class="rounded-lg bg-pink-500 p-1">
+                                    {move || if submit.pending().get() { "Submitting..." } else { "Submit" }}
+                                </div>
+                            </button>
+                        }
+                            .into_any()
+                    }
+                }}
+            </div>
+        </Modal>
+    }
+}
+
+/// Bottom sheet with the comments of a post
+#[component]
+pub fn CommentSheet(post: PostDetails, show: RwSignal<bool>) -> impl IntoView {
+    let (viewer, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+    let ctx = CommentSheetCtx {
+        post,
+        viewer,
+        replying_to: RwSignal::new(None),
+        reporting: RwSignal::new(None),
+        posted: RwSignal::new(vec![]),
+        deleted: RwSignal::new(HashSet::new()),
+    };
+    provide_context(ctx.clone());
+
+    let posted_comments = move || ctx.posted_under(None);
+
+    view! {
+        <Show when=show>
+            <div
+                class="fixed inset-0 z-50 flex flex-col justify-end bg-black/50"
+                on:click=move |_| show.set(false)
+            >
+                <div
+                    class="flex flex-col w-full h-[70dvh] rounded-t-2xl bg-neutral-900 px-4 pt-3 pb-6 gap-3"
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <div class="flex flex-row justify-between items-center text-white">
+                        <span class="font-semibold">Comments</span>
+                        <button on:click=move |_| show.set(false)>
+                            <Icon attr:class="text-lg" icon=icondata::ChCross />
+                        </button>
+                    </div>
+                    <div class="flex flex-col gap-4 overflow-y-auto grow">
+                        <For
+                            each=posted_comments.clone()
+                            key=|comment| comment.id.clone()
+                            children=|comment| view! { <CommentItem comment depth=0 /> }
+                        />
+                        <CommentList parent=None fetch_count=COMMENTS_CHUNK_SZ depth=0 />
+                    </div>
+                    <CommentInput />
+                </div>
+            </div>
+            <CommentReportModal />
+        </Show>
+    }
+}
@@ -0,0 +1,124 @@
+use auth::server_impl::extract_principal_impl;
+use candid::Principal;
+use leptos::prelude::*;
+use state::{
+    canisters::unauth_canisters,
+    comments::{Comment, CommentStore, CommentStoreImpl},
+};
+use utils::types::PostId;
+use web_time::SystemTime;
+
+use super::{CommentDetails, MAX_COMMENT_LEN};
+
+async fn caller() -> Result<Principal, ServerFnError> {
+    extract_principal_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))
+}
+
+fn to_details(comment: Comment) -> CommentDetails {
+    CommentDetails {
+        liked_by_caller: comment.liked_by_viewer,
+        likes: comment.likes,
+        id: comment.id,
+        parent_id: comment.parent_id,
+        author: comment.author,
+        text: comment.text,
+        created_at: comment.created_at,
+        reply_count: comment.reply_count,
+    }
+}
+
+pub async fn get_comments(
+    post: PostId,
+    parent: Option<String>,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<CommentDetails>, bool), ServerFnError> {
+    let store: CommentStoreImpl = expect_context();
+    let caller = extract_principal_impl().await?;
+    let (comments, list_end) = store
+        .list_comments(post, parent.as_deref(), start, end, caller)
+        .await?;
+    Ok((comments.into_iter().map(to_details).collect(), list_end))
+}
+
+pub async fn add_comment(
+    post: PostId,
+    parent: Option<String>,
+    text: String,
+) -> Result<CommentDetails, ServerFnError> {
+    let author = caller().await?;
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(ServerFnError::new("comment is empty"));
+    }
+    if text.chars().count() > MAX_COMMENT_LEN {
+        return Err(ServerFnError::new("comment is too long"));
+    }
+    if unauth_canisters()
+        .get_post_details(post.0, post.1)
+        .await?
+        .is_none()
+    {
+        return Err(ServerFnError::new("post not found"));
+    }
+
+    let created_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs();
+    let comment = Comment {
+        id: uuid::Uuid::new_v4().to_string(),
+        post,
+        parent_id: parent,
+        author,
+        text,
+        created_at,
+        likes: 0,
+        liked_by_viewer: false,
+        reply_count: 0,
+    };
+
+    let store: CommentStoreImpl = expect_context();
+    store.add_comment(comment.clone()).await?;
+    Ok(to_details(comment))
+}
+
+pub async fn toggle_comment_like(
+    post: PostId,
+    comment_id: String,
+) -> Result<(bool, u64), ServerFnError> {
+    let caller = caller().await?;
+    let store: CommentStoreImpl = expect_context();
+    let Some(comment) = store.get_comment(post, &comment_id, Some(caller)).await? else {
+        return Err(ServerFnError::new("comment not found"));
+    };
+
+    let liked = !comment.liked_by_viewer;
+    let likes = store
+        .set_comment_like(post, &comment_id, caller, liked)
+        .await?;
+
+    Ok((liked, likes))
+}
+
+pub async fn delete_comment(post: PostId, comment_id: String) -> Result<(), ServerFnError> {
+    let caller = caller().await?;
+    let store: CommentStoreImpl = expect_context();
+    let Some(comment) = store.get_comment(post, &comment_id, None).await? else {
+        return Ok(());
+    };
+
+    if comment.author != caller {
+        // the creator of the post moderates its comments
+        let post_details = unauth_canisters().get_post_details(post.0, post.1).await?;
+        if post_details.map(|p| p.poster_principal) != Some(caller) {
+            return Err(ServerFnError::new(
+                "only the author or the creator can delete a comment",
+            ));
+        }
+    }
+
+    store.delete_comment(post, &comment_id).await
+}
@@ -0,0 +1,195 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use candid::Principal;
+use codee::string::FromToStringCodec;
+use component::spinner::FullScreenSpinner;
+use consts::USER_PRINCIPAL_STORE;
+use leptos::{prelude::*, task::spawn_local};
+use leptos_meta::Title;
+use leptos_use::use_cookie;
+use serde::{Deserialize, Serialize};
+use state::app_state::AppState;
+use utils::types::PostId;
+use yral_canisters_common::utils::posts::PostDetails;
+
+use crate::post_view::{
+    feed_strategy::{FollowingFeed, MlHybridFeed},
+    PostViewCtx, PostViewWithUpdatesFeed,
+};
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Debug)]
+pub struct FollowedCreator {
+    pub principal: Principal,
+    /// User canister of the creator, used to page their posts
+    pub canister: Principal,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Debug)]
+pub struct FollowInfo {
+    pub followers_cnt: u64,
+    pub following_cnt: u64,
+    pub followed_by_caller: bool,
+}
+
+#[server]
+pub async fn get_follow_info(profile: Principal) -> Result<FollowInfo, ServerFnError> {
+    server_impl::get_follow_info(profile).await
+}
+
+#[server]
+pub async fn set_following(creator: Principal, follow: bool) -> Result<(), ServerFnError> {
+    server_impl::set_following(creator, follow).await
+}
+
+#[server]
+pub async fn get_followed_creators() -> Result<Vec<FollowedCreator>, ServerFnError> {
+    server_impl::get_followed_creators().await
+}
+
+/// The followed creators' latest posts, newest first,
+/// returns the posts and whether the end of the timeline was reached
+#[server]
+pub async fn get_following_posts(
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    server_impl::get_following_posts(start, end).await
+}
+
+/// Follow info of `profile`, fetched on the client
+pub fn use_follow_info(profile: Principal) -> RwSignal<Option<FollowInfo>> {
+    let info = RwSignal::new(None);
+    Effect::new(move |_| {
+        spawn_local(async move {
+            match get_follow_info(profile).await {
+                Ok(res) => info.set(Some(res)),
+                Err(e) => log::warn!("failed to fetch follow info: {e}"),
+            }
+        });
+    });
+    info
+}
+
+#[component]
+pub fn FollowButton(creator: FollowedCreator, info: RwSignal<Option<FollowInfo>>) -> impl IntoView {
+    let (viewer_principal, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+    let show =
+        move || info.with(|i| i.is_some()) && viewer_principal.get() != Some(creator.principal);
+    let following = move || info.with(|i| i.map(|i| i.followed_by_caller).unwrap_or_default());
+
+    let toggle_follow = Action::new(move |&follow: &bool| {
+        let update = move |follow: bool| {
+            info.update(|i| {
+                let Some(i) = i.as_mut() else {
+                    return;
+                };
+                if i.followed_by_caller == follow {
+                    return;
+                }
+                i.followed_by_caller = follow;
+                if follow {
+                    i.followers_cnt += 1;
+                } else {
+                    i.followers_cnt = i.followers_cnt.saturating_sub(1);
+                }
+            })
+        };
+        async move {
+            update(follow);
+            if let Err(e) = set_following(creator.principal, follow).await {
+                log::warn!("failed to update follow status: {e}");
+                update(!follow);
+            }
+        }
+    });
+
+    view! {
+        <Show when=show>
+            <button
+                class="px-3 py-0.5 rounded-full text-xs md:text-sm font-semibold"
+                class=(["bg-primary-600", "text-white"], move || !following())
+                class=(["bg-white/10", "text-white/80"], following)
+                disabled=move || toggle_follow.pending().get()
+                on:click=move |ev| {
+                    ev.prevent_default();
+                    toggle_follow.dispatch(!following());
+                }
+            >
+                {move || if following() { "Following" } else { "Follow" }}
+            </button>
+        </Show>
+    }
+}
+
+#[component]
+fn NoFollowedCreators() -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-2 justify-center items-center w-dvw h-dvh bg-black text-white text-center px-8">
+            <span class="text-lg font-bold">"You aren't following anyone yet"</span>
+            <span class="text-sm text-white/70">
+                "Follow creators from their profile or video to see their latest posts here"
+            </span>
+            <a href="/" class="mt-4 px-6 py-2 rounded-full bg-primary-600 font-semibold">
+                "Explore videos"
+            </a>
+        </div>
+    }
+}
+
+/// Latest posts of the followed creators,
+/// `fallback_to_default` serves the default feed instead of the empty state when nobody is followed
+#[component]
+pub(crate) fn FollowedPostsFeed(
+    initial_post: Option<PostDetails>,
+    #[prop(default = true)] sync_url: bool,
+    #[prop(default = false)] fallback_to_default: bool,
+) -> impl IntoView {
+    let creators = LocalResource::new(get_followed_creators);
+
+    view! {
+        <Suspense fallback=FullScreenSpinner>
+            {move || {
+                let initial_post = initial_post.clone();
+                creators
+                    .get()
+                    .map(|creators| {
+                        let creators = match creators.take() {
+                            Ok(creators) => creators,
+                            Err(e) => {
+                                log::warn!("failed to fetch followed creators: {e}");
+                                vec![]
+                            }
+                        };
+                        if creators.is_empty() && fallback_to_default {
+                            return view! {
+                                <PostViewWithUpdatesFeed initial_post strategy=MlHybridFeed sync_url />
+                            }
+                                .into_any();
+                        }
+                        if creators.is_empty() {
+                            return view! { <NoFollowedCreators /> }.into_any();
+                        }
+                        view! {
+                            <PostViewWithUpdatesFeed initial_post strategy=FollowingFeed sync_url />
+                        }
+                            .into_any()
+                    })
+            }}
+        </Suspense>
+    }
+}
+
+#[component]
+pub fn FollowingFeedPage() -> impl IntoView {
+    // separate queue from the home feed
+    provide_context(PostViewCtx::default());
+
+    let app_state = use_context::<AppState>();
+    let page_title = app_state.unwrap().name.to_owned() + " - Following";
+
+    view! {
+        <Title text=page_title />
+        <FollowedPostsFeed initial_post=None sync_url=false />
+    }
+}
@@ -0,0 +1,259 @@
+use std::cmp::Reverse;
+
+use auth::server_impl::{extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use futures::StreamExt;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use state::{canisters::unauth_canisters, search::paginate};
+use utils::types::PostId;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use super::{FollowInfo, FollowedCreator};
+use crate::profile::profile_iter::{FixedFetchCursor, ProfVideoStream, ProfileVideoStream};
+
+/// Creators `principal` follows, scored by when they were followed
+fn following_key(principal: Principal) -> String {
+    format!("following:{}", principal.to_text())
+}
+
+/// Users following `principal`, scored by when they followed
+fn followers_key(principal: Principal) -> String {
+    format!("followers:{}", principal.to_text())
+}
+
+fn user_canister_key(principal: Principal) -> String {
+    format!("user-canister:{}", principal.to_text())
+}
+
+/// Merged timeline of the followed creators' posts, see [`FollowingTimeline`]
+fn timeline_key(principal: Principal) -> String {
+    format!("following-timeline:{}", principal.to_text())
+}
+
+/// The following feed pages posts from at most this many of the latest followed creators
+const MAX_FEED_CREATORS: usize = 200;
+/// Latest posts of each creator merged into the timeline
+const POSTS_PER_CREATOR: u64 = 10;
+/// Creator canisters queried at once while building a timeline
+const TIMELINE_FETCH_CONCURRENCY: usize = 16;
+/// A timeline is rebuilt from the canisters at most this often
+const TIMELINE_TTL_MS: u64 = 5 * 60 * 1000;
+
+/// Newest first, cached so paging the feed doesn't fan out to every followed canister
+#[derive(Serialize, Deserialize)]
+struct FollowingTimeline {
+    built_at_ms: u64,
+    posts: Vec<PostId>,
+}
+
+fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as f64
+}
+
+pub(crate) async fn follow_info(
+    kv: &KVStoreImpl,
+    profile: Principal,
+    caller: Option<Principal>,
+) -> Result<FollowInfo, ServerFnError> {
+    let followed_by_caller = match caller {
+        Some(caller) => {
+            kv.set_contains(followers_key(profile), caller.to_text())
+                .await?
+        }
+        None => false,
+    };
+    Ok(FollowInfo {
+        followers_cnt: kv.set_len(followers_key(profile)).await? as u64,
+        following_cnt: kv.set_len(following_key(profile)).await? as u64,
+        followed_by_caller,
+    })
+}
+
+/// Both sides are sets, so repeated or concurrent (un)follows can't duplicate or drop entries
+pub(crate) async fn update_following(
+    kv: &KVStoreImpl,
+    caller: Principal,
+    creator: Principal,
+    follow: bool,
+) -> Result<(), ServerFnError> {
+    if follow {
+        let followed_at = now_ms();
+        kv.set_add(following_key(caller), creator.to_text(), followed_at)
+            .await?;
+        kv.set_add(followers_key(creator), caller.to_text(), followed_at)
+            .await?;
+    } else {
+        kv.set_remove(following_key(caller), creator.to_text())
+            .await?;
+        kv.set_remove(followers_key(creator), caller.to_text())
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn get_follow_info(profile: Principal) -> Result<FollowInfo, ServerFnError> {
+    let kv: KVStoreImpl = expect_context();
+    let caller = extract_principal_impl().await?;
+    follow_info(&kv, profile, caller).await
+}
+
+pub async fn set_following(creator: Principal, follow: bool) -> Result<(), ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Err(ServerFnError::new("not logged in"));
+    };
+    if caller == creator {
+        return Err(ServerFnError::new("can't follow yourself"));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    if follow {
+        // resolved here, the followed feed pages posts from this canister
+        let Some(canister) = unauth_canisters()
+            .get_individual_canister_by_user_principal(creator)
+            .await?
+        else {
+            return Err(ServerFnError::new("creator not found"));
+        };
+        kv.write_json(user_canister_key(creator), &canister).await?;
+    }
+    update_following(&kv, caller, creator, follow).await?;
+    // rebuilt with the new set of creators on the next fetch
+    kv.delete(timeline_key(caller)).await?;
+    Ok(())
+}
+
+pub async fn get_followed_creators() -> Result<Vec<FollowedCreator>, ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Ok(vec![]);
+    };
+    let kv: KVStoreImpl = expect_context();
+    followed_creators(&kv, caller).await
+}
+
+async fn followed_creators(
+    kv: &KVStoreImpl,
+    caller: Principal,
+) -> Result<Vec<FollowedCreator>, ServerFnError> {
+    let followed = kv
+        .set_range(following_key(caller), 0, MAX_FEED_CREATORS)
+        .await?;
+
+    let mut creators = Vec::with_capacity(followed.len());
+    for principal in followed {
+        let principal = Principal::from_text(principal)?;
+        let Some(canister) = kv.read_json(user_canister_key(principal)).await? else {
+            continue;
+        };
+        creators.push(FollowedCreator {
+            principal,
+            canister,
+        });
+    }
+    Ok(creators)
+}
+
+async fn build_timeline(
+    kv: &KVStoreImpl,
+    caller: Principal,
+) -> Result<FollowingTimeline, ServerFnError> {
+    let creators = followed_creators(kv, caller).await?;
+    let canisters = unauth_canisters();
+    let cursor = FixedFetchCursor::<POSTS_PER_CREATOR> {
+        start: 0,
+        limit: POSTS_PER_CREATOR,
+    };
+    let mut posts = futures::stream::iter(creators)
+        .map(|creator| {
+            ProfileVideoStream::<POSTS_PER_CREATOR>::fetch_next_posts(
+                cursor,
+                &canisters,
+                creator.canister,
+            )
+        })
+        .buffer_unordered(TIMELINE_FETCH_CONCURRENCY)
+        .filter_map(|res| async move {
+            match res {
+                Ok(res) => Some(res.posts),
+                // the rest of the timeline is still worth serving
+                Err(e) => {
+                    log::warn!("failed to fetch posts of a followed creator: {e}");
+                    None
+                }
+            }
+        })
+        .concat()
+        .await;
+    posts.sort_by_key(|post| Reverse(post.created_at));
+
+    Ok(FollowingTimeline {
+        built_at_ms: now_ms() as u64,
+        posts: posts
+            .into_iter()
+            .map(|post| (post.canister_id, post.post_id))
+            .collect(),
+    })
+}
+
+pub async fn get_following_posts(
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Ok((vec![], true));
+    };
+    let kv: KVStoreImpl = expect_context();
+    let key = timeline_key(caller);
+    let cached: Option<FollowingTimeline> = kv.read_json(key.clone()).await?;
+    let age_ms =
+        |timeline: &FollowingTimeline| (now_ms() as u64).saturating_sub(timeline.built_at_ms);
+    let timeline = match cached {
+        // later pages stay on the timeline the first page was served from
+        Some(timeline) if start > 0 || age_ms(&timeline) < TIMELINE_TTL_MS => timeline,
+        _ => {
+            let timeline = build_timeline(&kv, caller).await?;
+            kv.write_json(key, &timeline).await?;
+            timeline
+        }
+    };
+    Ok(paginate(&timeline.posts, start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_on, test_kv};
+
+    fn user(n: u8) -> Principal {
+        Principal::from_slice(&[n])
+    }
+
+    #[test]
+    fn follows_are_idempotent_and_counted_on_both_sides() {
+        let kv = test_kv("follows");
+        block_on(async {
+            for _ in 0..2 {
+                update_following(&kv, user(1), user(2), true).await.unwrap();
+            }
+            update_following(&kv, user(3), user(2), true).await.unwrap();
+
+            let creator = follow_info(&kv, user(2), Some(user(1))).await.unwrap();
+            assert_eq!(creator.followers_cnt, 2);
+            assert_eq!(creator.following_cnt, 0);
+            assert!(creator.followed_by_caller);
+
+            update_following(&kv, user(1), user(2), false)
+                .await
+                .unwrap();
+            let creator = follow_info(&kv, user(2), Some(user(1))).await.unwrap();
+            assert_eq!(creator.followers_cnt, 1);
+            assert!(!creator.followed_by_caller);
+
+            let follower = follow_info(&kv, user(3), None).await.unwrap();
+            assert_eq!(follower.following_cnt, 1);
+        });
+    }
+}
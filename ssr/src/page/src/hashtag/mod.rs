@@ -0,0 +1,224 @@
+#[cfg(feature = "ssr")]
+pub(crate) mod server_impl;
+
+use component::{
+    back_btn::BackButton, bullet_loader::BulletLoader, infinite_scroller::InfiniteScroller,
+    title::TitleText,
+};
+use futures::stream::{FuturesOrdered, StreamExt, TryStreamExt};
+use leptos::{html, prelude::*};
+use leptos_icons::*;
+use leptos_meta::Title;
+use leptos_router::{
+    hooks::{use_navigate, use_params},
+    params::Params,
+};
+pub use state::search::normalize_hashtag;
+use state::{app_state::AppState, canisters::unauth_canisters};
+use utils::{bg_url, types::PostId};
+use yral_canisters_common::{
+    cursored_data::{CursoredDataProvider, PageEntry},
+    utils::posts::PostDetails,
+    Canisters,
+};
+
+const HASHTAG_CHUNK_SZ: usize = 12;
+
+pub fn hashtag_url(hashtag: &str) -> String {
+    format!("/tag/{}", normalize_hashtag(hashtag))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextSegment {
+    Text(String),
+    Hashtag(String),
+}
+
+/// Split free form text into plain text and `#hashtag` segments
+pub fn split_hashtags(text: &str) -> Vec<TextSegment> {
+    let mut segments = vec![];
+    let mut rest = text;
+    while let Some(idx) = rest.find('#') {
+        let tag_len = rest[idx + 1..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len() - idx - 1);
+        if tag_len == 0 {
+            segments.push(TextSegment::Text(rest[..=idx].to_string()));
+            rest = &rest[idx + 1..];
+            continue;
+        }
+        if idx > 0 {
+            segments.push(TextSegment::Text(rest[..idx].to_string()));
+        }
+        segments.push(TextSegment::Hashtag(
+            rest[idx + 1..idx + 1 + tag_len].to_string(),
+        ));
+        rest = &rest[idx + 1 + tag_len..];
+    }
+    if !rest.is_empty() {
+        segments.push(TextSegment::Text(rest.to_string()));
+    }
+    segments
+}
+
+/// Newest first, returns the posts and whether the end of the list was reached
+#[server]
+pub async fn get_hashtag_posts(
+    hashtag: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    server_impl::get_hashtag_posts(normalize_hashtag(&hashtag), start, end).await
+}
+
+#[derive(Clone)]
+pub struct HashtagPostsProvider {
+    canisters: Canisters<false>,
+    hashtag: String,
+}
+
+impl CursoredDataProvider for HashtagPostsProvider {
+    type Data = PostDetails;
+    type Error = ServerFnError;
+
+    async fn get_by_cursor_inner(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<PageEntry<PostDetails>, ServerFnError> {
+        let (post_ids, list_end) = get_hashtag_posts(self.hashtag.clone(), start, end).await?;
+        let data = post_ids
+            .into_iter()
+            .map(|(canister_id, post_id)| self.canisters.get_post_details(canister_id, post_id))
+            .collect::<FuturesOrdered<_>>()
+            .filter_map(|res| async { res.transpose() })
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(ServerFnError::new)?;
+
+        Ok(PageEntry {
+            data,
+            end: list_end,
+        })
+    }
+}
+
+#[component]
+pub(crate) fn PostThumbnail(details: PostDetails, _ref: NodeRef<html::Div>) -> impl IntoView {
+    let post_url = format!("/post/{}/{}", details.canister_id, details.post_id);
+
+    view! {
+        <div node_ref=_ref class="relative w-full basis-1/3 md:basis-1/4 xl:basis-1/5">
+            <a href=post_url class="block relative aspect-[9/16] rounded-md border-white/20 m-2 border-[1px] overflow-hidden">
+                <img class="object-cover w-full h-full" src=bg_url(details.uid.clone()) />
+                <div class="absolute bottom-1 right-1 flex flex-row items-center gap-1">
+                    <Icon
+                        attr:class="h-5 w-5 p-1 text-white rounded-full bg-black/30"
+                        icon=icondata::AiEyeOutlined
+                    />
+                    <span class="text-white text-xs">{details.views}</span>
+                </div>
+            </a>
+        </div>
+    }
+}
+
+#[component]
+fn HashtagSearch(#[prop(into)] initial: String) -> impl IntoView {
+    let query = RwSignal::new(initial);
+    let search = move || {
+        let hashtag = normalize_hashtag(&query.get_untracked());
+        if hashtag.is_empty() {
+            return;
+        }
+        use_navigate()(&hashtag_url(&hashtag), Default::default());
+    };
+
+    view! {
+        <form
+            class="flex flex-row items-center gap-2 w-11/12 sm:w-7/12 px-4 py-2 rounded-full bg-white/10"
+            on:submit=move |ev| {
+                ev.prevent_default();
+                search();
+            }
+        >
+            <span class="text-white/60">#</span>
+            <input
+                class="w-full bg-transparent text-white outline-none placeholder-white/40"
+                type="text"
+                placeholder="Search hashtags"
+                prop:value=query
+                on:input=move |ev| query.set(event_target_value(&ev))
+            />
+            <button type="submit" class="text-white text-xl">
+                <Icon icon=icondata::AiSearchOutlined />
+            </button>
+        </form>
+    }
+}
+
+#[derive(Params, PartialEq, Clone)]
+struct HashtagParams {
+    hashtag: String,
+}
+
+#[component]
+pub fn HashtagPage() -> impl IntoView {
+    let params = use_params::<HashtagParams>();
+    let hashtag = Memo::new(move |_| {
+        params.with(|p| {
+            p.as_ref()
+                .map(|p| normalize_hashtag(&p.hashtag))
+                .unwrap_or_default()
+        })
+    });
+
+    let app_state = use_context::<AppState>();
+    let app_name = app_state.unwrap().name.to_owned();
+
+    view! {
+        <Title text=move || format!("{app_name} - #{}", hashtag()) />
+        <div class="min-h-screen w-full flex flex-col text-white pt-2 pb-12 bg-black items-center gap-4">
+            <TitleText justify_center=false>
+                <div class="flex flex-row justify-between">
+                    <BackButton fallback="/".to_string() />
+                    <span class="font-bold text-2xl">{move || format!("#{}", hashtag())}</span>
+                    <div></div>
+                </div>
+            </TitleText>
+            {move || {
+                let hashtag = hashtag();
+                let provider = HashtagPostsProvider {
+                    canisters: unauth_canisters(),
+                    hashtag: hashtag.clone(),
+                };
+                view! {
+                    <HashtagSearch initial=hashtag.clone() />
+                    <div class="flex flex-row gap-y-3 flex-wrap justify-center w-full sm:w-7/12">
+                        <InfiniteScroller
+                            provider
+                            fetch_count=HASHTAG_CHUNK_SZ
+                            children=|details, _ref| {
+                                view! { <PostThumbnail details _ref=_ref.unwrap_or_default() /> }
+                            }
+                            empty_content=move || {
+                                view! {
+                                    <span class="pt-9 text-lg text-white/70">
+                                        {format!("No videos with #{hashtag} yet")}
+                                    </span>
+                                }
+                            }
+                            custom_loader=move || {
+                                view! {
+                                    <div class="w-full flex justify-center items-center pt-9">
+                                        <BulletLoader />
+                                    </div>
+                                }
+                            }
+                        />
+                    </div>
+                }
+            }}
+        </div>
+    }
+}
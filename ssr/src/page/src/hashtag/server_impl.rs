@@ -0,0 +1,12 @@
+use leptos::prelude::*;
+use state::search::{SearchIndex, SearchIndexImpl};
+use utils::types::PostId;
+
+pub async fn get_hashtag_posts(
+    hashtag: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    let search_index: SearchIndexImpl = expect_context();
+    search_index.hashtag_posts(&hashtag, start, end).await
+}
@@ -38,6 +38,7 @@ use utils::token::firestore::listen_to_documents;
 use utils::token::icpump::get_paginated_token_list;
 use utils::token::icpump::TokenListItem;
 
+use crate::blocking::{HiddenCreators, HiddenCreatorsCtx};
 use crate::wallet::airdrop::AirdropPopup;
 use component::overlay::ShadowOverlay;
 
@@ -51,6 +52,17 @@ pub struct ProcessedTokenListResponse {
     pub is_airdrop_claimed: bool,
 }
 
+impl ProcessedTokenListResponse {
+    fn is_created_by(&self, creators: &HiddenCreators) -> bool {
+        let owner = self.token_owner.as_ref().map(|o| o.principal_id);
+        let user = Principal::from_text(&self.token_details.user_id).ok();
+        owner
+            .into_iter()
+            .chain(user)
+            .any(|p| creators.is_blocked(p))
+    }
+}
+
 #[cfg(any(feature = "local-bin", feature = "local-lib"))]
 pub async fn process_token_list_item(
     token_list_item: Vec<TokenListItem>,
@@ -140,6 +152,7 @@ pub fn ICPumpListingFeed() -> impl IntoView {
     let token_list: RwSignal<Vec<ProcessedTokenListResponse>> = RwSignal::new(vec![]);
     let new_token_list: RwSignal<VecDeque<ProcessedTokenListResponse>> =
         RwSignal::new(VecDeque::new());
+    let hidden_creators: HiddenCreatorsCtx = expect_context();
 
     let fetch_res = Resource::new(
         move || page.get(),
@@ -160,6 +173,8 @@ pub fn ICPumpListingFeed() -> impl IntoView {
                 if fetched_token_list.len() < ICPUMP_LISTING_PAGE_SIZE {
                     end.set(true);
                 }
+                let hidden = hidden_creators.load().await;
+                fetched_token_list.retain(|t| !t.is_created_by(&hidden));
 
                 token_list.update(|t| {
                     t.append(&mut fetched_token_list);
@@ -178,7 +193,8 @@ pub fn ICPumpListingFeed() -> impl IntoView {
                 let mut stream = listen_to_documents(&firestore);
                 while let Some(doc) = stream.next().await {
                     let doc = process_token_list_item(doc, principal).await;
-                    for item in doc {
+                    let hidden = hidden_creators.load().await;
+                    for item in doc.into_iter().filter(|t| !t.is_created_by(&hidden)) {
                         new_token_list.try_update(move |list| {
                             list.push_front(item.clone());
                         });
@@ -1,10 +1,15 @@
 #![recursion_limit = "256"]
 pub mod about_us;
 pub mod airdrop;
+pub mod bet_outcomes;
+pub mod blocking;
+pub mod comments;
 pub mod err;
 pub mod faq;
+pub mod following;
 #[cfg(any(feature = "oauth-ssr", feature = "oauth-hydrate"))]
 pub mod google_redirect;
+pub mod hashtag;
 pub mod icpump;
 pub mod leaderboard;
 pub mod logout;
@@ -17,11 +22,15 @@ pub mod privacy;
 pub mod profile;
 pub mod pumpdump;
 pub mod refer_earn;
+pub mod reports;
 pub mod root;
 pub mod scrolling_post_view;
+pub mod search;
 pub mod settings;
 pub mod terms;
 pub mod terms_ios;
+#[cfg(all(test, feature = "ssr"))]
+mod test_utils;
 pub mod token;
 pub mod upload;
 pub mod view_profile_redirect;
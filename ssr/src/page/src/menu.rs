@@ -298,6 +298,9 @@ pub fn Menu() -> impl IntoView {
                 // <NsfwToggle />
                 <MenuItem href="/refer-earn" text="Refer & Earn" icon=icondata::AiGiftFilled />
                 <MenuItem href="/leaderboard" text="Leaderboard" icon=icondata::ChTrophy />
+                <MenuItem href="/following" text="Following" icon=icondata::FiUsers />
+                <MenuItem href="/search" text="Search" icon=icondata::AiSearchOutlined />
+                <MenuItem href="/reports" text="My Reports" icon=icondata::TbMessageReport />
                 <MenuItem
                     href=domain_specific_href("TELEGRAM")
                     text="Talk to the team"
@@ -1,14 +1,20 @@
-use crate::post_view::BetEligiblePostCtx;
-use component::{
-    bullet_loader::BulletLoader, canisters_prov::AuthCansProvider, hn_icons::*, spinner::SpinnerFit,
-};
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use crate::upload::hot_or_not::{hot_or_not_status, HotOrNotStatus};
+use crate::{bet_outcomes::BetOutcomesCtx, post_view::BetEligiblePostCtx};
+use candid::Principal;
+use component::{bullet_loader::BulletLoader, hn_icons::*, spinner::SpinnerFit};
 use leptos::{either::Either, prelude::*};
 use leptos_icons::*;
 use leptos_use::use_interval_fn;
-use state::canisters::{authenticated_canisters, unauth_canisters};
-use utils::{send_wrap, time::to_hh_mm_ss, try_or_redirect_opt};
+use state::canisters::authenticated_canisters;
+use thiserror::Error;
+use utils::{
+    event_streaming::events::auth_canisters_store, send_wrap, time::to_hh_mm_ss,
+    try_or_redirect_opt,
+};
 use web_time::Duration;
-use yral_canisters_client::individual_user_template::BettingStatus;
 use yral_canisters_common::{
     utils::{
         posts::PostDetails,
@@ -17,56 +23,167 @@ use yral_canisters_common::{
     Canisters,
 };
 
+/// Stakes offered as quick picks
+const BET_PRESETS: [u64; 3] = [50, 100, 200];
+/// Every post takes the same limits, checked by the picker and again by `place_bet`
+pub const BET_LIMITS: BetLimits = BetLimits { min: 10, max: 1000 };
+
+/// Stake limits, inclusive
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum CoinState {
-    C50,
-    C100,
-    C200,
+pub struct BetLimits {
+    pub min: u64,
+    pub max: u64,
 }
 
-impl CoinState {
-    fn wrapping_next(self) -> Self {
-        match self {
-            CoinState::C50 => CoinState::C100,
-            CoinState::C100 => CoinState::C200,
-            CoinState::C200 => CoinState::C50,
-        }
+#[derive(Clone, Copy, Debug, PartialEq, Error)]
+pub enum BetValidationError {
+    #[error("Minimum stake is {0} tokens")]
+    BelowMin(u64),
+    #[error("Maximum stake is {0} tokens")]
+    AboveMax(u64),
+    #[error("Not enough tokens, your balance is {0}")]
+    InsufficientBalance(u64),
+    #[error("Betting is closed for this post")]
+    BettingClosed,
+}
+
+impl BetLimits {
+    /// Largest stake allowed, given the balance if known
+    pub fn max_stake(&self, balance: Option<u64>) -> u64 {
+        balance.map_or(self.max, |b| b.min(self.max))
+    }
+
+    pub fn clamp(&self, amount: u64, balance: Option<u64>) -> u64 {
+        amount.clamp(self.min, self.max_stake(balance).max(self.min))
     }
 
-    fn wrapping_prev(self) -> Self {
-        match self {
-            CoinState::C50 => CoinState::C200,
-            CoinState::C100 => CoinState::C50,
-            CoinState::C200 => CoinState::C100,
+    pub fn check(&self, amount: u64, balance: Option<u64>) -> Result<(), BetValidationError> {
+        if amount < self.min {
+            return Err(BetValidationError::BelowMin(self.min));
+        }
+        if amount > self.max {
+            return Err(BetValidationError::AboveMax(self.max));
+        }
+        match balance {
+            Some(balance) if balance < amount => {
+                Err(BetValidationError::InsufficientBalance(balance))
+            }
+            _ => Ok(()),
         }
     }
 }
 
-impl From<CoinState> for u64 {
-    fn from(coin: CoinState) -> u64 {
-        match coin {
-            CoinState::C50 => 50,
-            CoinState::C100 => 100,
-            CoinState::C200 => 200,
-        }
+/// Places the caller's bet on a post once the stake is checked against [`BET_LIMITS`],
+/// its betting status and the caller's balance
+#[server]
+pub async fn place_bet(
+    canister_id: Principal,
+    post_id: u64,
+    amount: u64,
+    hot: bool,
+) -> Result<(), ServerFnError> {
+    server_impl::place_bet((canister_id, post_id), amount, hot).await
+}
+
+/// 1500 -> 1.5K
+fn compact_amount(amount: u64) -> String {
+    match amount {
+        0..1_000 => amount.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", amount as f64 / 1e3),
+        _ => format!("{:.1}M", amount as f64 / 1e6),
     }
+    .replace(".0", "")
 }
 
 #[component]
-fn CoinStateView(
-    #[prop(into)] coin: Signal<CoinState>,
+fn CoinAmountView(
+    #[prop(into)] amount: Signal<u64>,
     #[prop(into)] class: String,
     #[prop(optional, into)] disabled: Signal<bool>,
 ) -> impl IntoView {
-    let icon = Signal::derive(move || match coin() {
-        CoinState::C50 => C50Icon,
-        CoinState::C100 => C100Icon,
-        CoinState::C200 => C200Icon,
-    });
+    view! {
+        <div
+            class=format!(
+                "{class} flex justify-center items-center rounded-full border-2 border-amber-200 bg-gradient-to-b from-amber-300 to-amber-600 font-bold text-black",
+            )
+            class:grayscale=disabled
+        >
+            <span class="text-xs md:text-sm">{move || compact_amount(amount())}</span>
+        </div>
+    }
+}
+
+/// Presets, a slider and a numeric input for the stake
+#[component]
+fn StakePicker(
+    stake: RwSignal<u64>,
+    limits: BetLimits,
+    balance: Option<u64>,
+    #[prop(into)] disabled: Signal<bool>,
+) -> impl IntoView {
+    let max_stake = limits.max_stake(balance);
+    let set_stake = move |amount: u64| stake.set(limits.clamp(amount, balance));
 
     view! {
-        <div class:grayscale=disabled>
-            <Icon attr:class=class icon />
+        <div class="flex flex-col gap-2 items-center w-full px-6 touch-manipulation">
+            <div class="flex flex-row gap-2 justify-center">
+                {BET_PRESETS
+                    .into_iter()
+                    .filter(|preset| (limits.min..=max_stake).contains(preset))
+                    .map(|preset| {
+                        view! {
+                            <button
+                                class="px-3 py-1 rounded-full text-xs font-semibold"
+                                class=(["bg-primary-600", "text-white"], move || stake() == preset)
+                                class=(["bg-black/40", "text-white/80"], move || stake() != preset)
+                                disabled=disabled
+                                on:click=move |_| set_stake(preset)
+                            >
+                                {preset}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+            <div class="flex flex-row gap-3 items-center w-full">
+                <input
+                    class="grow accent-primary-600"
+                    type="range"
+                    min=limits.min
+                    max=max_stake
+                    step=1
+                    disabled=disabled
+                    prop:value=move || stake().to_string()
+                    on:input=move |ev| {
+                        if let Ok(amount) = event_target_value(&ev).parse() {
+                            set_stake(amount);
+                        }
+                    }
+                />
+                <input
+                    class="w-20 px-2 py-1 rounded-md bg-black/40 text-white text-sm text-center outline-none"
+                    type="number"
+                    inputmode="numeric"
+                    min=limits.min
+                    max=max_stake
+                    disabled=disabled
+                    prop:value=move || stake().to_string()
+                    on:change=move |ev| {
+                        let amount = event_target_value(&ev)
+                            .parse()
+                            .unwrap_or(stake.get_untracked());
+                        set_stake(amount);
+                    }
+                />
+            </div>
+            {balance
+                .map(|balance| {
+                    view! {
+                        <span class="text-xs text-white/70">
+                            {format!("Balance: {balance} tokens")}
+                        </span>
+                    }
+                })}
         </div>
     }
 }
@@ -99,40 +216,59 @@ fn HNButton(
     }
 }
 
+fn bet_error_message(e: ServerFnError) -> String {
+    match e {
+        ServerFnError::ServerError(msg) => msg,
+        e => e.to_string(),
+    }
+}
+
 #[component]
 fn HNButtonOverlay(
     post: PostDetails,
-    coin: RwSignal<CoinState>,
+    stake: RwSignal<u64>,
     bet_direction: RwSignal<Option<VoteKind>>,
     refetch_bet: Trigger,
 ) -> impl IntoView {
-    let place_bet_action = Action::new(
-        move |(canisters, bet_direction, bet_amount): &(Canisters<true>, VoteKind, u64)| {
-            let post_can_id = post.canister_id;
-            let post_id = post.post_id;
-            let cans = canisters.clone();
-            let bet_amount = *bet_amount;
-            let bet_direction = *bet_direction;
-            send_wrap(async move {
-                match cans
-                    .vote_on_post(bet_amount, bet_direction, post_id, post_can_id)
-                    .await
-                {
-                    Ok(_) => Some(()),
-                    Err(e) => {
-                        log::error!("{e}");
-                        None
-                    }
-                }
-            })
+    let post_can_id = post.canister_id;
+    let post_id = post.post_id;
+    let bet_error = RwSignal::new(None::<String>);
+    let show_picker = RwSignal::new(false);
+
+    let balance = Resource::new(
+        || (),
+        move |_| async move {
+            async {
+                let cans = authenticated_canisters().await?;
+                let cans = Canisters::from_wire(cans, expect_context())?;
+                let user = cans.authenticated_user().await;
+                let balance = send_wrap(user.get_utility_token_balance()).await?;
+                Ok::<_, ServerFnError>(balance)
+            }
+            .await
+            .ok()
         },
     );
-    let place_bet_res = place_bet_action.value();
-    Effect::new(move |_| {
-        if place_bet_res().flatten().is_some() {
-            refetch_bet.notify();
+
+    let place_bet_action = Action::new(move |(bet_direction, bet_amount): &(VoteKind, u64)| {
+        let hot = matches!(bet_direction, VoteKind::Hot);
+        let bet_amount = *bet_amount;
+        async move {
+            match place_bet(post_can_id, post_id, bet_amount, hot).await {
+                Ok(()) => Some(()),
+                Err(e) => {
+                    bet_error.set(Some(bet_error_message(e)));
+                    None
+                }
+            }
         }
     });
+    let place_bet_res = place_bet_action.value();
+    Effect::new(move |_| match place_bet_res() {
+        Some(Some(())) => refetch_bet.notify(),
+        Some(None) => bet_direction.set(None),
+        None => (),
+    });
     let running = place_bet_action.pending();
 
     let BetEligiblePostCtx { can_place_bet } = expect_context();
@@ -145,49 +281,65 @@ fn HNButtonOverlay(
         }
     });
 
-    view! {
-        <AuthCansProvider let:canisters>
-
-            {
-                Effect::new(move |_| {
-                    let Some(bet_direction) = bet_direction() else {
-                        return;
-                    };
-                    let bet_amount = coin.get_untracked().into();
-                    place_bet_action.dispatch((canisters.clone(), bet_direction, bet_amount));
-                });
-            }
+    Effect::new(move |_| {
+        let Some(bet_direction) = bet_direction() else {
+            return;
+        };
+        bet_error.set(None);
+        let bet_amount = stake.get_untracked();
+        place_bet_action.dispatch((bet_direction, bet_amount));
+    });
 
-        </AuthCansProvider>
+    // keep the picked stake within what can be placed
+    Effect::new(move |_| {
+        if let Some(balance) = balance.get() {
+            stake.update(|s| *s = BET_LIMITS.clamp(*s, balance));
+        }
+    });
 
-        <div class="flex justify-center w-full touch-manipulation">
-            <button disabled=running on:click=move |_| coin.update(|c| *c = c.wrapping_next())>
-                <Icon attr:class="justify-self-end text-2xl text-white" icon=icondata::AiUpOutlined />
-            </button>
-        </div>
+    view! {
+        <Show when=show_picker>
+            <Suspense>
+                {move || {
+                    balance
+                        .get()
+                        .map(|balance| {
+                            view! {
+                                <StakePicker stake limits=BET_LIMITS balance disabled=running />
+                            }
+                        })
+                }}
+            </Suspense>
+        </Show>
         <div class="flex flex-row gap-6 justify-center items-center w-full touch-manipulation">
             <HNButton disabled=running bet_direction kind=VoteKind::Hot />
-            <button disabled=running on:click=move |_| coin.update(|c| *c = c.wrapping_next())>
-                <CoinStateView
+            <button disabled=running on:click=move |_| show_picker.update(|s| *s = !*s)>
+                <CoinAmountView
                     disabled=running
                     class="w-12 h-12 md:w-14 md:h-14 lg:w-16 lg:h-16 drop-shadow-lg"
-                    coin
+                    amount=stake
                 />
-
             </button>
             <HNButton disabled=running bet_direction kind=VoteKind::Not />
         </div>
-        // Bottom row: Hot <down arrow> Not
+        // Bottom row: Hot <stake picker toggle> Not
         // most of the CSS is for alignment with above icons
         <div class="flex gap-6 justify-center items-center pt-2 w-full text-base font-medium text-center md:text-lg lg:text-xl touch-manipulation">
             <p class="w-14 md:w-16 lg:w-18">Hot</p>
             <div class="flex justify-center w-12 md:w-14 lg:w-16">
-                <button disabled=running on:click=move |_| coin.update(|c| *c = c.wrapping_prev())>
-                    <Icon attr:class="text-2xl text-white" icon=icondata::AiDownOutlined />
+                <button disabled=running on:click=move |_| show_picker.update(|s| *s = !*s)>
+                    <span class="flex" class:rotate-180=show_picker>
+                        <Icon attr:class="text-2xl text-white" icon=icondata::AiDownOutlined />
+                    </span>
                 </button>
             </div>
             <p class="w-14 md:w-16 lg:w-18">Not</p>
         </div>
+        {move || {
+            bet_error
+                .get()
+                .map(|e| view! { <p class="pt-1 text-sm text-center text-red-500">{e}</p> })
+        }}
         <ShadowBg />
     }
 }
@@ -222,22 +374,13 @@ fn LostBadge() -> impl IntoView {
 fn HNWonLost(participation: VoteDetails) -> impl IntoView {
     let won = matches!(participation.outcome, VoteOutcome::Won(_));
     let bet_amount = participation.vote_amount;
-    let coin = match bet_amount {
-        50 => CoinState::C50,
-        100 => CoinState::C100,
-        200 => CoinState::C200,
-        amt => {
-            log::warn!("Invalid bet amount: {amt}, using fallback");
-            CoinState::C50
-        }
-    };
     let is_hot = matches!(participation.vote_kind, VoteKind::Hot);
     let hn_icon = if is_hot { HotIcon } else { NotIcon };
 
     view! {
         <div class="flex gap-6 justify-center items-center p-4 w-full bg-transparent rounded-xl shadow-sm">
             <div class="relative flex-shrink-0 drop-shadow-lg">
-                <CoinStateView class="w-14 h-14 md:w-16 md:h-16" coin />
+                <CoinAmountView class="w-14 h-14 md:w-16 md:h-16" amount=bet_amount />
                 <Icon attr:class="absolute -bottom-0.5 -right-3 w-7 h-7 md:w-9 md:h-9" icon=hn_icon />
 
             </div>
@@ -246,7 +389,7 @@ fn HNWonLost(participation: VoteDetails) -> impl IntoView {
             <div class="flex flex-col gap-2 w-full md:w-1/2 lg:w-1/3">
                 // <!-- Result Text -->
                 <div class="p-1 text-sm leading-snug text-white rounded-full">
-                    <p>You staked {bet_amount}tokens on {if is_hot { "Hot" } else { "Not" }}.</p>
+                    <p>You staked {bet_amount} tokens on {if is_hot { "Hot" } else { "Not" }}.</p>
                     <p>
                         {if let Some(reward) = participation.reward() {
                             format!("You received {reward} tokens.")
@@ -269,16 +412,12 @@ fn HNWonLost(participation: VoteDetails) -> impl IntoView {
 }
 
 #[component]
-fn BetTimer(post: PostDetails, participation: VoteDetails, refetch_bet: Trigger) -> impl IntoView {
+fn BetTimer(post: PostDetails, participation: VoteDetails) -> impl IntoView {
     let bet_duration = participation.vote_duration().as_secs();
     let time_remaining = RwSignal::new(participation.time_remaining(post.created_at));
     _ = use_interval_fn(
         move || {
             time_remaining.try_update(|t| *t = t.saturating_sub(Duration::from_secs(1)));
-            _ = refetch_bet;
-            // if time_remaining.try_get_untracked() == Some(Duration::ZERO) {
-            //     refetch_bet.notify();
-            // }
         },
         1000,
     );
@@ -315,33 +454,40 @@ fn HNAwaitingResults(
     let hn_icon = if is_hot { HotIcon } else { NotIcon };
 
     let bet_amount = participation.vote_amount;
-    let coin = match bet_amount {
-        50 => CoinState::C50,
-        100 => CoinState::C100,
-        200 => CoinState::C200,
-        amt => {
-            log::warn!("Invalid bet amount: {amt}, using fallback");
-            CoinState::C50
+
+    // the outcome watcher reports when the slot closes
+    let outcomes: BetOutcomesCtx = expect_context();
+    let bet_key = (participation.canister_id, participation.post_id);
+    let canisters = auth_canisters_store();
+    let watched = (post.clone(), participation.clone());
+    Effect::new(move |_| {
+        let Some(voter) = canisters.with(|c| c.as_ref().map(|c| c.user_canister())) else {
+            return;
+        };
+        let (post, participation) = watched.clone();
+        outcomes.watch(voter, post, participation);
+        if outcomes.settled_outcome(voter, bet_key).is_some() {
+            refetch_bet.notify();
         }
-    };
+    });
 
     view! {
         <div class="flex flex-col gap-1 items-center p-4 w-full shadow-sm">
             <div class="flex flex-row gap-4 justify-center items-end w-full">
                 <div class="relative flex-shrink-0 drop-shadow-lg">
                     <Icon attr:class="w-12 h-12 md:w-14 md:h-14 lg:w-16 lg:h-16" icon=hn_icon />
-                    <CoinStateView
+                    <CoinAmountView
                         class="absolute bottom-0 -right-3 w-7 h-7 md:w-9 md:h-9 lg:w-11 lg:h-11"
-                        coin
+                        amount=bet_amount
                     />
 
                 </div>
                 <div class="w-1/2 md:w-1/3 lg:w-1/4">
-                    <BetTimer post refetch_bet participation />
+                    <BetTimer post participation />
                 </div>
             </div>
             <p class="p-1 text-center text-white rounded-full bg-black/15 ps-2">
-                You staked {bet_amount}tokens on {bet_direction_text}.
+                You staked {bet_amount} tokens on {bet_direction_text}.
                 Result is still pending.
 
             </p>
@@ -379,7 +525,7 @@ pub fn HNUserParticipation(
 fn MaybeHNButtons(
     post: PostDetails,
     bet_direction: RwSignal<Option<VoteKind>>,
-    coin: RwSignal<CoinState>,
+    stake: RwSignal<u64>,
     refetch_bet: Trigger,
 ) -> impl IntoView {
     let post = StoredValue::new(post);
@@ -387,15 +533,13 @@ fn MaybeHNButtons(
         move || (),
         move |_| {
             let post = post.get_value();
-            send_wrap(async move {
-                let canisters = unauth_canisters();
-                let user = canisters.individual_user(post.canister_id).await;
-                let res = user
-                    .get_hot_or_not_bet_details_for_this_post(post.post_id)
+            async move {
+                // also covers creators that opted out after publishing
+                let status = hot_or_not_status(post.canister_id, post.post_id)
                     .await
                     .ok()?;
-                Some(matches!(res, BettingStatus::BettingOpen { .. }))
-            })
+                Some(status == HotOrNotStatus::Open)
+            }
         },
     );
     let BetEligiblePostCtx { can_place_bet } = expect_context();
@@ -413,8 +557,8 @@ fn MaybeHNButtons(
                             view! {
                                 <HNButtonOverlay
                                     post=post.get_value()
+                                    stake
                                     bet_direction
-                                    coin
                                     refetch_bet
                                 />
                             },
@@ -448,7 +592,7 @@ fn ShadowBg() -> impl IntoView {
 #[component]
 pub fn HNGameOverlay(post: PostDetails) -> impl IntoView {
     let bet_direction = RwSignal::new(None::<VoteKind>);
-    let coin = RwSignal::new(CoinState::C50);
+    let stake = RwSignal::new(BET_PRESETS[0]);
 
     let refetch_bet = Trigger::new();
     let post = StoredValue::new(post);
@@ -511,7 +655,7 @@ pub fn HNGameOverlay(post: PostDetails) -> impl IntoView {
                                 }.into_any()
                             } else {
                                 view! {
-                                    <MaybeHNButtons post bet_direction coin refetch_bet />
+                                    <MaybeHNButtons post bet_direction stake refetch_bet />
                                 }.into_any()
                             },
                         )
@@ -524,3 +668,41 @@ pub fn HNGameOverlay(post: PostDetails) -> impl IntoView {
         </Suspense>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: BetLimits = BetLimits { min: 10, max: 100 };
+
+    #[test]
+    fn stakes_are_checked_against_limits_and_balance() {
+        let cases = [
+            (5, None, Err(BetValidationError::BelowMin(10))),
+            (101, None, Err(BetValidationError::AboveMax(100))),
+            (
+                50,
+                Some(40),
+                Err(BetValidationError::InsufficientBalance(40)),
+            ),
+            (50, Some(50), Ok(())),
+            (100, None, Ok(())),
+        ];
+        for (amount, balance, expected) in cases {
+            assert_eq!(
+                LIMITS.check(amount, balance),
+                expected,
+                "{amount} {balance:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn stakes_are_clamped_into_range() {
+        assert_eq!(LIMITS.clamp(5, None), 10);
+        assert_eq!(LIMITS.clamp(500, None), 100);
+        assert_eq!(LIMITS.clamp(80, Some(60)), 60);
+        // too poor to bet, the picker still shows the minimum
+        assert_eq!(LIMITS.clamp(80, Some(5)), 10);
+    }
+}
@@ -0,0 +1,61 @@
+use auth::server_impl::{extract_identity_impl, store::KVStoreImpl};
+use leptos::prelude::*;
+use utils::types::PostId;
+use yral_canisters_client::individual_user_template::BettingStatus;
+use yral_canisters_common::{utils::vote::VoteKind, Canisters};
+
+use super::{BetValidationError, BET_LIMITS};
+use crate::upload::hot_or_not::server_impl::post_allows_bets;
+
+/// Checks the stake against the limits, the post's betting status and the bettor's balance
+async fn validate_bet(
+    canisters: &Canisters<true>,
+    post: PostId,
+    amount: u64,
+) -> Result<(), ServerFnError> {
+    BET_LIMITS.check(amount, None).map_err(ServerFnError::new)?;
+
+    let status = canisters
+        .individual_user(post.0)
+        .await
+        .get_hot_or_not_bet_details_for_this_post(post.1)
+        .await?;
+    if !matches!(status, BettingStatus::BettingOpen { .. }) {
+        return Err(ServerFnError::new(BetValidationError::BettingClosed));
+    }
+    let details = canisters
+        .get_post_details(post.0, post.1)
+        .await?
+        .ok_or_else(|| ServerFnError::new("post not found"))?;
+    let kv: KVStoreImpl = expect_context();
+    if !post_allows_bets(&kv, &details).await? {
+        return Err(ServerFnError::new(BetValidationError::BettingClosed));
+    }
+
+    let balance = canisters
+        .authenticated_user()
+        .await
+        .get_utility_token_balance()
+        .await?;
+    BET_LIMITS
+        .check(amount, Some(balance))
+        .map_err(ServerFnError::new)
+}
+
+pub async fn place_bet(post: PostId, amount: u64, hot: bool) -> Result<(), ServerFnError> {
+    let Some(identity) = extract_identity_impl().await? else {
+        return Err(ServerFnError::new("not logged in"));
+    };
+    let canisters = Canisters::authenticate_with_network(identity, None).await?;
+    validate_bet(&canisters, post, amount).await?;
+
+    let direction = if hot { VoteKind::Hot } else { VoteKind::Not };
+    canisters
+        .vote_on_post(amount, direction, post.1, post.0)
+        .await
+        .map_err(|e| {
+            log::error!("failed to place bet: {e}");
+            ServerFnError::new("Couldn't place your bet, please try again")
+        })?;
+    Ok(())
+}
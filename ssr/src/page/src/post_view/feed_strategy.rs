@@ -0,0 +1,177 @@
+use std::cmp::Reverse;
+
+use codee::string::FromToStringCodec;
+use consts::FEED_STRATEGY_STORE;
+use futures::{stream::FuturesOrdered, StreamExt};
+use indexmap::IndexSet;
+use leptos::prelude::*;
+use leptos_use::use_cookie;
+use priority_queue::DoublePriorityQueue;
+use utils::posts::FetchCursor;
+use yral_canisters_common::{utils::posts::PostDetails, Canisters};
+
+use super::video_iter::{FeedResultType, FetchVideosRes, VideoFetchStream};
+use crate::following::get_following_posts;
+
+/// (batch, Reverse(position in batch)), higher is served first
+pub type FeedPriority = (usize, Reverse<usize>);
+pub type FeedQueue = DoublePriorityQueue<PostDetails, FeedPriority>;
+
+const FEED_CHUNK_SZ: usize = 3;
+const FOLLOWING_CHUNK_SZ: u64 = 10;
+
+/// Owns how the home feed is assembled:
+/// which candidates are fetched, how they are ranked in the priority queue
+/// and how they are interleaved into the video queue
+pub(crate) trait FeedStrategy: Clone + 'static {
+    async fn fetch<'a>(
+        &self,
+        canisters: &'a Canisters<true>,
+        cursor: FetchCursor,
+        allow_nsfw: bool,
+        video_queue: Vec<PostDetails>,
+    ) -> Result<FetchVideosRes<'a>, ServerFnError>;
+
+    /// Priority of the `idx`th candidate of batch `batch`
+    fn score(&self, batch: usize, idx: usize, _post: &PostDetails) -> FeedPriority {
+        (batch, Reverse(idx))
+    }
+
+    /// Move the best ranked candidates from the priority queue to the video queue
+    fn interleave(&self, prio_q: &mut FeedQueue, video_queue: &mut IndexSet<PostDetails>) {
+        let mut cnt = 0;
+        while let Some((next, _)) = prio_q.pop_max() {
+            if video_queue.insert(next) {
+                cnt += 1;
+            }
+            if cnt >= 25 {
+                break;
+            }
+        }
+    }
+
+    /// Backfill the priority queue once it holds less than this many candidates
+    fn backfill_threshold(&self) -> usize {
+        100
+    }
+
+    /// Candidates skip the priority queue while the user is at most
+    /// this many posts away from the end of the video queue
+    fn lookahead(&self) -> usize {
+        25
+    }
+
+    fn advance_cursor(&self, cursor: &mut FetchCursor, _res_type: &FeedResultType) {
+        cursor.advance();
+    }
+}
+
+/// ML feed with a fallback to the ML feed cache
+#[derive(Clone, Copy, Default)]
+pub struct MlHybridFeed;
+
+impl FeedStrategy for MlHybridFeed {
+    async fn fetch<'a>(
+        &self,
+        canisters: &'a Canisters<true>,
+        cursor: FetchCursor,
+        allow_nsfw: bool,
+        video_queue: Vec<PostDetails>,
+    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
+        let mut fetch_stream = VideoFetchStream::new(canisters, cursor);
+        fetch_stream
+            .fetch_post_uids_hybrid(FEED_CHUNK_SZ, allow_nsfw, video_queue)
+            .await
+    }
+
+    fn advance_cursor(&self, cursor: &mut FetchCursor, res_type: &FeedResultType) {
+        if *res_type != FeedResultType::MLFeed {
+            cursor.set_limit(50);
+            cursor.advance_and_set_limit(50);
+        }
+    }
+}
+
+/// Cold start candidates, newest first
+#[derive(Clone, Copy, Default)]
+pub struct ChronologicalFeed;
+
+impl FeedStrategy for ChronologicalFeed {
+    async fn fetch<'a>(
+        &self,
+        canisters: &'a Canisters<true>,
+        mut cursor: FetchCursor,
+        allow_nsfw: bool,
+        video_queue: Vec<PostDetails>,
+    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
+        cursor.set_limit(50);
+        let fetch_stream = VideoFetchStream::new(canisters, cursor);
+        let res = fetch_stream
+            .fetch_post_uids_mlfeed_cache_chunked(FEED_CHUNK_SZ, allow_nsfw, video_queue)
+            .await?;
+
+        let (mut posts, errs): (Vec<_>, Vec<_>) = res
+            .posts_stream
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .partition(|post| post.is_ok());
+        posts.sort_by_key(|post| Reverse(post.as_ref().map(|post| post.created_at).ok()));
+
+        let chunk_stream =
+            futures::stream::iter(errs.into_iter().chain(posts)).chunks(FEED_CHUNK_SZ);
+        Ok(FetchVideosRes {
+            posts_stream: Box::pin(chunk_stream),
+            end: res.end,
+            res_type: res.res_type,
+        })
+    }
+}
+
+/// Latest posts of the creators the user follows, newest first
+/// the merged timeline is built and cached by the server
+#[derive(Clone, Copy, Default)]
+pub struct FollowingFeed;
+
+impl FeedStrategy for FollowingFeed {
+    async fn fetch<'a>(
+        &self,
+        canisters: &'a Canisters<true>,
+        cursor: FetchCursor,
+        _allow_nsfw: bool,
+        video_queue: Vec<PostDetails>,
+    ) -> Result<FetchVideosRes<'a>, ServerFnError> {
+        let start = cursor.start as usize;
+        let (post_ids, end) = get_following_posts(start, start + cursor.limit as usize).await?;
+        let posts_stream = post_ids
+            .into_iter()
+            .filter(|&(canister_id, post_id)| {
+                !video_queue
+                    .iter()
+                    .any(|p| p.canister_id == canister_id && p.post_id == post_id)
+            })
+            .map(|(canister_id, post_id)| canisters.get_post_details(canister_id, post_id))
+            .collect::<FuturesOrdered<_>>()
+            .filter_map(|res| async { res.transpose() })
+            .chunks(FEED_CHUNK_SZ);
+
+        Ok(FetchVideosRes {
+            posts_stream: Box::pin(posts_stream),
+            end,
+            res_type: FeedResultType::PostCache,
+        })
+    }
+
+    fn advance_cursor(&self, cursor: &mut FetchCursor, _res_type: &FeedResultType) {
+        cursor.set_limit(FOLLOWING_CHUNK_SZ);
+        cursor.advance_and_set_limit(FOLLOWING_CHUNK_SZ);
+    }
+}
+
+/// Identifier for the feed strategy experiment, consumed by `abselector!`
+/// kept in a cookie so the server renders the same strategy the client hydrates
+pub fn feed_strategy_identifier() -> Option<String> {
+    let (strategy, _) = use_cookie::<String, FromToStringCodec>(FEED_STRATEGY_STORE);
+    strategy.get_untracked()
+}
@@ -0,0 +1,126 @@
+use component::{modal::Modal, toggle::ToggleWithLabel};
+use leptos::{html::Input, prelude::*};
+use leptos_icons::*;
+use utils::event_streaming::events::auth_canisters_store;
+use yral_canisters_common::utils::posts::PostDetails;
+
+use crate::upload::hot_or_not::{
+    hot_or_not_status, set_hot_or_not_participation, HotOrNotIneligible, HotOrNotStatus,
+};
+
+/// Betting status of a post and its Hot or Not toggle, only rendered for its creator
+#[component]
+pub fn HotOrNotSettings(post: PostDetails) -> impl IntoView {
+    let canister_store = auth_canisters_store();
+    let post = StoredValue::new(post);
+    let is_owner = move || {
+        canister_store.with(|cans| {
+            cans.as_ref().map(|c| c.user_principal())
+                == Some(post.with_value(|p| p.poster_principal))
+        })
+    };
+    // betting can't be added to a post published without it
+    let published_with_hot_or_not = post.with_value(|p| p.hot_or_not_feed_ranking_score.is_some());
+    let show_settings = RwSignal::new(false);
+    let error = RwSignal::new(String::new());
+    let enable_hot_or_not = NodeRef::<Input>::new();
+
+    // only fetched for the creator, nobody else sees it
+    let hot_or_not = LocalResource::new(move || {
+        let owner = is_owner();
+        let (canister_id, post_id) = post.with_value(|p| (p.canister_id, p.post_id));
+        async move {
+            if !owner {
+                return None;
+            }
+            if !published_with_hot_or_not {
+                return Some(HotOrNotStatus::Off);
+            }
+            hot_or_not_status(canister_id, post_id).await.ok()
+        }
+    });
+    let betting_status = move || hot_or_not.get().and_then(|s| s.take());
+    let participating = move || {
+        betting_status()
+            .map(|s| s.participating())
+            .unwrap_or(published_with_hot_or_not)
+    };
+
+    let save: Action<(), (), LocalStorage> = Action::new_unsync(move |&()| {
+        let post_id = post.with_value(|p| p.post_id);
+        let was_participating = untrack(participating);
+        let enabled = enable_hot_or_not
+            .get_untracked()
+            .map(|n| n.checked())
+            .unwrap_or(was_participating);
+        async move {
+            let Some(cans) = canister_store.get_untracked() else {
+                return;
+            };
+            // eligibility and the moderation verdict are checked by the server
+            if enabled != was_participating {
+                let res =
+                    set_hot_or_not_participation(cans.user_principal(), post_id, enabled).await;
+                if let Err(e) = res {
+                    return error.set(e.to_string());
+                }
+            }
+            hot_or_not.refetch();
+            show_settings.set(false);
+        }
+    });
+
+    view! {
+        <Show when=is_owner>
+            <button on:click=move |_| {
+                error.set(String::new());
+                show_settings.set(true);
+            }>
+                <Icon attr:class="drop-shadow-lg" icon=icondata::AiSettingOutlined />
+            </button>
+            {move || {
+                betting_status()
+                    .map(|status| {
+                        view! {
+                            <span class="px-2 py-1 rounded-full bg-black/40 text-xs drop-shadow-lg">
+                                {status.label()}
+                            </span>
+                        }
+                    })
+            }}
+        </Show>
+        <Modal show=show_settings>
+            <div class="flex flex-col gap-4 w-80 md:w-96 text-white text-base">
+                <span class="text-lg font-bold text-center">Hot or Not</span>
+                <Show when=move || error.with(|e| !e.is_empty())>
+                    <span class="text-red-500 text-sm">{error}</span>
+                </Show>
+                <Show
+                    when=move || published_with_hot_or_not
+                    fallback=|| {
+                        view! {
+                            <span class="text-sm text-neutral-300">
+                                {HotOrNotIneligible::PublishedWithout.to_string()}
+                            </span>
+                        }
+                    }
+                >
+                    <ToggleWithLabel
+                        lab="Participate in Hot or Not"
+                        node_ref=enable_hot_or_not
+                        checked=Signal::derive(participating)
+                    />
+                    <button
+                        class="py-2 rounded-full bg-primary-600 font-bold disabled:bg-primary-400"
+                        disabled=move || save.pending().get()
+                        on:click=move |_| {
+                            save.dispatch(());
+                        }
+                    >
+                        Save
+                    </button>
+                </Show>
+            </div>
+        </Modal>
+    }
+}
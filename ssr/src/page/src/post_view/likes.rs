@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use gloo::timers::future::TimeoutFuture;
+use leptos::{prelude::*, task::spawn_local};
+use utils::{event_streaming::events::LikeVideo, send_wrap, types::PostId};
+use yral_canisters_common::{utils::posts::PostDetails, Canisters};
+
+use super::PostDetailsCacheCtx;
+
+/// Rapid taps within this window are sent as a single canister call
+const LIKE_DEBOUNCE_MS: u32 = 400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LikeState {
+    pub liked: bool,
+    pub likes: u64,
+    /// like status last acknowledged by the canister
+    confirmed: bool,
+    /// bumped on every toggle, only the latest toggle syncs
+    generation: u64,
+    /// a canister call is in flight, it syncs again once done if the status changed meanwhile
+    syncing: bool,
+}
+
+impl LikeState {
+    fn toggled(self) -> Self {
+        let liked = !self.liked;
+        let likes = if liked {
+            self.likes + 1
+        } else {
+            self.likes.saturating_sub(1)
+        };
+        Self {
+            liked,
+            likes,
+            generation: self.generation + 1,
+            ..self
+        }
+    }
+
+    fn rolled_back(self) -> Self {
+        if self.liked == self.confirmed {
+            self
+        } else {
+            self.toggled()
+        }
+    }
+}
+
+/// Toggles the like on the canister until it matches the shown status
+/// only one call per post is in flight, toggles made meanwhile are synced once it completes
+async fn sync_like(
+    like_state: RwSignal<HashMap<PostId, LikeState>>,
+    canisters: Canisters<true>,
+    post: PostId,
+) {
+    let started = like_state
+        .try_update(|s| {
+            let state = s.get_mut(&post)?;
+            // the taps cancelled out, or the call in flight will pick this up
+            if state.syncing || state.liked == state.confirmed {
+                return None;
+            }
+            state.syncing = true;
+            Some(())
+        })
+        .flatten();
+    if started.is_none() {
+        return;
+    }
+
+    loop {
+        let individual = canisters.individual_user(post.0).await;
+        let res = send_wrap(individual.update_post_toggle_like_status_by_caller(post.1)).await;
+        let again = like_state
+            .try_update(|s| {
+                let state = s.get_mut(&post)?;
+                match &res {
+                    Ok(_) => state.confirmed = !state.confirmed,
+                    Err(e) => {
+                        log::warn!("Error toggling like status: {:?}", e);
+                        *state = state.rolled_back();
+                    }
+                }
+                state.syncing = res.is_ok() && state.liked != state.confirmed;
+                Some(state.syncing)
+            })
+            .flatten()
+            .unwrap_or_default();
+        if !again {
+            break;
+        }
+    }
+}
+
+impl PostDetailsCacheCtx {
+    pub fn like_state(&self, post: PostId) -> Option<LikeState> {
+        self.like_state.with(|s| s.get(&post).copied())
+    }
+
+    /// Record the like status fetched from the canister
+    /// ignored if the post is already known, the cached state is newer
+    pub fn init_like_state(&self, post: PostId, liked: bool, likes: u64) {
+        self.like_state.update(|s| {
+            s.entry(post).or_insert(LikeState {
+                liked,
+                likes,
+                confirmed: liked,
+                generation: 0,
+                syncing: false,
+            });
+        });
+    }
+
+    /// Optimistically toggle the like status of `post`
+    /// returns the new state, the canister is updated once the taps settle
+    pub fn toggle_like(&self, canisters: Canisters<true>, post: PostId) -> Option<LikeState> {
+        let mut new_state = None;
+        self.like_state.update(|s| {
+            if let Some(state) = s.get_mut(&post) {
+                *state = state.toggled();
+                new_state = Some(*state);
+            }
+        });
+        let generation = new_state?.generation;
+
+        let like_state = self.like_state;
+        spawn_local(async move {
+            TimeoutFuture::new(LIKE_DEBOUNCE_MS).await;
+            let latest = like_state
+                .try_with_untracked(|s| s.get(&post).map(|s| s.generation))
+                .flatten();
+            // a later toggle owns the sync
+            if latest == Some(generation) {
+                sync_like(like_state, canisters, post).await;
+            }
+        });
+
+        new_state
+    }
+
+    /// Toggle the like of `post` as the logged in user, recording likes as events
+    /// `like_only` leaves liked posts untouched, as double tapping does
+    pub fn like_action(
+        &self,
+        canisters: RwSignal<Option<Canisters<true>>>,
+        post: &PostDetails,
+        like_only: bool,
+    ) -> Option<LikeState> {
+        let Some(cans) = canisters.get_untracked() else {
+            log::warn!("Trying to toggle like without auth");
+            return None;
+        };
+        let key = (post.canister_id, post.post_id);
+        if like_only
+            && self
+                .like_state
+                .with_untracked(|s| s.get(&key).map(|s| s.liked))?
+        {
+            return None;
+        }
+        let state = self.toggle_like(cans, key)?;
+        if state.liked {
+            LikeVideo.send_event(post.clone(), RwSignal::new(state.likes), canisters);
+        }
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(liked: bool, likes: u64) -> LikeState {
+        LikeState {
+            liked,
+            likes,
+            confirmed: liked,
+            generation: 0,
+            syncing: false,
+        }
+    }
+
+    #[test]
+    fn toggles_update_the_count_and_roll_back_to_confirmed() {
+        let liked = state(false, 3).toggled();
+        assert!(liked.liked);
+        assert_eq!(liked.likes, 4);
+        assert_eq!(liked.generation, 1);
+        assert_eq!(
+            liked.rolled_back(),
+            LikeState {
+                generation: 2,
+                ..state(false, 3)
+            }
+        );
+
+        let twice = liked.toggled();
+        assert_eq!((twice.liked, twice.likes), (false, 3));
+        // already matches the canister
+        assert_eq!(twice.rolled_back(), twice);
+
+        assert_eq!(state(true, 0).toggled().likes, 0);
+    }
+}
@@ -1,16 +1,24 @@
 mod bet;
 pub mod error;
+pub(crate) mod feed_strategy;
+pub mod hot_or_not_settings;
+pub mod likes;
 pub mod overlay;
+pub mod prefetch;
 pub mod single_post;
 pub mod video_iter;
 pub mod video_loader;
-use crate::scrolling_post_view::ScrollingPostView;
+use crate::{
+    following::FollowedPostsFeed, reports::use_my_reports, scrolling_post_view::ScrollingPostView,
+};
 use component::spinner::FullScreenSpinner;
 use consts::NSFW_TOGGLE_STORE;
+use feed_strategy::{
+    feed_strategy_identifier, ChronologicalFeed, FeedQueue, FeedStrategy, MlHybridFeed,
+};
 use indexmap::IndexSet;
-use priority_queue::DoublePriorityQueue;
 use state::canisters::{authenticated_canisters, unauth_canisters};
-use std::{cmp::Reverse, collections::HashMap};
+use std::collections::{HashMap, HashSet};
 use yral_types::post::PostItem;
 
 use candid::Principal;
@@ -23,10 +31,10 @@ use leptos_router::{
 };
 use leptos_use::{storage::use_local_storage, use_debounce_fn};
 use utils::{
-    posts::FetchCursor, route::failure_redirect, send_wrap, try_or_redirect, types::PostId,
+    abselector, posts::FetchCursor, route::failure_redirect, send_wrap, try_or_redirect,
+    types::PostId,
 };
 
-use video_iter::{FeedResultType, VideoFetchStream};
 use yral_canisters_common::{utils::posts::PostDetails, Canisters};
 
 #[derive(Params, PartialEq, Clone, Copy)]
@@ -51,13 +59,48 @@ pub struct PostViewCtx {
     video_queue: RwSignal<IndexSet<PostDetails>>,
     current_idx: RwSignal<usize>,
     queue_end: RwSignal<bool>,
-    priority_q: RwSignal<DoublePriorityQueue<PostDetails, (usize, Reverse<usize>)>>, // we are using DoublePriorityQueue for GC in the future through pop_min
+    priority_q: RwSignal<FeedQueue>, // we are using DoublePriorityQueue for GC in the future through pop_min
     batch_cnt: RwSignal<usize>,
 }
 
-#[derive(Clone, Default)]
+impl PostViewCtx {
+    /// Drop `post` from the upcoming posts, the current post stays in place
+    pub fn hide_post(&self, post: PostId) {
+        let current_idx = self.current_idx.get_untracked();
+        self.video_queue.update(|vq| {
+            if let Some(idx) = vq
+                .iter()
+                .position(|p| (p.canister_id, p.post_id) == post)
+                .filter(|idx| *idx > current_idx)
+            {
+                vq.shift_remove_index(idx);
+            }
+        });
+        self.priority_q.update(|pq| {
+            pq.retain(|p, _| (p.canister_id, p.post_id) != post);
+        });
+    }
+
+    /// Drop the upcoming posts of `creator`
+    pub fn hide_creator(&self, creator: Principal) {
+        let current_idx = self.current_idx.get_untracked();
+        self.video_queue.update(|vq| {
+            let mut idx = 0;
+            vq.retain(|p| {
+                idx += 1;
+                idx - 1 <= current_idx || p.poster_principal != creator
+            });
+        });
+        self.priority_q.update(|pq| {
+            pq.retain(|p, _| p.poster_principal != creator);
+        });
+    }
+}
+
+#[derive(Clone, Copy, Default)]
 pub struct PostDetailsCacheCtx {
     pub post_details: RwSignal<HashMap<PostId, PostItem>>,
+    pub like_state: RwSignal<HashMap<PostId, likes::LikeState>>,
 }
 
 #[component]
@@ -65,6 +108,9 @@ pub fn CommonPostViewWithUpdates<S: Storage<ArcAction<(), ()>>>(
     initial_post: Option<PostDetails>,
     fetch_video_action: Action<(), (), S>,
     threshold_trigger_fetch: usize,
+    /// keep the route in sync with the current post
+    #[prop(default = true)]
+    sync_url: bool,
 ) -> impl IntoView {
     let PostViewCtx {
         fetch_cursor,
@@ -120,6 +166,9 @@ pub fn CommonPostViewWithUpdates<S: Storage<ArcAction<(), ()>>>(
     });
 
     Effect::new(move || {
+        if !sync_url {
+            return;
+        }
         let Some((canister_id, post_id)) = current_post_base() else {
             return;
         };
@@ -143,7 +192,11 @@ pub fn CommonPostViewWithUpdates<S: Storage<ArcAction<(), ()>>>(
 }
 
 #[component]
-pub fn PostViewWithUpdatesMLFeed(initial_post: Option<PostDetails>) -> impl IntoView {
+pub(crate) fn PostViewWithUpdatesFeed<F: FeedStrategy>(
+    initial_post: Option<PostDetails>,
+    strategy: F,
+    #[prop(default = true)] sync_url: bool,
+) -> impl IntoView {
     let PostViewCtx {
         fetch_cursor,
         video_queue,
@@ -158,26 +211,17 @@ pub fn PostViewWithUpdatesMLFeed(initial_post: Option<PostDetails>) -> impl Into
 
     let fetch_video_action: Action<_, _, LocalStorage> = Action::new_local(move |_| {
         let auth_cans = auth_cans;
+        let strategy = strategy.clone();
         let (nsfw_enabled, _, _) = use_local_storage::<bool, FromToStringCodec>(NSFW_TOGGLE_STORE);
+        let (my_reports, _) = use_my_reports();
         async move {
-            {
-                let mut prio_q = priority_q.write();
-                let mut cnt = 0;
-                while let Some((next, _)) = prio_q.pop_max() {
-                    video_queue.update(|vq| {
-                        if vq.insert(next) {
-                            cnt += 1;
-                        }
-                    });
-                    if cnt >= 25 {
-                        break;
-                    }
-                }
-            }
+            priority_q.update(|prio_q| {
+                video_queue.update(|vq| strategy.interleave(prio_q, vq));
+            });
 
-            // backfill PQ from ML feed server
+            // backfill PQ from the feed strategy
             // fetch to video_queue based on threshold
-            if priority_q.with_untracked(|q| q.len()) < 100 {
+            if priority_q.with_untracked(|q| q.len()) < strategy.backfill_threshold() {
                 let Some(cursor) = fetch_cursor.try_get_untracked() else {
                     return;
                 };
@@ -191,32 +235,38 @@ pub fn PostViewWithUpdatesMLFeed(initial_post: Option<PostDetails>) -> impl Into
                 let canisters = auth_cans.await;
                 let cans_true = Canisters::from_wire(canisters.unwrap(), expect_context()).unwrap();
 
-                let mut fetch_stream = VideoFetchStream::new(&cans_true, cursor);
-                let chunks = fetch_stream
-                    .fetch_post_uids_hybrid(
-                        3,
+                let chunks = strategy
+                    .fetch(
+                        &cans_true,
+                        cursor,
                         nsfw_enabled,
                         video_queue.get_untracked().iter().cloned().collect(),
                     )
                     .await;
 
                 let res = try_or_redirect!(chunks);
+                let reported: HashSet<PostId> =
+                    my_reports.with_untracked(|reports| reports.iter().map(|r| r.post()).collect());
                 let mut chunks = res.posts_stream;
                 let mut cnt = 0usize;
                 while let Some(chunk) = chunks.next().await {
                     for uid in chunk {
                         let post_detail = try_or_redirect!(uid);
+                        if reported.contains(&(post_detail.canister_id, post_detail.post_id)) {
+                            continue;
+                        }
                         if video_queue
                             .with_untracked(|vq| vq.len())
                             .saturating_sub(current_idx.get_untracked())
-                            <= 25
+                            <= strategy.lookahead()
                         {
                             video_queue.update(|vq| {
                                 let _ = vq.insert(post_detail);
                             });
                         } else {
+                            let priority = strategy.score(batch_cnt_val, cnt, &post_detail);
                             priority_q.update(|pq| {
-                                pq.push(post_detail, (batch_cnt_val, Reverse(cnt)));
+                                pq.push(post_detail, priority);
                             });
                         }
                         cnt += 1;
@@ -224,12 +274,7 @@ pub fn PostViewWithUpdatesMLFeed(initial_post: Option<PostDetails>) -> impl Into
                 }
 
                 leptos::logging::log!("feed type: {:?} cnt {}", res.res_type, cnt); // For debugging purposes
-                if res.res_type != FeedResultType::MLFeed {
-                    fetch_cursor.try_update(|c| {
-                        c.set_limit(50);
-                        c.advance_and_set_limit(50)
-                    });
-                }
+                fetch_cursor.try_update(|c| strategy.advance_cursor(c, &res.res_type));
 
                 if res.end {
                     queue_end.try_set(res.end);
@@ -240,7 +285,32 @@ pub fn PostViewWithUpdatesMLFeed(initial_post: Option<PostDetails>) -> impl Into
         }
     });
 
-    view! { <CommonPostViewWithUpdates initial_post fetch_video_action threshold_trigger_fetch=50 /> }.into_any()
+    view! { <CommonPostViewWithUpdates initial_post fetch_video_action threshold_trigger_fetch=50 sync_url /> }.into_any()
+}
+
+type FeedView = Box<dyn FnOnce() -> AnyView>;
+
+#[component]
+fn PostViewWithUpdates(initial_post: Option<PostDetails>) -> impl IntoView {
+    let post = initial_post.clone();
+    let component_ml_hybrid: FeedView = Box::new(move || {
+        view! { <PostViewWithUpdatesFeed initial_post=post strategy=MlHybridFeed /> }.into_any()
+    });
+    let post = initial_post.clone();
+    let component_chronological: FeedView = Box::new(move || {
+        view! { <PostViewWithUpdatesFeed initial_post=post strategy=ChronologicalFeed /> }
+            .into_any()
+    });
+    let component_following: FeedView = Box::new(move || {
+        view! { <FollowedPostsFeed initial_post fallback_to_default=true /> }.into_any()
+    });
+
+    abselector!(
+        feed_strategy_identifier,
+        component_ml_hybrid,
+        component_chronological,
+        component_following
+    )()
 }
 
 #[component]
@@ -308,7 +378,7 @@ pub fn PostView() -> impl IntoView {
         <Suspense fallback=FullScreenSpinner>
             {move || Suspend::new(async move {
                 let initial_post = fetch_first_video_uid.await.ok()?;
-                { Some(view! { <PostViewWithUpdatesMLFeed initial_post /> }.into_any()) }
+                { Some(view! { <PostViewWithUpdates initial_post /> }.into_any()) }
             })}
         </Suspense>
     }
@@ -1,114 +1,89 @@
 use codee::string::FromToStringCodec;
 use component::buttons::HighlightedButton;
-use component::{
-    canisters_prov::with_cans, hn_icons::HomeFeedShareIcon, modal::Modal, option::SelectOption,
-};
+use component::{canisters_prov::with_cans, hn_icons::HomeFeedShareIcon, modal::Modal};
 
 use consts::NSFW_TOGGLE_STORE;
 use gloo::timers::callback::Timeout;
-use leptos::{prelude::*, task::spawn_local};
+use leptos::prelude::*;
 use leptos_icons::*;
 use leptos_use::storage::use_local_storage;
 use leptos_use::use_window;
 use utils::event_streaming::events::auth_canisters_store;
 use utils::host::show_nsfw_content;
 use utils::{
-    event_streaming::events::{LikeVideo, ShareVideo},
-    report::ReportOption,
+    event_streaming::events::ShareVideo,
     route::failure_redirect,
     send_wrap,
-    user::UserDetails,
     web::{copy_to_clipboard, share_url},
 };
 use yral_canisters_common::{utils::posts::PostDetails, Canisters};
 
-use super::bet::HNGameOverlay;
+use super::{bet::HNGameOverlay, hot_or_not_settings::HotOrNotSettings, PostDetailsCacheCtx};
+use crate::blocking::CreatorActionsButton;
+use crate::comments::CommentSheet;
+use crate::following::{use_follow_info, FollowButton, FollowedCreator};
+use crate::hashtag::{hashtag_url, normalize_hashtag, split_hashtags, TextSegment};
+use crate::reports::ReportPostModal;
 
 #[component]
 fn LikeAndAuthCanLoader(post: PostDetails) -> impl IntoView {
-    let likes = RwSignal::new(post.likes);
+    let post_details_cache: PostDetailsCacheCtx = expect_context();
+    let post_key = (post.canister_id, post.post_id);
+    let like_state = move || post_details_cache.like_state(post_key);
 
-    let liked = RwSignal::new(None::<bool>);
+    let initial_liked = (post.liked_by_user, post.likes);
+    let likes = move || like_state().map(|s| s.likes).unwrap_or(initial_liked.1);
     let icon_name = Signal::derive(move || {
-        if liked().unwrap_or_default() {
+        if like_state().map(|s| s.liked).unwrap_or_default() {
             "/img/heart-icon-liked.svg"
         } else {
             "/img/heart-icon-white.svg"
         }
     });
 
-    let post_canister = post.canister_id;
-    let post_id = post.post_id;
-    let initial_liked = (post.liked_by_user, post.likes);
     let canisters = auth_canisters_store();
+    let post = StoredValue::new(post);
 
-    let like_toggle = Action::new(move |&()| {
-        let post_details = post.clone();
-        let canister_store = canisters;
-
-        async move {
-            let Some(canisters) = canisters.get_untracked() else {
-                log::warn!("Trying to toggle like without auth");
-                return;
-            };
-
-            let should_like = {
-                let mut liked_w = liked.write();
-                let current = liked_w.unwrap_or_default();
-                *liked_w = Some(!current);
-                !current
-            };
-
-            if should_like {
-                likes.update(|l| *l += 1);
-                LikeVideo.send_event(post_details, likes, canister_store);
-            } else {
-                likes.update(|l| *l -= 1);
-            }
-
-            let individual = canisters.individual_user(post_canister).await;
-            match send_wrap(individual.update_post_toggle_like_status_by_caller(post_id)).await {
-                Ok(_) => (),
-                Err(e) => {
-                    log::warn!("Error toggling like status: {:?}", e);
-                    liked.update(|l| _ = l.as_mut().map(|l| *l = !*l));
-                }
-            }
-        }
-    });
+    let like_toggle = move || {
+        post.with_value(|post| post_details_cache.like_action(canisters, post, false));
+    };
 
     let liked_fetch = with_cans(move |cans: Canisters<true>| {
         send_wrap(async move {
+            if post_details_cache
+                .like_state
+                .with_untracked(|s| s.contains_key(&post_key))
+            {
+                return Ok(None);
+            }
             let result = if let Some(liked) = initial_liked.0 {
                 (liked, initial_liked.1)
             } else {
-                match cans.post_like_info(post_canister, post_id).await {
+                match cans.post_like_info(post_key.0, post_key.1).await {
                     Ok(liked) => liked,
                     Err(e) => {
                         failure_redirect(e);
-                        (false, likes.try_get_untracked().unwrap_or_default())
+                        (false, initial_liked.1)
                     }
                 }
             };
-            Ok::<_, ServerFnError>(result)
+            Ok::<_, ServerFnError>(Some(result))
         })
     });
 
     view! {
         <div class="flex flex-col gap-1 items-center">
-            <button
-                on:click=move |_| {like_toggle.dispatch(());}
-            >
+            <button on:click=move |_| like_toggle()>
                 <img src=icon_name style="width: 1em; height: 1em;" />
             </button>
             <span class="text-xs md:text-sm">{likes}</span>
             <Suspense>
                 {move || Suspend::new(async move {
                     match liked_fetch.await {
-                        Ok(res) => {
-                            likes.set(res.1);
-                            liked.set(Some(res.0))
-                        },
+                        Ok(Some((liked, likes))) => {
+                            post_details_cache.init_like_state(post_key, liked, likes)
+                        }
+                        Ok(None) => (),
                         Err(e) => {
                             log::warn!("failed to fetch like status {e}");
                         }
@@ -124,7 +99,6 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
     let show_share = RwSignal::new(false);
     let show_report = RwSignal::new(false);
     let show_nsfw_permission = RwSignal::new(false);
-    let report_option = RwSignal::new(ReportOption::Nudity.as_str().to_string());
     let show_copied_popup = RwSignal::new(false);
     let base_url = || {
         use_window()
@@ -137,9 +111,9 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
             .unwrap_or_default()
     };
 
+    let post_settings = post.clone();
     let post_details_share = post.clone();
     let canisters = auth_canisters_store();
-    let canisters_copy = canisters;
 
     let share = move || {
         let post_details = post_details_share.clone();
@@ -152,7 +126,14 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
     };
 
     let profile_url = format!("/profile/{}/tokens", post.poster_principal.to_text());
+    let follow_info = use_follow_info(post.poster_principal);
+    let creator = FollowedCreator {
+        principal: post.poster_principal,
+        canister: post.canister_id,
+    };
     let post_c = post.clone();
+    let post_comments = post.clone();
+    let show_comments = RwSignal::new(false);
 
     let click_copy = move |text: String| {
         _ = copy_to_clipboard(&text);
@@ -160,34 +141,7 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
         Timeout::new(1200, move || show_copied_popup.set(false)).forget();
     };
 
-    let post_details_report = post.clone();
-    let click_report = Action::new(move |()| {
-        #[cfg(feature = "ga4")]
-        {
-            use utils::report::send_report_offchain;
-
-            let post_details = post_details_report.clone();
-            let user_details = UserDetails::try_get_from_canister_store(canisters_copy).unwrap();
-
-            spawn_local(async move {
-                send_report_offchain(
-                    user_details.details.principal.to_string(),
-                    post_details.poster_principal.to_string(),
-                    post_details.canister_id.to_string(),
-                    post_details.post_id.to_string(),
-                    post_details.uid,
-                    report_option.get_untracked(),
-                    video_url(),
-                )
-                .await
-                .unwrap();
-            });
-        }
-
-        async move {
-            show_report.set(false);
-        }
-    });
+    let post_report = post.clone();
 
     let (nsfw_enabled, set_nsfw_enabled, _) =
         use_local_storage::<bool, FromToStringCodec>(NSFW_TOGGLE_STORE);
@@ -238,6 +192,7 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
                             <span class="font-semibold truncate">
                                 <a href=profile_url>{post.display_name}</a>
                             </span>
+                            <FollowButton creator info=follow_info />
                             <span class="font-semibold">"|"</span>
                             <span class="flex flex-row gap-1 items-center">
                                 <Icon
@@ -247,7 +202,7 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
                                 {post.views}
                             </span>
                         </div>
-                        <ExpandableText description=post.description />
+                        <ExpandableText description=post.description hashtags=post.hastags />
                     </div>
                 </div>
                 <button class="pointer-events-auto py-2">
@@ -261,6 +216,8 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
             </div>
             <div class="flex flex-col gap-2 w-full">
                 <div class="flex flex-col pointer-events-auto gap-6 self-end items-end text-2xl md:text-3xl lg:text-4xl">
+                    <CreatorActionsButton creator=post.poster_principal class="" />
+                    <HotOrNotSettings post=post_settings />
                     <button on:click=move |_| show_report.set(true)>
                         <Icon attr:class="drop-shadow-lg" icon=icondata::TbMessageReport />
                     </button>
@@ -268,6 +225,9 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
                         <Icon attr:class="drop-shadow-lg" icon=icondata::AiGiftFilled />
                     </a>
                     <LikeAndAuthCanLoader post=post_c.clone() />
+                    <button on:click=move |_| show_comments.set(true)>
+                        <Icon attr:class="drop-shadow-lg" icon=icondata::BiCommentDotsRegular />
+                    </button>
                     <button on:click=move |_| share()>
                         <Icon attr:class="drop-shadow-lg" icon=HomeFeedShareIcon />
                     </button>
@@ -298,46 +258,8 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
                 </div>
             </Show>
         </Modal>
-        <Modal show=show_report>
-            <div class="flex flex-col justify-center items-center gap-4 text-white">
-                <span class="text-lg">Report Post</span>
-                <span class="text-lg">Please select a reason:</span>
-                <div class="max-w-full text-md text-black">
-                    <select
-                        class="p-2 w-full block rounded-lg text-sm"
-                        on:change=move |ev| {
-                            let new_value = event_target_value(&ev);
-                            report_option.set(new_value);
-                        }
-                    >
-
-                        <SelectOption
-                            value=report_option.read_only()
-                            is=format!("{}", ReportOption::Nudity.as_str())
-                        />
-                        <SelectOption
-                            value=report_option.read_only()
-                            is=format!("{}", ReportOption::Violence.as_str())
-                        />
-                        <SelectOption
-                            value=report_option.read_only()
-                            is=format!("{}", ReportOption::Offensive.as_str())
-                        />
-                        <SelectOption
-                            value=report_option.read_only()
-                            is=format!("{}", ReportOption::Spam.as_str())
-                        />
-                        <SelectOption
-                            value=report_option.read_only()
-                            is=format!("{}", ReportOption::Other.as_str())
-                        />
-                    </select>
-                </div>
-                <button on:click=move |_| {click_report.dispatch(());}>
-                    <div class="rounded-lg bg-pink-500 p-1">Submit</div>
-                </button>
-            </div>
-        </Modal>
+        <ReportPostModal post=post_report show=show_report />
+        <CommentSheet post=post_comments show=show_comments />
         <Modal show=show_nsfw_permission>
             <div class="flex flex-col justify-center items-center gap-4 text-white">
                 <img class="h-32 w-32 object-contain" src="/img/yral/nsfw/nsfw-modal-logo.svg" />
@@ -360,9 +282,39 @@ pub fn VideoDetailsOverlay(post: PostDetails) -> impl IntoView {
 }
 
 #[component]
-fn ExpandableText(description: String) -> impl IntoView {
+fn ExpandableText(description: String, hashtags: Vec<String>) -> impl IntoView {
     let truncated = RwSignal::new(true);
 
+    let segments = split_hashtags(&description);
+    let in_description = segments
+        .iter()
+        .filter_map(|s| match s {
+            TextSegment::Hashtag(h) => Some(normalize_hashtag(h)),
+            TextSegment::Text(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let extra_hashtags = hashtags
+        .into_iter()
+        .filter(|h| !in_description.contains(&normalize_hashtag(h)))
+        .map(TextSegment::Hashtag);
+    let segments = segments
+        .into_iter()
+        .chain(extra_hashtags.flat_map(|h| [TextSegment::Text(" ".into()), h]))
+        .map(|segment| match segment {
+            TextSegment::Text(text) => text.into_any(),
+            TextSegment::Hashtag(hashtag) => view! {
+                <a
+                    class="font-semibold"
+                    href=hashtag_url(&hashtag)
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    {format!("#{hashtag}")}
+                </a>
+            }
+            .into_any(),
+        })
+        .collect_view();
+
     view! {
         <span
             class="text-xs md:text-sm lg:text-base w-full"
@@ -370,7 +322,7 @@ fn ExpandableText(description: String) -> impl IntoView {
 
             on:click=move |_| truncated.update(|e| *e = !*e)
         >
-            {description}
+            {segments}
         </span>
     }
 }
@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use codee::string::FromToStringCodec;
+use consts::DATA_SAVER_STORE;
+use futures::future::{AbortHandle, Abortable};
+use gloo::timers::future::TimeoutFuture;
+use indexmap::IndexSet;
+use leptos::{prelude::*, task::spawn_local};
+use leptos_use::storage::use_local_storage;
+use reqwest::{header::RANGE, Client};
+use utils::mp4_url;
+use yral_canisters_common::utils::posts::PostDetails;
+
+/// Prefetching only starts once the user settles on a post,
+/// fast swipes cancel the pending work
+const PREFETCH_DEBOUNCE_MS: u32 = 350;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrefetchPlan {
+    /// Number of upcoming posts to warm
+    pub posts: usize,
+    /// Size of the head of each post's mp4 download that is warmed
+    pub bytes: usize,
+}
+
+impl PrefetchPlan {
+    pub const OFF: Self = Self { posts: 0, bytes: 0 };
+
+    /// Plan for the given `navigator.connection.effectiveType`
+    pub fn for_network(effective_type: Option<&str>) -> Self {
+        match effective_type {
+            Some("slow-2g" | "2g") => Self::OFF,
+            Some("3g") => Self {
+                posts: 1,
+                bytes: 256 * 1024,
+            },
+            _ => Self {
+                posts: 3,
+                bytes: 512 * 1024,
+            },
+        }
+    }
+}
+
+/// `saveData` and `effectiveType` from the Network Information API
+/// defaults to (false, None) if the API is not available
+fn network_info() -> (bool, Option<String>) {
+    #[cfg(feature = "hydrate")]
+    {
+        use wasm_bindgen::JsValue;
+        use web_sys::js_sys::Reflect;
+
+        let Some(nav) = leptos_use::use_window().navigator() else {
+            return (false, None);
+        };
+        let Ok(conn) = Reflect::get(&nav.into(), &JsValue::from_str("connection")) else {
+            return (false, None);
+        };
+        if conn.is_undefined() || conn.is_null() {
+            return (false, None);
+        }
+        let save_data = Reflect::get(&conn, &JsValue::from_str("saveData"))
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default();
+        let effective_type = Reflect::get(&conn, &JsValue::from_str("effectiveType"))
+            .ok()
+            .and_then(|v| v.as_string());
+        (save_data, effective_type)
+    }
+
+    #[cfg(not(feature = "hydrate"))]
+    {
+        (false, None)
+    }
+}
+
+/// Pull the head of the mp4 download into the HTTP cache,
+/// the player plays the mp4 so nothing else is fetched
+async fn warm_post(client: &Client, uid: &str, plan: PrefetchPlan) -> Option<()> {
+    client
+        .get(mp4_url(uid))
+        .header(RANGE, format!("bytes=0-{}", plan.bytes - 1))
+        .send()
+        .await
+        .ok()?
+        .bytes()
+        .await
+        .ok()?;
+
+    Some(())
+}
+
+/// Warms the posts following `current_idx` in `video_queue`
+/// Respects the data saver setting and the network conditions
+pub fn use_prefetch(video_queue: RwSignal<IndexSet<PostDetails>>, current_idx: RwSignal<usize>) {
+    let (data_saver, _, _) = use_local_storage::<bool, FromToStringCodec>(DATA_SAVER_STORE);
+    let pending = StoredValue::new(None::<AbortHandle>);
+    let warmed = StoredValue::new(HashSet::<String>::new());
+    let queue_len = Memo::new(move |_| video_queue.with(|q| q.len()));
+
+    Effect::new(move |_| {
+        let idx = current_idx();
+        queue_len.track();
+        pending.update_value(|pending| {
+            if let Some(handle) = pending.take() {
+                handle.abort();
+            }
+        });
+
+        let (save_data, effective_type) = network_info();
+        let plan = if save_data || data_saver.get_untracked() {
+            PrefetchPlan::OFF
+        } else {
+            PrefetchPlan::for_network(effective_type.as_deref())
+        };
+
+        let uids = video_queue.with_untracked(|q| {
+            q.iter()
+                .skip(idx + 1)
+                .take(plan.posts)
+                .map(|post| post.uid.clone())
+                .filter(|uid| !warmed.with_value(|warmed| warmed.contains(uid)))
+                .collect::<Vec<_>>()
+        });
+        if uids.is_empty() {
+            return;
+        }
+
+        let (handle, registration) = AbortHandle::new_pair();
+        pending.set_value(Some(handle));
+        let prefetch = async move {
+            TimeoutFuture::new(PREFETCH_DEBOUNCE_MS).await;
+            let client = Client::new();
+            for uid in uids {
+                // a post that failed to warm isn't retried on every swipe
+                if warm_post(&client, &uid, plan).await.is_none() {
+                    log::debug!("failed to prefetch {uid}");
+                }
+                warmed.update_value(|warmed| {
+                    warmed.insert(uid);
+                });
+            }
+        };
+        spawn_local(async move {
+            _ = Abortable::new(prefetch, registration).await;
+        });
+    });
+}
@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use std::{collections::HashSet, pin::Pin};
 
 use candid::Principal;
 use codee::string::JsonSerdeCodec;
@@ -18,7 +18,10 @@ use utils::{
 };
 use yral_canisters_common::{utils::posts::PostDetails, Canisters, Error as CanistersError};
 
-type PostsStream<'a> = Pin<Box<dyn Stream<Item = Vec<Result<PostDetails, CanistersError>>> + 'a>>;
+use crate::blocking::HiddenCreatorsCtx;
+
+pub type PostsStream<'a> =
+    Pin<Box<dyn Stream<Item = Vec<Result<PostDetails, CanistersError>>> + 'a>>;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum FeedResultType {
@@ -34,6 +37,13 @@ pub struct FetchVideosRes<'a> {
     pub res_type: FeedResultType,
 }
 
+/// Drops posts of creators blocked or muted by the user
+fn is_visible(hidden: &HashSet<Principal>, res: &Result<PostDetails, CanistersError>) -> bool {
+    res.as_ref()
+        .map(|post| !hidden.contains(&post.poster_principal))
+        .unwrap_or(true)
+}
+
 pub struct VideoFetchStream<'a, const AUTH: bool> {
     canisters: &'a Canisters<AUTH>,
     cursor: FetchCursor,
@@ -52,6 +62,7 @@ impl<'a, const AUTH: bool> VideoFetchStream<'a, AUTH> {
     ) -> Result<FetchVideosRes<'a>, ServerFnError> {
         let (user_canister_id_local_storage, _, _) =
             use_local_storage::<Option<Principal>, JsonSerdeCodec>(USER_CANISTER_ID_STORE);
+        let hidden_creators = use_context::<HiddenCreatorsCtx>().unwrap_or_default();
         let user_canister_id;
         if let Some(canister_id) = user_canister_id_local_storage.get_untracked() {
             user_canister_id = canister_id;
@@ -70,6 +81,7 @@ impl<'a, const AUTH: bool> VideoFetchStream<'a, AUTH> {
             user_canister_id = cans.user_canister();
         }
 
+        let hidden = hidden_creators.load().await.post_filter();
         let show_nsfw = allow_nsfw || show_nsfw_content();
         let top_posts = if show_nsfw {
             get_ml_feed_nsfw(
@@ -90,17 +102,21 @@ impl<'a, const AUTH: bool> VideoFetchStream<'a, AUTH> {
         };
 
         let end = false;
+        let canisters = self.canisters;
         let chunk_stream = top_posts
             .into_iter()
             .map(move |item| {
-                self.canisters.get_post_details_with_nsfw_info(
+                canisters.get_post_details_with_nsfw_info(
                     item.canister_id,
                     item.post_id,
                     item.nsfw_probability,
                 )
             })
             .collect::<FuturesOrdered<_>>()
-            .filter_map(|res| async { res.transpose() })
+            .filter_map(move |res| {
+                let res = res.transpose().filter(|res| is_visible(&hidden, res));
+                async { res }
+            })
             .chunks(chunks);
 
         Ok(FetchVideosRes {
@@ -121,6 +137,11 @@ impl<'a> VideoFetchStream<'a, true> {
         let cans_true = self.canisters;
 
         let user_canister_id = cans_true.user_canister();
+        let hidden = use_context::<HiddenCreatorsCtx>()
+            .unwrap_or_default()
+            .load()
+            .await
+            .post_filter();
 
         let show_nsfw = allow_nsfw || show_nsfw_content();
         let top_posts = if show_nsfw {
@@ -142,17 +163,21 @@ impl<'a> VideoFetchStream<'a, true> {
         };
 
         let end = false;
+        let canisters = self.canisters;
         let chunk_stream = top_posts
             .into_iter()
             .map(move |item| {
-                self.canisters.get_post_details_with_nsfw_info(
+                canisters.get_post_details_with_nsfw_info(
                     item.canister_id,
                     item.post_id,
                     item.nsfw_probability,
                 )
             })
             .collect::<FuturesOrdered<_>>()
-            .filter_map(|res| async { res.transpose() })
+            .filter_map(move |res| {
+                let res = res.transpose().filter(|res| is_visible(&hidden, res));
+                async { res }
+            })
             .chunks(chunks);
 
         Ok(FetchVideosRes {
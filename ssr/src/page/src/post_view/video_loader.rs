@@ -1,26 +1,33 @@
 use std::cmp::Ordering;
 
 use codee::string::FromToStringCodec;
+use gloo::timers::callback::Timeout;
 use indexmap::IndexSet;
 use leptos::ev;
 use leptos::{html::Video, prelude::*};
+use leptos_router::hooks::use_navigate;
 use leptos_use::storage::use_local_storage;
 use leptos_use::use_event_listener;
-use state::canisters::unauth_canisters;
+use state::{app_state::AppState, audio_state::AudioState, canisters::unauth_canisters};
 use utils::send_wrap;
 use yral_canisters_client::individual_user_template::PostViewDetailsFromFrontend;
 
-use crate::post_view::BetEligiblePostCtx;
+use crate::{post_view::BetEligiblePostCtx, reports::ReportVideoCtx};
 use component::show_any::ShowAny;
 use component::{
-    feed_popup::FeedPopUp, onboarding_flow::OnboardingPopUp, video_player::VideoPlayer,
+    feed_popup::FeedPopUp,
+    gestures::{Gesture, Side},
+    onboarding_flow::OnboardingPopUp,
+    video_player::VideoPlayer,
 };
 use consts::USER_ONBOARDING_STORE;
 use state::local_storage::use_referrer_store;
-use utils::event_streaming::events::VideoWatched;
+use utils::event_streaming::events::{auth_canisters_store, VideoWatched};
 use utils::{bg_url, event_streaming::events::account_connected_reader, mp4_url};
 
-use super::{overlay::VideoDetailsOverlay, PostDetails};
+use super::{overlay::VideoDetailsOverlay, PostDetails, PostDetailsCacheCtx};
+
+const HEART_ANIMATION_MS: u32 = 800;
 
 #[component]
 pub fn BgView(
@@ -40,6 +47,7 @@ pub fn BgView(
 
     let onboarding_eligible_post_context = BetEligiblePostCtx::default();
     provide_context(onboarding_eligible_post_context.clone());
+    provide_context(ReportVideoCtx(NodeRef::new()));
 
     let (show_onboarding_popup, set_show_onboarding_popup) = signal(false);
     let (is_onboarded, set_onboarded, _) =
@@ -212,11 +220,72 @@ pub fn VideoView(
 
     VideoWatched.send_event(post, _ref);
 
+    let post_details_cache: PostDetailsCacheCtx = expect_context();
+    let canisters = auth_canisters_store();
+    let seek_secs = expect_context::<AppState>().gestures.side_tap_seek_secs;
+    let navigate = use_navigate();
+    let hearts = RwSignal::new(Vec::<(u64, f64, f64)>::new());
+
+    let on_gesture = Callback::new(move |gesture| {
+        let video = _ref.get_untracked();
+        match gesture {
+            Gesture::Tap => AudioState::toggle_mute(),
+            Gesture::DoubleTap { x, y } => {
+                let id = hearts.with_untracked(|h| h.last().map(|h| h.0 + 1).unwrap_or_default());
+                hearts.update(|h| h.push((id, x, y)));
+                Timeout::new(HEART_ANIMATION_MS, move || {
+                    hearts.try_update(|h| h.retain(|heart| heart.0 != id));
+                })
+                .forget();
+                post.with_untracked(|post| {
+                    post.as_ref()
+                        .map(|post| post_details_cache.like_action(canisters, post, true))
+                });
+            }
+            Gesture::SideTap(side) => {
+                let Some(video) = video else {
+                    return;
+                };
+                let current_time = video.current_time();
+                let target = match side {
+                    Side::Left => (current_time - seek_secs).max(0.0),
+                    Side::Right => (current_time + seek_secs).min(video.duration()),
+                };
+                video.set_current_time(target);
+            }
+            Gesture::LongPressStart => _ = video.map(|v| v.pause()),
+            Gesture::LongPressEnd => _ = video.map(|v| v.play()),
+            Gesture::SwipeLeft => {
+                if let Some(principal) =
+                    post.with_untracked(|p| p.as_ref().map(|p| p.poster_principal))
+                {
+                    navigate(&format!("/profile/{principal}/posts"), Default::default());
+                }
+            }
+            Gesture::SwipeRight => (),
+        }
+    });
+
     view! {
         <VideoPlayer
             node_ref=_ref
             view_bg_url=Signal::derive(view_bg_url)
             view_video_url=Signal::derive(view_video_url)
+            on_gesture
+        />
+        <For
+            each=move || hearts.get()
+            key=|heart| heart.0
+            children=|(_, x, y)| {
+                view! {
+                    <img
+                        src="/img/heart-icon-liked.svg"
+                        class="absolute z-[5] w-20 h-20 -translate-x-1/2 -translate-y-1/2 pointer-events-none animate-ping"
+                        style:left=format!("{x}px")
+                        style:top=format!("{y}px")
+                    />
+                }
+            }
         />
     }
     .into_any()
@@ -229,7 +298,9 @@ pub fn VideoViewForQueue(
     idx: usize,
     muted: RwSignal<bool>,
 ) -> impl IntoView {
-    let container_ref = NodeRef::<Video>::new();
+    let container_ref = use_context::<ReportVideoCtx>()
+        .map(|ctx| ctx.0)
+        .unwrap_or_default();
 
     // Handles autoplay
     Effect::new(move |_| {
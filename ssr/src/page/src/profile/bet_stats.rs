@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use candid::Principal;
+use leptos::prelude::*;
+use state::canisters::unauth_canisters;
+use utils::send_wrap;
+use yral_canisters_common::{
+    cursored_data::{vote::VotesProvider, CursoredDataProvider},
+    utils::{
+        profile::ProfileDetails,
+        vote::{VoteDetails, VoteKind, VoteOutcome},
+    },
+};
+
+use super::speculation::{ExternalUser, FallbackUser};
+use crate::bet_outcomes::BetOutcomesCtx;
+
+/// Number of bets fetched per request while walking the history
+const HISTORY_CHUNK_SZ: usize = 50;
+const CHART_WIDTH: f64 = 300.0;
+const CHART_HEIGHT: f64 = 80.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KindAccuracy {
+    pub settled: u64,
+    pub won: u64,
+}
+
+impl KindAccuracy {
+    pub fn percentage(&self) -> Option<u64> {
+        (self.settled > 0).then(|| self.won * 100 / self.settled)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BetStats {
+    pub won: u64,
+    pub lost: u64,
+    pub draws: u64,
+    pub pending: u64,
+    pub net: i64,
+    pub hot: KindAccuracy,
+    pub not: KindAccuracy,
+    /// Running profit/loss after every settled bet, oldest first
+    pub timeline: Vec<i64>,
+    /// Net result per creator canister, best first
+    pub creators: Vec<(Principal, i64)>,
+}
+
+/// The parts of a bet the stats are computed from
+#[derive(Clone, Debug)]
+struct BetRecord {
+    creator_canister: Principal,
+    kind: VoteKind,
+    stake: u64,
+    outcome: VoteOutcome,
+}
+
+impl From<&VoteDetails> for BetRecord {
+    fn from(details: &VoteDetails) -> Self {
+        Self {
+            creator_canister: details.canister_id,
+            kind: details.vote_kind.clone(),
+            stake: details.vote_amount,
+            outcome: details.outcome.clone(),
+        }
+    }
+}
+
+impl BetStats {
+    /// `history` is expected to be ordered latest first, as returned by [VotesProvider]
+    pub fn from_history(history: &[VoteDetails]) -> Self {
+        Self::from_records(history.iter().rev().map(BetRecord::from))
+    }
+
+    /// `records` are ordered oldest first
+    fn from_records(records: impl Iterator<Item = BetRecord>) -> Self {
+        let mut stats = Self::default();
+        let mut per_creator = HashMap::<Principal, i64>::new();
+
+        for record in records {
+            let stake = record.stake as i64;
+            let (pnl, won) = match record.outcome {
+                VoteOutcome::Won(amt) => {
+                    stats.won += 1;
+                    (amt as i64 - stake, true)
+                }
+                VoteOutcome::Draw(amt) => {
+                    stats.draws += 1;
+                    (amt as i64 - stake, false)
+                }
+                VoteOutcome::Lost => {
+                    stats.lost += 1;
+                    (-stake, false)
+                }
+                VoteOutcome::AwaitingResult => {
+                    stats.pending += 1;
+                    continue;
+                }
+            };
+
+            let accuracy = if matches!(record.kind, VoteKind::Hot) {
+                &mut stats.hot
+            } else {
+                &mut stats.not
+            };
+            accuracy.settled += 1;
+            accuracy.won += won as u64;
+
+            stats.net += pnl;
+            stats.timeline.push(stats.net);
+            *per_creator.entry(record.creator_canister).or_default() += pnl;
+        }
+
+        stats.creators = per_creator.into_iter().collect();
+        stats
+            .creators
+            .sort_by_key(|(_, pnl)| std::cmp::Reverse(*pnl));
+        stats
+    }
+
+    pub fn settled(&self) -> u64 {
+        self.won + self.lost + self.draws
+    }
+
+    pub fn win_rate(&self) -> Option<u64> {
+        let settled = self.settled();
+        (settled > 0).then(|| self.won * 100 / settled)
+    }
+
+    pub fn best_creator(&self) -> Option<(Principal, i64)> {
+        self.creators.first().copied().filter(|(_, pnl)| *pnl > 0)
+    }
+
+    pub fn worst_creator(&self) -> Option<(Principal, i64)> {
+        self.creators.last().copied().filter(|(_, pnl)| *pnl < 0)
+    }
+}
+
+/// Bet stats computed during this session, keyed by user principal
+/// along with the [BetOutcomesCtx::balance_version] they were computed at
+#[derive(Clone, Copy)]
+pub struct BetStatsCache(RwSignal<HashMap<Principal, (u64, BetStats)>>);
+
+impl Default for BetStatsCache {
+    fn default() -> Self {
+        Self(RwSignal::new(HashMap::new()))
+    }
+}
+
+async fn fetch_bet_history(user_canister: Principal) -> Result<Vec<VoteDetails>, ServerFnError> {
+    let provider = VotesProvider::new(unauth_canisters(), user_canister);
+    let mut history = vec![];
+    loop {
+        let start = history.len();
+        let page = provider
+            .get_by_cursor(start, start + HISTORY_CHUNK_SZ)
+            .await
+            .map_err(ServerFnError::new)?;
+        history.extend(page.data);
+        if page.end || history.len() == start {
+            break;
+        }
+    }
+    Ok(history)
+}
+
+fn signed_amount(amt: i64) -> String {
+    if amt > 0 {
+        format!("+{amt}")
+    } else {
+        amt.to_string()
+    }
+}
+
+#[component]
+fn StatTile(#[prop(into)] label: String, #[prop(into)] value: String) -> impl IntoView {
+    view! {
+        <div class="flex flex-col flex-1 items-center gap-1 p-3 rounded-md bg-white/10">
+            <span class="text-lg font-bold">{value}</span>
+            <span class="text-xs text-white/60 uppercase">{label}</span>
+        </div>
+    }
+}
+
+#[component]
+fn PnlChart(timeline: Vec<i64>) -> impl IntoView {
+    let min = timeline.iter().copied().min().unwrap_or_default().min(0);
+    let max = timeline.iter().copied().max().unwrap_or_default().max(0);
+    let range = (max - min).max(1) as f64;
+    let y = move |v: i64| CHART_HEIGHT - (v - min) as f64 / range * CHART_HEIGHT;
+    // the chart starts from 0, before the first bet
+    let step = CHART_WIDTH / timeline.len().max(1) as f64;
+
+    let points = std::iter::once(0)
+        .chain(timeline.iter().copied())
+        .enumerate()
+        .map(|(idx, v)| format!("{:.1},{:.1}", idx as f64 * step, y(v)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let zero = y(0);
+    let stroke = if timeline.last().copied().unwrap_or_default() >= 0 {
+        "rgb(var(--color-primary-600))"
+    } else {
+        "#ffffff"
+    };
+
+    view! {
+        <svg
+            class="w-full h-20"
+            viewBox=format!("0 0 {CHART_WIDTH} {CHART_HEIGHT}")
+            preserveAspectRatio="none"
+        >
+            <line
+                x1="0"
+                x2=CHART_WIDTH
+                y1=zero
+                y2=zero
+                stroke="#ffffff30"
+                stroke-dasharray="4 4"
+            />
+            <polyline points=points fill="none" stroke=stroke stroke-width="2" />
+        </svg>
+    }
+}
+
+#[component]
+fn CreatorResult(#[prop(into)] label: String, creator: Option<(Principal, i64)>) -> impl IntoView {
+    let Some((canister_id, pnl)) = creator else {
+        return view! {
+            <div class="flex flex-col flex-1 gap-1 p-3 rounded-md bg-white/10">
+                <span class="text-xs text-white/60 uppercase">{label}</span>
+                <span class="text-sm text-white/60">-</span>
+            </div>
+        }
+        .into_any();
+    };
+
+    let profile_details = Resource::new(
+        move || canister_id,
+        move |canister_id| {
+            send_wrap(async move {
+                let canister = unauth_canisters();
+                let user = canister.individual_user(canister_id).await;
+                let profile_details = user.get_profile_details().await.ok()?;
+                Some(ProfileDetails::from(profile_details))
+            })
+        },
+    );
+
+    view! {
+        <div class="flex flex-col flex-1 gap-1 p-3 rounded-md bg-white/10 overflow-hidden">
+            <span class="text-xs text-white/60 uppercase">{label}</span>
+            <Suspense fallback=FallbackUser>
+                {move || profile_details.get().map(|user| view! { <ExternalUser user /> })}
+            </Suspense>
+            <span class="text-sm font-semibold">{signed_amount(pnl)} Tokens</span>
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+fn BetStatsView(stats: BetStats) -> impl IntoView {
+    let percentage = |p: Option<u64>| p.map(|p| format!("{p}%")).unwrap_or("-".into());
+    let best = stats.best_creator();
+    let worst = stats.worst_creator();
+
+    view! {
+        <div class="flex flex-col gap-3 w-full text-white">
+            <div class="flex flex-row gap-2">
+                <StatTile label="Win rate" value=percentage(stats.win_rate()) />
+                <StatTile label="Net" value=signed_amount(stats.net) />
+                <StatTile label="Games" value=stats.settled().to_string() />
+            </div>
+            {(!stats.timeline.is_empty())
+                .then(|| {
+                    view! {
+                        <div class="flex flex-col gap-1 p-3 rounded-md bg-white/10">
+                            <span class="text-xs text-white/60 uppercase">Profit / loss</span>
+                            <PnlChart timeline=stats.timeline.clone() />
+                        </div>
+                    }
+                })}
+            <div class="flex flex-row gap-2">
+                <StatTile label="Hot accuracy" value=percentage(stats.hot.percentage()) />
+                <StatTile label="Not accuracy" value=percentage(stats.not.percentage()) />
+            </div>
+            <div class="flex flex-row gap-2">
+                <CreatorResult label="Best creator" creator=best />
+                <CreatorResult label="Worst creator" creator=worst />
+            </div>
+        </div>
+    }
+}
+
+/// Aggregated results of all bets placed by the user
+#[component]
+pub fn BetStatsPanel(user_canister: Principal, user_principal: Principal) -> impl IntoView {
+    let cache: BetStatsCache = expect_context();
+    let bet_outcomes: BetOutcomesCtx = expect_context();
+
+    let stats = LocalResource::new(move || {
+        // settled bets change the stats
+        let version = bet_outcomes.balance_version.get();
+        async move {
+            let cached = cache.0.with_untracked(|cache| {
+                cache
+                    .get(&user_principal)
+                    .filter(|(v, _)| *v == version)
+                    .map(|(_, stats)| stats.clone())
+            });
+            if let Some(stats) = cached {
+                return Some(stats);
+            }
+
+            let history = match fetch_bet_history(user_canister).await {
+                Ok(history) => history,
+                Err(e) => {
+                    log::warn!("failed to fetch bet history: {e}");
+                    return None;
+                }
+            };
+            let stats = BetStats::from_history(&history);
+            cache.0.update(|cache| {
+                cache.insert(user_principal, (version, stats.clone()));
+            });
+            Some(stats)
+        }
+    });
+
+    view! {
+        <Suspense fallback=|| {
+            view! { <div class="w-full h-40 rounded-md bg-white/10 animate-pulse"></div> }
+        }>
+            {move || {
+                let stats = stats.get()?.take()?;
+                (stats.settled() + stats.pending > 0).then(|| view! { <BetStatsView stats /> })
+            }}
+        </Suspense>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creator(n: u8) -> Principal {
+        Principal::from_slice(&[n])
+    }
+
+    fn bet(creator_n: u8, kind: VoteKind, stake: u64, outcome: VoteOutcome) -> BetRecord {
+        BetRecord {
+            creator_canister: creator(creator_n),
+            kind,
+            stake,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn stats_follow_settled_bets_in_order() {
+        let stats = BetStats::from_records(
+            [
+                bet(1, VoteKind::Hot, 100, VoteOutcome::Won(180)),
+                bet(2, VoteKind::Not, 50, VoteOutcome::Lost),
+                bet(1, VoteKind::Not, 20, VoteOutcome::Draw(20)),
+                bet(2, VoteKind::Hot, 30, VoteOutcome::AwaitingResult),
+                bet(3, VoteKind::Hot, 10, VoteOutcome::Lost),
+            ]
+            .into_iter(),
+        );
+
+        assert_eq!((stats.won, stats.lost, stats.draws), (1, 2, 1));
+        assert_eq!(stats.pending, 1);
+        assert_eq!(stats.net, 20);
+        assert_eq!(stats.timeline, [80, 30, 30, 20]);
+        assert_eq!(stats.win_rate(), Some(25));
+        assert_eq!(stats.hot, KindAccuracy { settled: 2, won: 1 });
+        assert_eq!(stats.not.percentage(), Some(0));
+        assert_eq!(stats.best_creator(), Some((creator(1), 80)));
+        assert_eq!(stats.worst_creator(), Some((creator(2), -50)));
+    }
+
+    #[test]
+    fn no_settled_bets_have_no_rates() {
+        let stats = BetStats::from_records(
+            [bet(1, VoteKind::Hot, 10, VoteOutcome::AwaitingResult)].into_iter(),
+        );
+        assert_eq!(stats.pending, 1);
+        assert!(stats.timeline.is_empty());
+        assert_eq!(stats.win_rate(), None);
+        assert_eq!(stats.hot.percentage(), None);
+        assert_eq!(stats.best_creator(), None);
+        assert_eq!(stats.worst_creator(), None);
+    }
+}
@@ -0,0 +1,210 @@
+use candid::Principal;
+use leptos::prelude::*;
+use utils::{bg_url, event_streaming::events::auth_canisters_store};
+
+use crate::upload::{
+    drafts::{
+        delete_draft, format_local_datetime, list_drafts, parse_local_datetime, publish_draft,
+        save_draft, schedule_validator, Draft,
+    },
+    validators::{description_validator, hashtags_validator},
+};
+
+#[component]
+fn DraftItem(draft: Draft, #[prop(into)] on_change: Callback<()>) -> impl IntoView {
+    let description = RwSignal::new(draft.description.clone());
+    let hashtags = RwSignal::new(draft.hashtags.join(","));
+    let publish_at = RwSignal::new(draft.publish_at_ms);
+    let error = RwSignal::new(draft.publish_error.clone().unwrap_or_default());
+    let canister_store = auth_canisters_store();
+    let draft = StoredValue::new(draft);
+
+    let edited = move |scheduled: bool| -> Result<Draft, String> {
+        let description = description.get_untracked();
+        description_validator(description.clone())?;
+        let hashtags = hashtags_validator(hashtags.get_untracked())?;
+        let publish_at_ms = if scheduled {
+            Some(schedule_validator(publish_at.get_untracked())?)
+        } else {
+            None
+        };
+        Ok(Draft {
+            description,
+            hashtags,
+            publish_at_ms,
+            publish_error: None,
+            ..draft.get_value()
+        })
+    };
+
+    // `true` schedules the draft, `false` keeps it as a plain draft
+    let save: Action<bool, (), LocalStorage> = Action::new_unsync(move |&scheduled: &bool| {
+        let res = edited(scheduled);
+        async move {
+            let draft = match res {
+                Ok(draft) => draft,
+                Err(e) => return error.set(e),
+            };
+            let Some(cans) = canister_store.get_untracked() else {
+                return;
+            };
+            match save_draft(cans.user_principal(), draft).await {
+                Ok(()) => on_change.run(()),
+                Err(e) => error.set(e.to_string()),
+            }
+        }
+    });
+    let publish: Action<(), (), LocalStorage> = Action::new_unsync(move |&()| {
+        let res = edited(false);
+        async move {
+            let draft = match res {
+                Ok(draft) => draft,
+                Err(e) => return error.set(e),
+            };
+            let Some(cans) = canister_store.get_untracked() else {
+                return;
+            };
+            match publish_draft(cans, draft).await {
+                Ok(_) => on_change.run(()),
+                Err(e) => error.set(e.to_string()),
+            }
+        }
+    });
+    let delete: Action<(), (), LocalStorage> = Action::new_unsync(move |&()| async move {
+        let Some(cans) = canister_store.get_untracked() else {
+            return;
+        };
+        match delete_draft(cans.user_principal(), draft.with_value(|d| d.uid.clone())).await {
+            Ok(()) => on_change.run(()),
+            Err(e) => error.set(e.to_string()),
+        }
+    });
+    let busy = move || save.pending()() || publish.pending()() || delete.pending()();
+
+    view! {
+        <div class="flex flex-row gap-4 p-3 rounded-md bg-white/5 text-white">
+            <img
+                class="object-cover w-24 aspect-[9/16] rounded-md"
+                src=draft.with_value(|d| bg_url(&d.uid))
+            />
+            <div class="flex flex-col gap-2 grow">
+                <Show when=move || error.with(|e| !e.is_empty())>
+                    <span class="text-red-500 text-sm">{error}</span>
+                </Show>
+                <textarea
+                    class="p-2 bg-neutral-800 rounded-md text-sm"
+                    rows=2
+                    prop:value=description
+                    on:input=move |ev| description.set(event_target_value(&ev))
+                ></textarea>
+                <input
+                    class="p-2 bg-neutral-800 rounded-md text-sm"
+                    type="text"
+                    prop:value=hashtags
+                    on:input=move |ev| hashtags.set(event_target_value(&ev))
+                />
+                <input
+                    class="p-2 bg-neutral-800 rounded-md text-sm"
+                    type="datetime-local"
+                    prop:value=move || publish_at().map(format_local_datetime).unwrap_or_default()
+                    on:input=move |ev| publish_at.set(parse_local_datetime(&event_target_value(&ev)))
+                />
+                <Show when=move || draft.with_value(|d| d.publish_at_ms.is_some())>
+                    <span class="text-xs text-white/60">
+                        "Scheduled for "
+                        {draft.with_value(|d| d.publish_at_ms.map(format_local_datetime))}
+                    </span>
+                </Show>
+                <div class="flex flex-row flex-wrap gap-2 text-sm">
+                    <button
+                        class="px-4 py-1 rounded-full bg-primary-600 disabled:bg-primary-400"
+                        disabled=busy
+                        on:click=move |_| {
+                            publish.dispatch(());
+                        }
+                    >
+                        Publish now
+                    </button>
+                    <button
+                        class="px-4 py-1 rounded-full bg-white/10"
+                        disabled=busy
+                        on:click=move |_| {
+                            save.dispatch(true);
+                        }
+                    >
+                        Schedule
+                    </button>
+                    <button
+                        class="px-4 py-1 rounded-full bg-white/10"
+                        disabled=busy
+                        on:click=move |_| {
+                            save.dispatch(false);
+                        }
+                    >
+                        {move || {
+                            if draft.with_value(|d| d.publish_at_ms.is_some()) {
+                                "Unschedule"
+                            } else {
+                                "Save"
+                            }
+                        }}
+                    </button>
+                    <button
+                        class="px-4 py-1 rounded-full bg-white/10 text-red-400"
+                        disabled=busy
+                        on:click=move |_| {
+                            delete.dispatch(());
+                        }
+                    >
+                        Delete
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Drafts and scheduled publishes, only listed on the creator's own profile
+#[component]
+pub fn ProfileDrafts(user_principal: Principal) -> impl IntoView {
+    let drafts = LocalResource::new(move || list_drafts(user_principal));
+    let refetch = Callback::new(move |()| drafts.refetch());
+
+    view! {
+        <Suspense>
+            {move || {
+                drafts
+                    .get()
+                    .map(|res| match res.take() {
+                        Ok(drafts) if drafts.is_empty() => {
+                            view! {
+                                <span class="text-white/60 text-center">
+                                    Uploads saved as drafts show up here
+                                </span>
+                            }
+                                .into_any()
+                        }
+                        Ok(drafts) => {
+                            view! {
+                                <div class="flex flex-col gap-4">
+                                    {drafts
+                                        .into_iter()
+                                        .map(|draft| view! { <DraftItem draft on_change=refetch /> })
+                                        .collect_view()}
+                                </div>
+                            }
+                                .into_any()
+                        }
+                        Err(e) => {
+                            view! {
+                                <span class="text-red-500 text-center">
+                                    {format!("Failed to load drafts: {e}")}
+                                </span>
+                            }
+                                .into_any()
+                        }
+                    })
+            }}
+        </Suspense>
+    }
+}
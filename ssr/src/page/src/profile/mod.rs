@@ -1,7 +1,9 @@
+pub mod bet_stats;
+mod drafts;
 mod ic;
 pub mod overlay;
 mod posts;
-mod profile_iter;
+pub(crate) mod profile_iter;
 pub mod profile_post;
 mod speculation;
 mod tokens;
@@ -10,6 +12,7 @@ use candid::Principal;
 use codee::string::FromToStringCodec;
 use component::connect::ConnectLogin;
 use consts::USER_PRINCIPAL_STORE;
+use drafts::ProfileDrafts;
 use indexmap::IndexSet;
 use leptos::prelude::*;
 use leptos_icons::*;
@@ -29,6 +32,9 @@ use yral_canisters_common::{
     Canisters,
 };
 
+use crate::blocking::CreatorActionsButton;
+use crate::following::{use_follow_info, FollowButton, FollowedCreator};
+
 #[derive(Clone, Default)]
 pub struct ProfilePostsContext {
     video_queue: RwSignal<IndexSet<PostDetails>>,
@@ -43,7 +49,7 @@ struct ProfileParams {
 }
 
 #[component]
-fn Stat(stat: u64, #[prop(into)] info: String) -> impl IntoView {
+fn Stat(#[prop(into)] stat: Signal<u64>, #[prop(into)] info: String) -> impl IntoView {
     view! {
         <div class="flex flex-1 flex-col items-center text-white space-y-0.5">
             <span class="font-bold text-xl">{stat}</span>
@@ -60,6 +66,8 @@ struct TabsParam {
 #[component]
 fn ListSwitcher1(user_canister: Principal, user_principal: Principal) -> impl IntoView {
     let param = use_params::<TabsParam>();
+    let (viewer_principal, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+    let is_own_profile = move || viewer_principal() == Some(user_principal);
 
     let current_tab = Memo::new(move |_| {
         param.with(|p| {
@@ -68,6 +76,7 @@ fn ListSwitcher1(user_canister: Principal, user_principal: Principal) -> impl In
                 "posts" => 0,
                 "stakes" => 1,
                 "tokens" => 2,
+                "drafts" => 3,
                 _ => 0,
             }
         })
@@ -97,6 +106,14 @@ fn ListSwitcher1(user_canister: Principal, user_principal: Principal) -> impl In
             >
                 <Icon icon=icondata::AiDollarCircleOutlined />
             </a>
+            <Show when=is_own_profile>
+                <a
+                    class=move || tab_class(3)
+                    href=move || format!("/profile/{}/drafts", user_principal)
+                >
+                    <Icon icon=icondata::AiFileTextOutlined />
+                </a>
+            </Show>
         </div>
 
         <div class="flex flex-col gap-y-12 justify-center pb-12 w-11/12 sm:w-7/12">
@@ -109,6 +126,9 @@ fn ListSwitcher1(user_canister: Principal, user_principal: Principal) -> impl In
             <Show when=move || current_tab() == 2>
                 <ProfileTokens user_canister user_principal />
             </Show>
+            <Show when=move || current_tab() == 3 && is_own_profile()>
+                <ProfileDrafts user_principal />
+            </Show>
         </div>
     }
 }
@@ -121,6 +141,11 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
     let earnings = user.lifetime_earnings;
     let (is_connected, _) = account_connected_reader();
     let (viewer_principal, _) = use_cookie::<Principal, FromToStringCodec>(USER_PRINCIPAL_STORE);
+    let follow_info = use_follow_info(user.principal);
+    let creator = FollowedCreator {
+        principal: user.principal,
+        canister: user_canister,
+    };
 
     view! {
         <div class="min-h-screen bg-black text-white overflow-y-auto pt-10 pb-12">
@@ -146,6 +171,10 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
                                 // <p class="text-white">@ {username_or_principal}</p>
                                 <p class="text-primary-500">{earnings} Earnings</p>
                             </div>
+                            <div class="flex flex-row gap-2 items-center pt-2">
+                                <FollowButton creator info=follow_info />
+                                <CreatorActionsButton creator=user.principal class="text-xl" />
+                            </div>
                             <Show when=move || !is_connected() && viewer_principal.get().map(|v| v.to_text() == username_or_principal).unwrap_or(false)>
                                 <div class="md:w-4/12 w-6/12 pt-5">
                                     <ConnectLogin cta_location="profile" />
@@ -155,8 +184,14 @@ fn ProfileViewInner(user: ProfileDetails, user_canister: Principal) -> impl Into
                     </div>
                 </div>
                 <div class="flex justify-around text-center rounded-full divide-x-2 divide-white/20 bg-white/10 p-4 my-4 w-11/12 sm:w-7/12">
-                    // <Stat stat=user.followers_cnt info="Lovers"/>
-                    // <Stat stat=user.following_cnt info="Loving"/>
+                    <Stat
+                        stat=Signal::derive(move || follow_info.with(|i| i.map(|i| i.followers_cnt).unwrap_or_default()))
+                        info="Followers"
+                    />
+                    <Stat
+                        stat=Signal::derive(move || follow_info.with(|i| i.map(|i| i.following_cnt).unwrap_or_default()))
+                        info="Following"
+                    />
                     <Stat stat=user.hots info="Hots" />
                     <Stat stat=user.nots info="Nots" />
                 </div>
@@ -6,7 +6,8 @@ use leptos_router::hooks::use_location;
 use leptos_use::use_interval_fn;
 use web_time::Duration;
 
-use super::ic::ProfileStream;
+use super::{bet_stats::BetStatsPanel, ic::ProfileStream};
+use crate::bet_outcomes::BetOutcomesCtx;
 use component::profile_placeholders::NoMoreBetsGraphic;
 use state::canisters::unauth_canisters;
 use utils::{bg_url, send_wrap, time::to_hh_mm_ss};
@@ -228,6 +229,7 @@ pub fn Speculation(details: VoteDetails, _ref: NodeRef<html::Div>) -> impl IntoV
 #[component]
 pub fn ProfileSpeculations(user_canister: Principal, user_principal: Principal) -> impl IntoView {
     let provider = VotesProvider::new(unauth_canisters(), user_canister);
+    let bet_outcomes: BetOutcomesCtx = expect_context();
     let location = use_location();
     let empty_text = if location
         .pathname
@@ -239,12 +241,21 @@ pub fn ProfileSpeculations(user_canister: Principal, user_principal: Principal)
         "Not played any games yet!"
     };
     view! {
+        <BetStatsPanel user_canister user_principal />
         <ProfileStream
             provider
             empty_graphic=NoMoreBetsGraphic
             empty_text
             children=move |details, _ref| {
-                view! { <Speculation details _ref=_ref.unwrap_or_default() /> }
+                let _ref = _ref.unwrap_or_default();
+                let bet_key = (details.canister_id, details.post_id);
+                move || {
+                    // outcomes of bets settled while the page is open
+                    let details = bet_outcomes
+                        .settled_outcome(user_canister, bet_key)
+                        .unwrap_or_else(|| details.clone());
+                    view! { <Speculation details _ref /> }
+                }
             }
         />
     }
@@ -0,0 +1,365 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use candid::Principal;
+use codee::string::JsonSerdeCodec;
+use component::{back_btn::BackButton, modal::Modal, title::TitleText};
+use consts::MY_REPORTS_STORE;
+use leptos::{html::Video, prelude::*};
+use leptos_meta::Title;
+use leptos_use::storage::use_local_storage;
+use serde::{Deserialize, Serialize};
+use state::app_state::AppState;
+use utils::{report::ReportOption, time::to_hh_mm_ss, types::PostId};
+use web_time::Duration;
+use yral_canisters_common::utils::posts::PostDetails;
+
+use crate::post_view::PostViewCtx;
+
+pub const MAX_REPORT_DESCRIPTION_LEN: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReportStatus {
+    /// Stored, waiting to be picked up by moderation
+    Received,
+    /// Sent to the moderation service
+    Forwarded,
+    /// Picked up by a moderator
+    UnderReview,
+    Resolved,
+}
+
+impl ReportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Received => "Received",
+            Self::Forwarded => "Sent for review",
+            Self::UnderReview => "Under review",
+            Self::Resolved => "Resolved",
+        }
+    }
+
+    /// Moderators move reports into and out of review, never back
+    pub fn can_move_to(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (
+                Self::Received | Self::Forwarded,
+                Self::UnderReview | Self::Resolved
+            ) | (Self::UnderReview, Self::Resolved)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReportRequest {
+    pub canister_id: Principal,
+    pub post_id: u64,
+    pub reason: ReportOption,
+    pub description: Option<String>,
+    /// Position in the video the report refers to
+    pub video_timestamp_secs: Option<u64>,
+    /// Set when reporting a comment on the post rather than the post itself
+    pub comment_id: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PostReport {
+    pub id: String,
+    pub canister_id: Principal,
+    pub post_id: u64,
+    pub reason: ReportOption,
+    pub description: Option<String>,
+    pub video_timestamp_secs: Option<u64>,
+    /// Seconds since the unix epoch
+    pub created_at: u64,
+    pub status: ReportStatus,
+    pub comment_id: Option<String>,
+}
+
+impl PostReport {
+    pub fn post(&self) -> PostId {
+        (self.canister_id, self.post_id)
+    }
+
+    /// The post or comment reported
+    pub fn target(&self) -> (PostId, Option<&str>) {
+        (self.post(), self.comment_id.as_deref())
+    }
+}
+
+/// Report a post, or a comment on it, as the logged in user
+/// Each is only reported once per user, later reports return the first one
+#[server]
+pub async fn submit_report(report: ReportRequest) -> Result<PostReport, ServerFnError> {
+    server_impl::submit_report(report).await
+}
+
+/// Reports submitted by the logged in user, latest first
+#[server]
+pub async fn get_my_reports() -> Result<Vec<PostReport>, ServerFnError> {
+    server_impl::get_my_reports().await
+}
+
+/// Move a report into review or resolve it, for moderators
+#[server]
+pub async fn set_report_status(
+    report_id: String,
+    status: ReportStatus,
+) -> Result<PostReport, ServerFnError> {
+    server_impl::set_report_status(report_id, status).await
+}
+
+/// Reports made from this device, latest first
+pub fn use_my_reports() -> (Signal<Vec<PostReport>>, WriteSignal<Vec<PostReport>>) {
+    let (reports, set_reports, _) =
+        use_local_storage::<Vec<PostReport>, JsonSerdeCodec>(MY_REPORTS_STORE);
+    (reports, set_reports)
+}
+
+/// Keeps the latest copy of a report made from this device
+pub fn remember_report(set_my_reports: WriteSignal<Vec<PostReport>>, report: PostReport) {
+    set_my_reports.update(|reports| {
+        reports.retain(|r| r.target() != report.target());
+        reports.insert(0, report);
+    });
+}
+
+/// Playback of the post shown in the feed, used to attach a timestamp to reports
+#[derive(Clone, Copy)]
+pub struct ReportVideoCtx(pub NodeRef<Video>);
+
+#[component]
+pub fn ReportPostModal(post: PostDetails, show: RwSignal<bool>) -> impl IntoView {
+    let (my_reports, set_my_reports) = use_my_reports();
+    let post_key = (post.canister_id, post.post_id);
+    let existing = move || {
+        my_reports.with(|reports| {
+            reports
+                .iter()
+                .find(|r| r.target() == (post_key, None))
+                .cloned()
+        })
+    };
+
+    let post_view_ctx = use_context::<PostViewCtx>();
+    let video_ref = use_context::<ReportVideoCtx>().map(|ctx| ctx.0);
+    let reason = RwSignal::new(ReportOption::Nudity);
+    let description = RwSignal::new(String::new());
+    let include_time = RwSignal::new(false);
+    let video_time = RwSignal::new(None::<u64>);
+    Effect::new(move |_| {
+        if show() {
+            video_time.set(
+                video_ref
+                    .and_then(|v| v.get_untracked())
+                    .map(|v| v.current_time() as u64),
+            );
+        }
+    });
+
+    let error = RwSignal::new(None::<String>);
+    let post = StoredValue::new(post);
+    let submit = Action::new(move |()| {
+        let request = post.with_value(|post| ReportRequest {
+            canister_id: post.canister_id,
+            post_id: post.post_id,
+            reason: reason.get_untracked(),
+            description: Some(description.get_untracked().trim().to_string())
+                .filter(|d| !d.is_empty()),
+            video_timestamp_secs: include_time
+                .get_untracked()
+                .then(|| video_time.get_untracked())
+                .flatten(),
+            comment_id: None,
+        });
+        async move {
+            match submit_report(request).await {
+                Ok(report) => {
+                    error.set(None);
+                    remember_report(set_my_reports, report);
+                    if let Some(ctx) = post_view_ctx {
+                        ctx.hide_post(post_key);
+                    }
+                }
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    view! {
+        <Modal show>
+            <div class="flex flex-col justify-center items-center gap-4 text-white">
+                <span class="text-lg">Report Post</span>
+                {move || match existing() {
+                    Some(report) => {
+                        view! {
+                            <div class="flex flex-col items-center gap-2 text-center">
+                                <span>Thanks, we have received your report.</span>
+                                <span class="text-sm text-white/60">
+                                    {format!("{} · {}", report.reason.as_str(), report.status.as_str())}
+                                </span>
+                                <a href="/reports" class="text-sm text-primary-600">
+                                    View my reports
+                                </a>
+                            </div>
+                        }
+                            .into_any()
+                    }
+                    None => {
+                        view! {
+                            <span class="text-lg">Please select a reason:</span>
+                            <div class="max-w-full w-full text-md text-black">
+                                <select
+                                    class="p-2 w-full block rounded-lg text-sm"
+                                    on:change=move |ev| {
+                                        if let Some(opt) = ReportOption::from_label(&event_target_value(&ev)) {
+                                            reason.set(opt);
+                                        }
+                                    }
+                                >
+                                    {ReportOption::ALL
+                                        .into_iter()
+                                        .map(|opt| {
+                                            let label = opt.as_str().to_string();
+                                            view! {
+                                                <option value=label.clone() selected=move || reason() == opt>
+                                                    {label.clone()}
+                                                </option>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </select>
+                            </div>
+                            <textarea
+                                class="w-full p-2 rounded-lg bg-white/10 text-sm resize-none"
+                                rows="3"
+                                maxlength=MAX_REPORT_DESCRIPTION_LEN
+                                placeholder="Tell us more (optional)"
+                                on:input=move |ev| description.set(event_target_value(&ev))
+                                prop:value=description
+                            ></textarea>
+                            {move || {
+                                video_time()
+                                    .map(|secs| {
+                                        view! {
+                                            <label class="flex flex-row gap-2 items-center self-start text-sm">
+                                                <input
+                                                    type="checkbox"
+                                                    prop:checked=include_time
+                                                    on:change=move |ev| include_time.set(event_target_checked(&ev))
+                                                />
+                                                {format!(
+                                                    "Problem is at {}",
+                                                    to_hh_mm_ss(Duration::from_secs(secs)),
+                                                )}
+                                            </label>
+                                        }
+                                    })
+                            }}
+                            {move || error().map(|e| view! { <span class="text-sm text-red-500">{e}</span> })}
+                            <button
+                                disabled=move || submit.pending().get()
+                                on:click=move |_| {
+                                    submit.dispatch(());
+                                }
+                            >
+                                <div class="rounded-lg bg-pink-500 p-1">
+                                    {move || if submit.pending().get() { "Submitting..." } else { "Submit" }}
+                                </div>
+                            </button>
+                        }
+                            .into_any()
+                    }
+                }}
+            </div>
+        </Modal>
+    }
+}
+
+#[component]
+fn ReportItem(report: PostReport) -> impl IntoView {
+    let post_url = format!("/post/{}/{}", report.canister_id, report.post_id);
+    let status_class = match report.status {
+        ReportStatus::Resolved => "text-green-500",
+        _ => "text-primary-600",
+    };
+    view! {
+        <a href=post_url class="flex flex-col gap-1 p-3 w-full rounded-md bg-white/10">
+            <div class="flex flex-row justify-between items-center">
+                <span class="font-semibold">
+                    {if report.comment_id.is_some() { "Comment · " } else { "" }}
+                    {report.reason.as_str().to_string()}
+                </span>
+                <span class=format!("text-xs {status_class}")>{report.status.as_str()}</span>
+            </div>
+            {report
+                .description
+                .map(|d| view! { <span class="text-sm text-white/70 line-clamp-2">{d}</span> })}
+            {report
+                .video_timestamp_secs
+                .map(|secs| {
+                    view! {
+                        <span class="text-xs text-white/50">
+                            {format!("At {}", to_hh_mm_ss(Duration::from_secs(secs)))}
+                        </span>
+                    }
+                })}
+        </a>
+    }
+}
+
+#[component]
+pub fn MyReports() -> impl IntoView {
+    let (my_reports, set_my_reports) = use_my_reports();
+
+    // refresh the statuses of reports made while logged in
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            match get_my_reports().await {
+                Ok(remote) => set_my_reports.update(|reports| {
+                    for report in remote.into_iter().rev() {
+                        reports.retain(|r| r.target() != report.target());
+                        reports.insert(0, report);
+                    }
+                    reports.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+                }),
+                Err(e) => log::warn!("failed to fetch reports: {e}"),
+            }
+        });
+    });
+
+    let app_state = use_context::<AppState>();
+    let page_title = app_state.unwrap().name.to_owned() + " - My Reports";
+
+    view! {
+        <Title text=page_title />
+        <div class="flex flex-col items-center pt-2 pb-12 bg-black min-w-dvw min-h-dvh text-white">
+            <TitleText justify_center=false>
+                <div class="flex flex-row justify-between">
+                    <BackButton fallback="/menu".to_string() />
+                    <span class="text-2xl font-bold">My Reports</span>
+                    <div></div>
+                </div>
+            </TitleText>
+            <div class="flex flex-col gap-2 w-11/12 md:w-9/12 pt-4">
+                <Show
+                    when=move || my_reports.with(|r| !r.is_empty())
+                    fallback=|| {
+                        view! {
+                            <span class="text-white/60 text-center pt-8">
+                                "You haven't reported any posts"
+                            </span>
+                        }
+                    }
+                >
+                    <For
+                        each=move || my_reports.get()
+                        key=|report| (report.id.clone(), report.status)
+                        children=|report| view! { <ReportItem report /> }
+                    />
+                </Show>
+            </div>
+        </div>
+    }
+}
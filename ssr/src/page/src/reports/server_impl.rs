@@ -0,0 +1,317 @@
+use auth::server_impl::{extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use state::{
+    canisters::unauth_canisters,
+    comments::{CommentStore, CommentStoreImpl},
+};
+use web_time::SystemTime;
+
+use super::{PostReport, ReportRequest, ReportStatus, MAX_REPORT_DESCRIPTION_LEN};
+
+/// Reports listed for a user, older ones are kept but not listed
+const MAX_LISTED_REPORTS: usize = 200;
+/// Comma separated principals allowed to move reports through review
+const MODERATORS_ENV: &str = "REPORT_MODERATORS";
+
+/// A report and who made it, stored on its own so moderation can update it by id
+#[derive(Serialize, Deserialize)]
+struct StoredReport {
+    reporter: Principal,
+    report: PostReport,
+}
+
+/// What a report is about, resolved on the server from the post and comment ids
+#[cfg_attr(not(feature = "ga4"), allow(dead_code))]
+struct ReportedContent {
+    /// Creator of the post, or author of the comment
+    publisher: Principal,
+    video_uid: String,
+}
+
+fn report_key(id: &str) -> String {
+    format!("report:{id}")
+}
+
+/// Ids of the reports made by `reporter`, scored by when they were made
+fn reporter_reports_key(reporter: Principal) -> String {
+    format!("reports-by:{}", reporter.to_text())
+}
+
+/// Posts and comments `reporter` already reported
+fn reported_targets_key(reporter: Principal) -> String {
+    format!("reported-targets:{}", reporter.to_text())
+}
+
+fn target_member(report: &ReportRequest) -> String {
+    format!(
+        "{}:{}:{}",
+        report.canister_id.to_text(),
+        report.post_id,
+        report.comment_id.as_deref().unwrap_or_default()
+    )
+}
+
+async fn caller() -> Result<Principal, ServerFnError> {
+    extract_principal_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))
+}
+
+async fn read_reports(
+    kv: &KVStoreImpl,
+    reporter: Principal,
+) -> Result<Vec<PostReport>, ServerFnError> {
+    let ids = kv
+        .set_range(reporter_reports_key(reporter), 0, MAX_LISTED_REPORTS)
+        .await?;
+    let mut reports = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(stored) = kv.read_json::<StoredReport>(report_key(&id)).await? {
+            reports.push(stored.report);
+        }
+    }
+    Ok(reports)
+}
+
+#[cfg(feature = "ga4")]
+async fn forward_report(
+    reporter: Principal,
+    id: &str,
+    report: &ReportRequest,
+    content: &ReportedContent,
+) -> Result<(), ServerFnError> {
+    use utils::{report::send_report_offchain, stream_url};
+
+    // moderation refers to the report by id when updating its status
+    let mut reason = format!("[{id}] {}", report.reason.as_str());
+    if let Some(comment_id) = &report.comment_id {
+        reason = format!("{reason} on comment {comment_id}");
+    }
+    if let Some(secs) = report.video_timestamp_secs {
+        reason.push_str(&format!(" @{secs}s"));
+    }
+    if let Some(description) = &report.description {
+        reason.push_str(&format!(": {description}"));
+    }
+
+    send_report_offchain(
+        reporter.to_string(),
+        content.publisher.to_string(),
+        report.canister_id.to_string(),
+        report.post_id.to_string(),
+        content.video_uid.clone(),
+        reason,
+        stream_url(&content.video_uid),
+    )
+    .await
+}
+
+pub async fn submit_report(mut report: ReportRequest) -> Result<PostReport, ServerFnError> {
+    let reporter = caller().await?;
+    if report
+        .description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_REPORT_DESCRIPTION_LEN)
+    {
+        return Err(ServerFnError::new("description is too long"));
+    }
+
+    let post = (report.canister_id, report.post_id);
+    let Some(details) = unauth_canisters().get_post_details(post.0, post.1).await? else {
+        return Err(ServerFnError::new("post not found"));
+    };
+    let mut content = ReportedContent {
+        publisher: details.poster_principal,
+        video_uid: details.uid,
+    };
+    if let Some(comment_id) = &report.comment_id {
+        let store: CommentStoreImpl = expect_context();
+        let Some(comment) = store.get_comment(post, comment_id, None).await? else {
+            return Err(ServerFnError::new("comment not found"));
+        };
+        // moderation acts on the author of the comment, not of the post
+        content.publisher = comment.author;
+        report.description = Some(match report.description.take() {
+            Some(d) => format!("{d} (comment: {})", comment.text),
+            None => format!("comment: {}", comment.text),
+        });
+    }
+
+    let created_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let kv: KVStoreImpl = expect_context();
+    let targets_key = reported_targets_key(reporter);
+    let member = target_member(&report);
+    // claimed first so concurrent reports of the same target store only one
+    let first_report = kv
+        .set_add(targets_key.clone(), member.clone(), created_at as f64)
+        .await?;
+    if !first_report {
+        let target = (post, report.comment_id.as_deref());
+        return read_reports(&kv, reporter)
+            .await?
+            .into_iter()
+            .find(|r| r.target() == target)
+            .ok_or_else(|| ServerFnError::new("already reported"));
+    }
+
+    let res = store_report(&kv, reporter, report, content, created_at).await;
+    if res.is_err() {
+        // nothing was stored, let the user report it again
+        if let Err(e) = kv.set_remove(targets_key, member).await {
+            log::warn!("failed to release reported target: {e}");
+        }
+    }
+    res
+}
+
+#[cfg_attr(not(feature = "ga4"), allow(unused_variables))]
+async fn store_report(
+    kv: &KVStoreImpl,
+    reporter: Principal,
+    report: ReportRequest,
+    content: ReportedContent,
+    created_at: u64,
+) -> Result<PostReport, ServerFnError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    #[cfg(feature = "ga4")]
+    let status = match forward_report(reporter, &id, &report, &content).await {
+        Ok(()) => ReportStatus::Forwarded,
+        Err(e) => {
+            log::warn!("failed to forward report: {e}");
+            ReportStatus::Received
+        }
+    };
+    #[cfg(not(feature = "ga4"))]
+    let status = ReportStatus::Received;
+
+    let stored = PostReport {
+        id,
+        canister_id: report.canister_id,
+        post_id: report.post_id,
+        reason: report.reason,
+        description: report.description,
+        video_timestamp_secs: report.video_timestamp_secs,
+        created_at,
+        status,
+        comment_id: report.comment_id,
+    };
+    kv.write_json(
+        report_key(&stored.id),
+        &StoredReport {
+            reporter,
+            report: stored.clone(),
+        },
+    )
+    .await?;
+    kv.set_add(
+        reporter_reports_key(reporter),
+        stored.id.clone(),
+        created_at as f64,
+    )
+    .await?;
+
+    Ok(stored)
+}
+
+pub async fn get_my_reports() -> Result<Vec<PostReport>, ServerFnError> {
+    let reporter = caller().await?;
+    let kv: KVStoreImpl = expect_context();
+    read_reports(&kv, reporter).await
+}
+
+fn is_moderator(principal: Principal) -> bool {
+    std::env::var(MODERATORS_ENV).is_ok_and(|moderators| {
+        moderators
+            .split(',')
+            .any(|m| Principal::from_text(m.trim()).is_ok_and(|m| m == principal))
+    })
+}
+
+pub(crate) async fn update_report_status(
+    kv: &KVStoreImpl,
+    report_id: &str,
+    status: ReportStatus,
+) -> Result<PostReport, ServerFnError> {
+    let key = report_key(report_id);
+    let Some(mut stored) = kv.read_json::<StoredReport>(key.clone()).await? else {
+        return Err(ServerFnError::new("report not found"));
+    };
+    if !stored.report.status.can_move_to(status) {
+        return Err(ServerFnError::new(format!(
+            "a report can't go from {} to {}",
+            stored.report.status.as_str(),
+            status.as_str()
+        )));
+    }
+    stored.report.status = status;
+    kv.write_json(key, &stored).await?;
+    Ok(stored.report)
+}
+
+pub async fn set_report_status(
+    report_id: String,
+    status: ReportStatus,
+) -> Result<PostReport, ServerFnError> {
+    let moderator = caller().await?;
+    if !is_moderator(moderator) {
+        return Err(ServerFnError::new("only moderators can update reports"));
+    }
+    let kv: KVStoreImpl = expect_context();
+    update_report_status(&kv, &report_id, status).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_on, test_kv};
+    use utils::report::ReportOption;
+
+    #[test]
+    fn reports_only_move_forward_through_review() {
+        let kv = test_kv("reports");
+        let report = PostReport {
+            id: "r1".into(),
+            canister_id: Principal::anonymous(),
+            post_id: 0,
+            reason: ReportOption::Spam,
+            description: None,
+            video_timestamp_secs: None,
+            created_at: 0,
+            status: ReportStatus::Forwarded,
+            comment_id: None,
+        };
+        block_on(async {
+            kv.write_json(
+                report_key("r1"),
+                &StoredReport {
+                    reporter: Principal::anonymous(),
+                    report,
+                },
+            )
+            .await
+            .unwrap();
+
+            let report = update_report_status(&kv, "r1", ReportStatus::UnderReview)
+                .await
+                .unwrap();
+            assert_eq!(report.status, ReportStatus::UnderReview);
+            assert!(update_report_status(&kv, "r1", ReportStatus::Received)
+                .await
+                .is_err());
+            update_report_status(&kv, "r1", ReportStatus::Resolved)
+                .await
+                .unwrap();
+            assert!(update_report_status(&kv, "r1", ReportStatus::UnderReview)
+                .await
+                .is_err());
+            assert!(update_report_status(&kv, "missing", ReportStatus::Resolved)
+                .await
+                .is_err());
+        });
+    }
+}
@@ -1,4 +1,7 @@
-use crate::post_view::video_loader::{BgView, VideoViewForQueue};
+use crate::post_view::{
+    prefetch::use_prefetch,
+    video_loader::{BgView, VideoViewForQueue},
+};
 use indexmap::IndexSet;
 use leptos::html;
 use leptos::prelude::*;
@@ -43,6 +46,8 @@ pub fn ScrollingPostView<F: Fn() -> V + Clone + 'static + Send + Sync, V>(
 
     let scroll_root: NodeRef<html::Div> = NodeRef::new();
 
+    use_prefetch(video_queue, current_idx);
+
     let var_name = view! {
         <div class="h-full w-full overflow-hidden overflow-y-auto">
             <div
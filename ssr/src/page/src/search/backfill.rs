@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use auth::server_impl::store::KVStoreImpl;
+use candid::Principal;
+use leptos::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+use state::{canisters::unauth_canisters, search::SearchIndexImpl};
+use utils::ml_feed::{get_ml_feed_coldstart_clean, get_ml_feed_coldstart_nsfw};
+use yral_canisters_client::individual_user_template::{GetPostsOfUserProfileError, Result13};
+use yral_canisters_common::utils::posts::PostDetails;
+
+use super::server_impl::index_post_details;
+
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Posts asked from the ML feed to discover creators
+const DISCOVERY_SAMPLE: u32 = 200;
+/// Creators walked per pass, the ones walked longest ago go first
+const CREATORS_PER_PASS: usize = 500;
+const PAGE_SZ: u64 = 20;
+/// Pages of history indexed per creator per pass, keeps each pass short
+const PAGES_PER_PASS: u64 = 5;
+/// Long enough for a creator's pages to be indexed, short enough to recover from a crash
+const CLAIM_TTL_MS: u64 = 5 * 60 * 1000;
+
+/// Creator canisters known to the backfill, scored by when they were last walked
+/// new ones are scored 0 so they're walked next
+const CREATORS_KEY: &str = "search:backfill:creators";
+
+fn progress_key(canister: Principal) -> String {
+    format!("search:backfill:progress:{}", canister.to_text())
+}
+
+fn claim_key(canister: Principal) -> String {
+    format!("search:backfill:claim:{}", canister.to_text())
+}
+
+/// How far the backfill got through a creator's posts
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct Progress {
+    /// Offset of the next page of history, newest post first
+    history_start: u64,
+    history_done: bool,
+    /// Newest post indexed, later posts are picked up from the top
+    newest_post_id: Option<u64>,
+}
+
+fn now_ms() -> f64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as f64
+}
+
+/// A page of `canister`'s posts, newest first, and whether it's the last one
+async fn posts_page(
+    canister: Principal,
+    start: u64,
+) -> Result<(Vec<PostDetails>, bool), ServerFnError> {
+    let canisters = unauth_canisters();
+    let res = canisters
+        .individual_user(canister)
+        .await
+        .get_posts_of_this_user_profile_with_pagination_cursor(start, PAGE_SZ)
+        .await?;
+    match res {
+        Result13::Ok(posts) => {
+            let end = posts.len() < PAGE_SZ as usize;
+            let posts = posts
+                .into_iter()
+                .map(|details| PostDetails::from_canister_post(false, canister, details))
+                .collect();
+            Ok((posts, end))
+        }
+        Result13::Err(GetPostsOfUserProfileError::ReachedEndOfItemsList) => Ok((vec![], true)),
+        Result13::Err(e) => Err(ServerFnError::new(format!("failed to page posts: {e:?}"))),
+    }
+}
+
+async fn index_posts(search_index: &SearchIndexImpl, posts: &[PostDetails]) {
+    for post in posts {
+        if let Err(e) = index_post_details(search_index, post.clone()).await {
+            log::warn!("failed to index post {}: {e}", post.post_id);
+        }
+    }
+}
+
+/// Indexes posts published since the last pass, then the next pages of history
+async fn backfill_creator(
+    kv: &KVStoreImpl,
+    search_index: &SearchIndexImpl,
+    canister: Principal,
+) -> Result<(), ServerFnError> {
+    let key = progress_key(canister);
+    let mut progress: Progress = kv.read_json(key.clone()).await?.unwrap_or_default();
+
+    let mut latest = None;
+    if let Some(newest) = progress.newest_post_id {
+        let mut start = 0;
+        for _ in 0..PAGES_PER_PASS {
+            let (posts, end) = posts_page(canister, start).await?;
+            if start == 0 {
+                latest = posts.first().map(|p| p.post_id);
+            }
+            let fresh = posts
+                .iter()
+                .filter(|p| p.post_id > newest)
+                .cloned()
+                .collect::<Vec<_>>();
+            index_posts(search_index, &fresh).await;
+            // new posts push the history down
+            progress.history_start += fresh.len() as u64;
+            if end || fresh.len() < posts.len() {
+                break;
+            }
+            start += PAGE_SZ;
+        }
+    }
+
+    for _ in 0..PAGES_PER_PASS {
+        if progress.history_done {
+            break;
+        }
+        let (posts, end) = posts_page(canister, progress.history_start).await?;
+        if progress.history_start == 0 {
+            latest = posts.first().map(|p| p.post_id);
+        }
+        index_posts(search_index, &posts).await;
+        progress.history_start += posts.len() as u64;
+        progress.history_done = end;
+    }
+    progress.newest_post_id = latest.max(progress.newest_post_id);
+
+    kv.write_json(key, &progress).await?;
+    Ok(())
+}
+
+/// Adds creators of the posts the ML feed serves to the backfill
+async fn discover_creators(kv: &KVStoreImpl) -> Result<(), ServerFnError> {
+    let mut items = get_ml_feed_coldstart_clean(Principal::anonymous(), DISCOVERY_SAMPLE, vec![])
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    items.extend(
+        get_ml_feed_coldstart_nsfw(Principal::anonymous(), DISCOVERY_SAMPLE, vec![])
+            .await
+            .map_err(|e| ServerFnError::new(e.to_string()))?,
+    );
+
+    for item in items {
+        track_creator(kv, item.canister_id).await?;
+    }
+    Ok(())
+}
+
+/// Adds a creator to the backfill, e.g. when they publish
+pub(crate) async fn track_creator(
+    kv: &KVStoreImpl,
+    canister: Principal,
+) -> Result<(), ServerFnError> {
+    let member = canister.to_text();
+    if !kv.set_contains(CREATORS_KEY.into(), member.clone()).await? {
+        kv.set_add(CREATORS_KEY.into(), member, 0.0).await?;
+    }
+    Ok(())
+}
+
+async fn backfill_pass(
+    kv: &KVStoreImpl,
+    search_index: &SearchIndexImpl,
+) -> Result<(), ServerFnError> {
+    if let Err(e) = discover_creators(kv).await {
+        log::warn!("search backfill couldn't discover creators: {e}");
+    }
+
+    let creators = kv
+        .set_range_until(CREATORS_KEY.into(), f64::MAX, CREATORS_PER_PASS)
+        .await?;
+    for canister in creators {
+        let canister = Principal::from_text(canister)?;
+        // replicas split the creators between them
+        if !kv.claim(claim_key(canister), CLAIM_TTL_MS).await? {
+            continue;
+        }
+        if let Err(e) = backfill_creator(kv, search_index, canister).await {
+            log::warn!("search backfill failed for {canister}: {e}");
+        }
+        kv.set_add(CREATORS_KEY.into(), canister.to_text(), now_ms())
+            .await?;
+        kv.delete(claim_key(canister)).await?;
+    }
+    Ok(())
+}
+
+/// Indexes the posts and creators already on the backend,
+/// along with anything published without going through `index_published_post`
+pub async fn run_search_backfill(kv: KVStoreImpl, search_index: SearchIndexImpl) {
+    let mut interval = tokio::time::interval(BACKFILL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = backfill_pass(&kv, &search_index).await {
+            log::warn!("search backfill pass failed: {e}");
+        }
+    }
+}
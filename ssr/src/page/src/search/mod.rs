@@ -0,0 +1,259 @@
+#[cfg(feature = "ssr")]
+mod backfill;
+#[cfg(feature = "ssr")]
+pub(crate) mod server_impl;
+
+#[cfg(feature = "ssr")]
+pub use backfill::run_search_backfill;
+
+use candid::Principal;
+use component::{
+    back_btn::BackButton, bullet_loader::BulletLoader, infinite_scroller::InfiniteScroller,
+    title::TitleText,
+};
+use futures::stream::{FuturesOrdered, StreamExt, TryStreamExt};
+use leptos::{html, prelude::*};
+use leptos_icons::*;
+use leptos_meta::Title;
+use leptos_router::hooks::{use_navigate, use_query_map};
+use state::{app_state::AppState, canisters::unauth_canisters, search::CreatorDoc};
+use utils::types::PostId;
+use yral_canisters_common::{
+    cursored_data::{CursoredDataProvider, PageEntry},
+    utils::posts::PostDetails,
+    Canisters,
+};
+
+use crate::hashtag::PostThumbnail;
+
+const SEARCH_CHUNK_SZ: usize = 12;
+
+/// Add a freshly published post (and its creator) to the hashtag and search indexes
+/// only the creator of the post can index it
+#[server]
+pub async fn index_published_post(
+    canister_id: Principal,
+    post_id: u64,
+) -> Result<(), ServerFnError> {
+    server_impl::index_published_post(canister_id, post_id).await
+}
+
+#[server]
+pub async fn search_posts(
+    query: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    server_impl::search_posts(query, start, end).await
+}
+
+#[server]
+pub async fn search_creators(
+    query: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<CreatorDoc>, bool), ServerFnError> {
+    server_impl::search_creators(query, start, end).await
+}
+
+#[derive(Clone)]
+pub struct PostSearchProvider {
+    canisters: Canisters<false>,
+    query: String,
+}
+
+impl CursoredDataProvider for PostSearchProvider {
+    type Data = PostDetails;
+    type Error = ServerFnError;
+
+    async fn get_by_cursor_inner(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<PageEntry<PostDetails>, ServerFnError> {
+        let (post_ids, list_end) = search_posts(self.query.clone(), start, end).await?;
+        let data = post_ids
+            .into_iter()
+            .map(|(canister_id, post_id)| self.canisters.get_post_details(canister_id, post_id))
+            .collect::<FuturesOrdered<_>>()
+            .filter_map(|res| async { res.transpose() })
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(ServerFnError::new)?;
+
+        Ok(PageEntry {
+            data,
+            end: list_end,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CreatorSearchProvider {
+    query: String,
+}
+
+impl CursoredDataProvider for CreatorSearchProvider {
+    type Data = CreatorDoc;
+    type Error = ServerFnError;
+
+    async fn get_by_cursor_inner(
+        &self,
+        start: usize,
+        end: usize,
+    ) -> Result<PageEntry<CreatorDoc>, ServerFnError> {
+        let (data, end) = search_creators(self.query.clone(), start, end).await?;
+        Ok(PageEntry { data, end })
+    }
+}
+
+#[component]
+fn CreatorResult(creator: CreatorDoc, _ref: NodeRef<html::Div>) -> impl IntoView {
+    let profile_url = format!("/profile/{}/posts", creator.principal);
+
+    view! {
+        <div node_ref=_ref class="w-full">
+            <a href=profile_url class="flex flex-row items-center gap-3 p-3 rounded-lg hover:bg-white/5">
+                <img class="w-12 h-12 rounded-full object-cover" src=creator.propic_url />
+                <div class="flex flex-col min-w-0">
+                    <span class="font-semibold truncate">{creator.display_name}</span>
+                    <span class="text-xs text-white/50 truncate">{creator.principal.to_text()}</span>
+                </div>
+            </a>
+        </div>
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SearchTab {
+    Videos,
+    Creators,
+}
+
+fn search_url(query: &str) -> String {
+    format!("/search?q={}", urlencoding::encode(query.trim()))
+}
+
+#[component]
+fn SearchResults(query: String, tab: SearchTab) -> impl IntoView {
+    let empty_text = format!("No results for \"{query}\"");
+    let empty_content = move || {
+        view! { <span class="pt-9 text-lg text-white/70">{empty_text.clone()}</span> }
+    };
+    let custom_loader = move || {
+        view! {
+            <div class="w-full flex justify-center items-center pt-9">
+                <BulletLoader />
+            </div>
+        }
+    };
+
+    match tab {
+        SearchTab::Videos => {
+            let provider = PostSearchProvider {
+                canisters: unauth_canisters(),
+                query,
+            };
+            view! {
+                <div class="flex flex-row gap-y-3 flex-wrap justify-center w-full sm:w-7/12">
+                    <InfiniteScroller
+                        provider
+                        fetch_count=SEARCH_CHUNK_SZ
+                        children=|details, _ref| {
+                            view! { <PostThumbnail details _ref=_ref.unwrap_or_default() /> }
+                        }
+                        empty_content
+                        custom_loader
+                    />
+                </div>
+            }
+            .into_any()
+        }
+        SearchTab::Creators => {
+            let provider = CreatorSearchProvider { query };
+            view! {
+                <div class="flex flex-col w-11/12 sm:w-7/12">
+                    <InfiniteScroller
+                        provider
+                        fetch_count=SEARCH_CHUNK_SZ
+                        children=|creator, _ref| {
+                            view! { <CreatorResult creator _ref=_ref.unwrap_or_default() /> }
+                        }
+                        empty_content
+                        custom_loader
+                    />
+                </div>
+            }
+            .into_any()
+        }
+    }
+}
+
+#[component]
+pub fn SearchPage() -> impl IntoView {
+    let query_map = use_query_map();
+    let query = Memo::new(move |_| query_map.with(|q| q.get("q").unwrap_or_default()));
+    let input = RwSignal::new(query.get_untracked());
+    let tab = RwSignal::new(SearchTab::Videos);
+
+    let app_state = use_context::<AppState>();
+    let page_title = app_state.unwrap().name.to_owned() + " - Search";
+
+    let tab_class = move |t: SearchTab| {
+        if tab() == t {
+            "text-primary-500 border-b-4 border-primary-500 flex justify-center w-full py-2"
+        } else {
+            "text-white flex justify-center w-full py-2"
+        }
+    };
+
+    view! {
+        <Title text=page_title />
+        <div class="min-h-screen w-full flex flex-col text-white pt-2 pb-12 bg-black items-center gap-4">
+            <TitleText justify_center=false>
+                <div class="flex flex-row justify-between">
+                    <BackButton fallback="/".to_string() />
+                    <span class="font-bold text-2xl">Search</span>
+                    <div></div>
+                </div>
+            </TitleText>
+            <form
+                class="flex flex-row items-center gap-2 w-11/12 sm:w-7/12 px-4 py-2 rounded-full bg-white/10"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    let q = input.get_untracked();
+                    if q.trim().is_empty() {
+                        return;
+                    }
+                    use_navigate()(&search_url(&q), Default::default());
+                }
+            >
+                <input
+                    class="w-full bg-transparent text-white outline-none placeholder-white/40"
+                    type="search"
+                    placeholder="Search videos, hashtags and creators"
+                    prop:value=input
+                    on:input=move |ev| input.set(event_target_value(&ev))
+                />
+                <button type="submit" class="text-white text-xl">
+                    <Icon icon=icondata::AiSearchOutlined />
+                </button>
+            </form>
+            <div class="flex flex-row w-11/12 sm:w-7/12 text-center text-lg">
+                <button class=move || tab_class(SearchTab::Videos) on:click=move |_| tab.set(SearchTab::Videos)>
+                    Videos
+                </button>
+                <button class=move || tab_class(SearchTab::Creators) on:click=move |_| tab.set(SearchTab::Creators)>
+                    Creators
+                </button>
+            </div>
+            {move || {
+                let query = query();
+                if query.trim().is_empty() {
+                    return None;
+                }
+                Some(view! { <SearchResults query tab=tab() /> })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,78 @@
+use auth::server_impl::{extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use state::{
+    canisters::unauth_canisters,
+    search::{CreatorDoc, PostDoc, SearchIndex, SearchIndexImpl},
+};
+use utils::types::PostId;
+use yral_canisters_common::utils::posts::PostDetails;
+
+use super::backfill::track_creator;
+
+pub async fn index_published_post(
+    canister_id: Principal,
+    post_id: u64,
+) -> Result<(), ServerFnError> {
+    let Some(caller) = extract_principal_impl().await? else {
+        return Err(ServerFnError::new("not logged in"));
+    };
+    let canisters = unauth_canisters();
+    let Some(post) = canisters.get_post_details(canister_id, post_id).await? else {
+        return Err(ServerFnError::new("post not found"));
+    };
+    if post.poster_principal != caller {
+        return Err(ServerFnError::new("only the creator can index a post"));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    // the rest of the creator's posts are left to the backfill
+    if let Err(e) = track_creator(&kv, canister_id).await {
+        log::warn!("failed to add creator to the search backfill: {e}");
+    }
+    let search_index: SearchIndexImpl = expect_context();
+    index_post_details(&search_index, post).await
+}
+
+/// Adds a post to the search and hashtag indexes
+/// for callers outside of a request, like the publish scheduler and the backfill
+pub(crate) async fn index_post_details(
+    search_index: &SearchIndexImpl,
+    post: PostDetails,
+) -> Result<(), ServerFnError> {
+    search_index
+        .index_creator(CreatorDoc {
+            principal: post.poster_principal,
+            canister_id: post.canister_id,
+            display_name: post.display_name.clone(),
+            propic_url: post.propic_url.clone(),
+        })
+        .await?;
+    search_index
+        .index_post(PostDoc {
+            canister_id: post.canister_id,
+            post_id: post.post_id,
+            description: post.description,
+            hashtags: post.hastags,
+            created_at: post.created_at.as_secs(),
+        })
+        .await
+}
+
+pub async fn search_posts(
+    query: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<PostId>, bool), ServerFnError> {
+    let search_index: SearchIndexImpl = expect_context();
+    search_index.search_posts(&query, start, end).await
+}
+
+pub async fn search_creators(
+    query: String,
+    start: usize,
+    end: usize,
+) -> Result<(Vec<CreatorDoc>, bool), ServerFnError> {
+    let search_index: SearchIndexImpl = expect_context();
+    search_index.search_creators(&query, start, end).await
+}
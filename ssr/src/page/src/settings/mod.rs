@@ -3,12 +3,13 @@ use component::back_btn::BackButton;
 use component::canisters_prov::AuthCansProvider;
 use component::title::TitleText;
 use component::{social::*, toggle::Toggle};
-use consts::NOTIFICATIONS_ENABLED_STORE;
+use consts::{DATA_SAVER_STORE, NOTIFICATIONS_ENABLED_STORE};
 use leptos::html::Input;
 use leptos::{ev, prelude::*};
 use leptos_icons::*;
 use leptos_use::storage::use_local_storage;
 use leptos_use::use_event_listener;
+use utils::consent::{ConsentCategory, ConsentCtx};
 use utils::event_streaming::events::account_connected_reader;
 use utils::host::{show_cdao_page, show_pnd_page};
 use utils::notifications::get_token_for_principal;
@@ -146,6 +147,57 @@ fn EnableNotifications(user_details: ProfileDetails) -> impl IntoView {
     }
 }
 
+#[component]
+fn DataSaver() -> impl IntoView {
+    let (data_saver, set_data_saver, _) =
+        use_local_storage::<bool, FromToStringCodec>(DATA_SAVER_STORE);
+    let toggle_ref = NodeRef::<Input>::new();
+
+    _ = use_event_listener(toggle_ref, ev::change, move |_| {
+        set_data_saver(!data_saver.get_untracked())
+    });
+
+    view! {
+        <div class="grid grid-cols-2 items-center w-full">
+            <div class="flex flex-row gap-4 items-center">
+                <Icon attr:class="text-2xl" icon=icondata::BiDataRegular />
+                <span>Data Saver</span>
+            </div>
+            <div class="justify-self-end">
+                <Toggle checked=data_saver node_ref=toggle_ref />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ConsentToggle(
+    category: ConsentCategory,
+    #[prop(into)] text: String,
+    icon: icondata::Icon,
+) -> impl IntoView {
+    let ctx: ConsentCtx = expect_context();
+    let allowed = Signal::derive(move || ctx.allows(category));
+    let toggle_ref = NodeRef::<Input>::new();
+
+    _ = use_event_listener(toggle_ref, ev::change, move |_| {
+        let consent = ctx.consent.get_untracked().unwrap_or_default();
+        ctx.set(consent.with(category, !allowed.get_untracked()))
+    });
+
+    view! {
+        <div class="grid grid-cols-2 items-center w-full">
+            <div class="flex flex-row gap-4 items-center">
+                <Icon attr:class="text-2xl" icon=icon />
+                <span>{text}</span>
+            </div>
+            <div class="justify-self-end">
+                <Toggle checked=allowed node_ref=toggle_ref />
+            </div>
+        </div>
+    }
+}
+
 #[component]
 pub fn Settings() -> impl IntoView {
     view! {
@@ -163,6 +215,23 @@ pub fn Settings() -> impl IntoView {
                 <AuthCansProvider let:canisters>
                     <EnableNotifications user_details=canisters.profile_details() />
                 </AuthCansProvider>
+                <DataSaver />
+                <span class="text-sm text-white/50">Privacy</span>
+                <ConsentToggle
+                    category=ConsentCategory::Analytics
+                    text="Analytics"
+                    icon=icondata::BiBarChartAltRegular
+                />
+                <ConsentToggle
+                    category=ConsentCategory::ErrorReporting
+                    text="Error Reporting"
+                    icon=icondata::BiBugRegular
+                />
+                <ConsentToggle
+                    category=ConsentCategory::Personalization
+                    text="Personalised Feed"
+                    icon=icondata::BiSlideshowRegular
+                />
             </div>
             <MenuFooter />
         </div>
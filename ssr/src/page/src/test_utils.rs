@@ -0,0 +1,17 @@
+use auth::server_impl::store::{redb_kv::ReDBKV, KVStoreImpl};
+
+/// A fresh on-disk KV store, unique to `name` and this test run
+pub fn test_kv(name: &str) -> KVStoreImpl {
+    let path = std::env::temp_dir().join(format!("page-kv-{name}-{}.db", std::process::id()));
+    _ = std::fs::remove_file(&path);
+    KVStoreImpl::ReDB(ReDBKV::open(path).unwrap())
+}
+
+/// The KV store runs its work on tokio's blocking pool
+pub fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
@@ -1,29 +1,52 @@
 use candid::Principal;
-#[cfg(all(feature = "cloudflare", feature = "ssr"))]
+#[cfg(all(feature = "stream-api", feature = "ssr"))]
 use cf_impl::server_func::*;
-#[cfg(feature = "cloudflare")]
-pub use cf_impl::{publish_video, upload_video_stream};
+#[cfg(all(feature = "stream-api", feature = "ssr"))]
+pub(super) use cf_impl::server_func::{
+    add_post_impl, restrict_video_impl, sample_video_frames_impl,
+};
+#[cfg(feature = "stream-api")]
+pub use cf_impl::upload_video_stream;
 use leptos::prelude::*;
-#[cfg(all(not(feature = "cloudflare"), feature = "ssr"))]
+#[cfg(all(not(feature = "stream-api"), feature = "ssr"))]
 use mock_impl::server_func::*;
-#[cfg(not(feature = "cloudflare"))]
-pub use mock_impl::{publish_video, upload_video_stream};
+#[cfg(all(not(feature = "stream-api"), feature = "ssr"))]
+pub(super) use mock_impl::server_func::{
+    add_post_impl, restrict_video_impl, sample_video_frames_impl,
+};
+#[cfg(not(feature = "stream-api"))]
+pub use mock_impl::upload_video_stream;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UploadInfo {
     pub uid: String,
+    /// tus endpoint the file is uploaded to
     pub upload_url: String,
 }
 
+#[cfg_attr(not(feature = "stream-api"), allow(dead_code))]
+const TUS_VERSION: &str = "1.0.0";
+/// Cloudflare expects chunks in multiples of 256KiB, at least 5MiB except for the last one
+#[cfg_attr(not(feature = "stream-api"), allow(dead_code))]
+const CHUNK_SIZE: u64 = 20 * 256 * 1024;
+
+/// Direct upload URL for a new video by `creator`
+/// the creator is authenticated with the session cookie
+/// `thumbnail_pct` picks the cover frame, as a percentage of the video's duration
 #[server(GetUploadInfo)]
 pub async fn get_upload_info(
     creator: Principal,
     hashtags: Vec<String>,
     description: String,
     file_name: String,
+    upload_length: u64,
+    thumbnail_pct: f32,
 ) -> Result<UploadInfo, ServerFnError> {
-    // TODO(SECURITY): authenticate creator
+    use super::server_impl::{authenticated_creator, consume_upload_quota, record_upload_owner};
+    use super::validators::MAX_VIDEO_SIZE_BYTES;
+
+    let creator = authenticated_creator(creator).await?;
 
     if description.len() < 10 {
         return Err(ServerFnError::Args(
@@ -33,8 +56,63 @@ pub async fn get_upload_info(
     if hashtags.len() > 8 {
         return Err(ServerFnError::Args("Too many hashtags".into()));
     }
+    if upload_length > MAX_VIDEO_SIZE_BYTES {
+        return Err(ServerFnError::Args("Video is too large".into()));
+    }
+    consume_upload_quota(creator).await?;
+
+    let info = get_upload_info_impl(
+        creator,
+        hashtags,
+        description,
+        file_name,
+        upload_length,
+        thumbnail_pct.clamp(0.0, 100.0),
+    )
+    .await?;
+    record_upload_owner(creator, &info.uid).await?;
+
+    Ok(info)
+}
+
+/// Publishes a processed video of the logged in creator
+/// the video is moderated first, the detector's NSFW verdict overrides `is_nsfw`
+#[server(PublishVideo)]
+pub async fn publish_video(
+    creator: Principal,
+    hashtags: Vec<String>,
+    description: String,
+    uid: String,
+    enable_hot_or_not: bool,
+    is_nsfw: bool,
+) -> Result<u64, ServerFnError> {
+    use auth::server_impl::{extract_identity_impl, store::KVStoreImpl};
+    use yral_canisters_common::Canisters;
 
-    get_upload_info_impl(creator, hashtags, description, file_name).await
+    use super::{
+        moderation::server_impl::moderate_video,
+        server_impl::{authenticated_creator, check_upload_owner, publish_checked},
+    };
+
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    check_upload_owner(&kv, creator, &uid).await?;
+    moderate_video(&kv, &uid).await?;
+
+    let identity = extract_identity_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+    let canisters = Canisters::authenticate_with_network(identity, None).await?;
+    publish_checked(
+        &kv,
+        &canisters,
+        hashtags,
+        description,
+        uid,
+        enable_hot_or_not,
+        is_nsfw,
+    )
+    .await
 }
 
 #[server(GetVideoStatus)]
@@ -42,52 +120,114 @@ pub async fn get_video_status(uid: String) -> Result<String, ServerFnError> {
     get_video_status_impl(uid).await
 }
 
-#[cfg(feature = "cloudflare")]
+#[cfg(feature = "stream-api")]
 mod cf_impl {
-    use leptos::prelude::ServerFnError;
-    use yral_canisters_client::individual_user_template::{PostDetailsFromFrontend, Result2};
-
-    use yral_canisters_common::Canisters;
+    use leptos::prelude::*;
 
-    use super::UploadInfo;
+    #[cfg(feature = "hydrate")]
+    use super::super::resumable::wait_while_paused;
+    #[cfg(feature = "hydrate")]
+    use super::CHUNK_SIZE;
+    use super::{super::resumable::UploadProgress, UploadInfo};
 
     #[cfg(feature = "ssr")]
     pub mod server_func {
+        use base64::{prelude::BASE64_STANDARD, Engine};
         use candid::Principal;
+        #[cfg(feature = "cloudflare")]
         use gob_cloudflare::{
-            api::stream_videos::{CreateDownloads, DirectUpload, VideoDetails},
+            api::stream_videos::{CreateDownloads, VideoDetails},
             CloudflareAuth,
         };
         use leptos::prelude::*;
+        use yral_canisters_client::individual_user_template::{PostDetailsFromFrontend, Result2};
+        use yral_canisters_common::Canisters;
 
-        use consts::CF_WATERMARK_UID;
+        use consts::{CF_BASE_URL, CF_STREAM_BASE, CF_WATERMARK_UID};
 
+        use super::super::TUS_VERSION;
         use super::UploadInfo;
-        use std::time::Duration;
+        use crate::upload::validators::MAX_VIDEO_DURATION_SECS;
+        #[cfg(feature = "cloudflare")]
+        use std::env;
+
+        /// API token and account the Stream API is called with
+        #[cfg(feature = "cloudflare")]
+        fn stream_credentials() -> Result<(String, String), ServerFnError> {
+            Ok((env::var("CF_TOKEN")?, env::var("CF_ACCOUNT_ID")?))
+        }
+
+        /// The local mock Stream API accepts any credentials
+        #[cfg(not(feature = "cloudflare"))]
+        fn stream_credentials() -> Result<(String, String), ServerFnError> {
+            Ok(("mock".into(), "mock".into()))
+        }
 
+        /// Creates a tus direct creator upload
+        /// the returned URL accepts the file without any credentials
         pub async fn get_upload_info_impl(
             creator: Principal,
             hashtags: Vec<String>,
             description: String,
             file_name: String,
+            upload_length: u64,
+            thumbnail_pct: f32,
         ) -> Result<UploadInfo, ServerFnError> {
-            let cf_api: CloudflareAuth = expect_context();
-            let req = DirectUpload::default()
-                .creator(creator.to_text())
-                .add_meta("hashtags", hashtags.join(","))
-                .add_meta("description", description)
-                .add_meta("fileName", file_name)
-                .add_meta("uploadType", "challenge")
-                .watermark(CF_WATERMARK_UID)
-                .max_duration(Duration::from_secs(60));
-            let res = cf_api.send_auth(req).await?;
+            let (token, account_id) = stream_credentials()?;
+            let client = reqwest::Client::new();
 
-            Ok(UploadInfo {
-                uid: res.uid,
-                upload_url: res.upload_url,
-            })
+            let max_duration = (MAX_VIDEO_DURATION_SECS as u64).to_string();
+            let metadata = [
+                ("name", file_name.as_str()),
+                ("maxDurationSeconds", max_duration.as_str()),
+                ("watermark", CF_WATERMARK_UID),
+            ]
+            .map(|(key, value)| format!("{key} {}", BASE64_STANDARD.encode(value)))
+            .join(",");
+            let url =
+                CF_BASE_URL.join(&format!("accounts/{account_id}/stream?direct_user=true"))?;
+            let res = client
+                .post(url)
+                .bearer_auth(&token)
+                .header("Tus-Resumable", TUS_VERSION)
+                .header("Upload-Length", upload_length)
+                .header("Upload-Creator", creator.to_text())
+                .header("Upload-Metadata", metadata)
+                .send()
+                .await?
+                .error_for_status()?;
+            let header = |name: &str| {
+                res.headers()
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+                    .ok_or_else(|| ServerFnError::new(format!("missing {name} in tus response")))
+            };
+            let upload_url = header("location")?;
+            let uid = header("stream-media-id")?;
+
+            // tus metadata only carries the upload settings, post details are set on the video
+            let url = CF_BASE_URL.join(&format!("accounts/{account_id}/stream/{uid}"))?;
+            client
+                .post(url)
+                .bearer_auth(&token)
+                .json(&serde_json::json!({
+                    "thumbnailTimestampPct": thumbnail_pct / 100.0,
+                    "meta": {
+                        "hashtags": hashtags.join(","),
+                        "description": description,
+                        "fileName": file_name,
+                        "uploadType": "challenge",
+                    }
+                }))
+                .send()
+                .await?
+                .error_for_status()?;
+
+            Ok(UploadInfo { uid, upload_url })
         }
 
+        #[cfg(feature = "cloudflare")]
         pub async fn get_video_status_impl(uid: String) -> Result<String, ServerFnError> {
             let cf_api: CloudflareAuth = expect_context();
             let req = VideoDetails::new(uid.clone());
@@ -101,62 +241,265 @@ mod cf_impl {
 
             Ok(state)
         }
+
+        /// Details of a video from the Stream API, as returned under `result`
+        async fn video_details(uid: &str) -> Result<serde_json::Value, ServerFnError> {
+            let (token, account_id) = stream_credentials()?;
+            let url = CF_BASE_URL.join(&format!("accounts/{account_id}/stream/{uid}"))?;
+            let mut details: serde_json::Value = reqwest::Client::new()
+                .get(url)
+                .bearer_auth(&token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            Ok(details["result"].take())
+        }
+
+        /// `CloudflareAuth` only talks to the real API, the local mock is called directly
+        #[cfg(not(feature = "cloudflare"))]
+        pub async fn get_video_status_impl(uid: String) -> Result<String, ServerFnError> {
+            let state = video_details(&uid).await?["status"]["state"]
+                .as_str()
+                .ok_or_else(|| ServerFnError::new("missing video state"))?
+                .to_string();
+            if state != "ready" {
+                return Ok(state);
+            }
+            let (token, account_id) = stream_credentials()?;
+            let url = CF_BASE_URL.join(&format!("accounts/{account_id}/stream/{uid}/downloads"))?;
+            reqwest::Client::new()
+                .post(url)
+                .bearer_auth(&token)
+                .send()
+                .await?
+                .error_for_status()?;
+
+            Ok(state)
+        }
+
+        /// `count` evenly spaced frames of a processed video, as base64 encoded jpegs
+        pub async fn sample_video_frames_impl(
+            uid: &str,
+            count: usize,
+        ) -> Result<Vec<String>, ServerFnError> {
+            let client = reqwest::Client::new();
+            let duration = video_details(uid).await?["duration"]
+                .as_f64()
+                .filter(|d| *d > 0.0)
+                .ok_or_else(|| ServerFnError::new("video is not processed yet"))?;
+
+            let mut frames = Vec::with_capacity(count);
+            for i in 0..count {
+                let time = duration * (i as f64 + 0.5) / count as f64;
+                let frame = client
+                    .get(format!("{CF_STREAM_BASE}/{uid}/thumbnails/thumbnail.jpg"))
+                    .query(&[("time", format!("{time:.1}s")), ("height", "480".into())])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .bytes()
+                    .await?;
+                frames.push(BASE64_STANDARD.encode(&frame));
+            }
+
+            Ok(frames)
+        }
+
+        /// Adds the post to the creator's canister and marks it ready to view
+        /// only called once the server has moderated the video
+        pub async fn add_post_impl(
+            canisters: &Canisters<true>,
+            details: PostDetailsFromFrontend,
+        ) -> Result<u64, ServerFnError> {
+            let user = canisters.authenticated_user().await;
+            let post_id = match user.add_post_v_2(details).await? {
+                Result2::Ok(p) => p,
+                Result2::Err(e) => return Err(ServerFnError::new(e)),
+            };
+            user.update_post_as_ready_to_view(post_id).await?;
+            Ok(post_id)
+        }
+
+        /// Stops a video from being streamed without a signed URL
+        /// the upload is kept, in case it has to be reported
+        pub async fn restrict_video_impl(uid: &str) -> Result<(), ServerFnError> {
+            let (token, account_id) = stream_credentials()?;
+            let url = CF_BASE_URL.join(&format!("accounts/{account_id}/stream/{uid}"))?;
+            reqwest::Client::new()
+                .post(url)
+                .bearer_auth(&token)
+                .json(&serde_json::json!({ "requireSignedURLs": true }))
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        }
     }
 
+    /// Uploads `file` in chunks, resuming from wherever the server left off
     pub async fn upload_video_stream(
-        _upload_res: &UploadInfo,
-        _file: &gloo::file::File,
-    ) -> Result<(), gloo::net::Error> {
+        upload_info: &UploadInfo,
+        file: &gloo::file::File,
+        progress: RwSignal<UploadProgress>,
+        paused: Signal<bool>,
+    ) -> Result<(), String> {
         #[cfg(feature = "hydrate")]
         {
-            use gloo::net::http::Request;
-            use leptos::web_sys::FormData;
-            let form = FormData::new().unwrap();
-            form.append_with_blob("file", _file.as_ref()).unwrap();
-            let req = Request::post(&_upload_res.upload_url).body(form).unwrap();
-            req.send().await?;
+            use gloo::timers::future::TimeoutFuture;
+
+            const MAX_RETRIES: u32 = 5;
+
+            let url = &upload_info.upload_url;
+            let total = file.size();
+            let mut offset = tus::upload_offset(url).await?;
+            let mut retries = 0;
+            while offset < total {
+                progress.set(UploadProgress {
+                    uploaded: offset,
+                    total,
+                });
+                wait_while_paused(paused).await;
+
+                let end = (offset + CHUNK_SIZE).min(total);
+                let chunk = file.slice(offset, end);
+                let chunk_start = offset;
+                let res = tus::upload_chunk(url, offset, &chunk, move |sent| {
+                    progress.set(UploadProgress {
+                        uploaded: chunk_start + sent,
+                        total,
+                    })
+                })
+                .await;
+                match res {
+                    Ok(new_offset) => {
+                        offset = new_offset;
+                        retries = 0;
+                    }
+                    Err(e) if retries < MAX_RETRIES => {
+                        log::warn!("chunk upload failed, retrying: {e}");
+                        retries += 1;
+                        TimeoutFuture::new(1000 * 2u32.pow(retries)).await;
+                        // part of the chunk might have made it
+                        offset = tus::upload_offset(url).await.unwrap_or(offset);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            progress.set(UploadProgress {
+                uploaded: total,
+                total,
+            });
+        }
+        #[cfg(not(feature = "hydrate"))]
+        {
+            _ = (upload_info, file, progress, paused);
         }
         Ok(())
     }
 
-    pub async fn publish_video(
-        canisters: Canisters<true>,
-        hashtags: Vec<String>,
-        description: String,
-        uid: String,
-        enable_hot_or_not: bool,
-        is_nsfw: bool,
-    ) -> Result<u64, ServerFnError> {
-        let user = canisters.authenticated_user().await;
-        let res = user
-            .add_post_v_2(PostDetailsFromFrontend {
-                hashtags,
-                description,
-                video_uid: uid,
-                creator_consent_for_inclusion_in_hot_or_not: enable_hot_or_not,
-                is_nsfw,
-            })
-            .await?;
-        let post_id = match res {
-            Result2::Ok(p) => p,
-            Result2::Err(e) => return Err(ServerFnError::new(e)),
-        };
-        user.update_post_as_ready_to_view(post_id).await?;
-        Ok(post_id)
+    #[cfg(feature = "hydrate")]
+    mod tus {
+        use std::{cell::RefCell, rc::Rc};
+
+        use futures::channel::oneshot;
+        use gloo::net::http::{Method, RequestBuilder};
+        use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+        use web_sys::{ProgressEvent, XmlHttpRequest};
+
+        use super::super::TUS_VERSION;
+
+        fn js_err(e: JsValue) -> String {
+            format!("{e:?}")
+        }
+
+        fn parse_offset(offset: Option<String>) -> Result<u64, String> {
+            offset
+                .and_then(|o| o.parse().ok())
+                .ok_or_else(|| "missing Upload-Offset in tus response".into())
+        }
+
+        /// Bytes the server already has
+        pub async fn upload_offset(url: &str) -> Result<u64, String> {
+            let res = RequestBuilder::new(url)
+                .method(Method::HEAD)
+                .header("Tus-Resumable", TUS_VERSION)
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !res.ok() {
+                return Err(format!("upload can't be resumed ({})", res.status()));
+            }
+            parse_offset(res.headers().get("Upload-Offset"))
+        }
+
+        /// Sends `chunk` at `offset`, reporting bytes sent so far to `on_progress`
+        /// returns the new offset
+        /// uses XHR as fetch doesn't report upload progress
+        pub async fn upload_chunk(
+            url: &str,
+            offset: u64,
+            chunk: &gloo::file::Blob,
+            on_progress: impl Fn(u64) + 'static,
+        ) -> Result<u64, String> {
+            let xhr = XmlHttpRequest::new().map_err(js_err)?;
+            xhr.open("PATCH", url).map_err(js_err)?;
+            for (name, value) in [
+                ("Tus-Resumable", TUS_VERSION.to_string()),
+                ("Upload-Offset", offset.to_string()),
+                (
+                    "Content-Type",
+                    "application/offset+octet-stream".to_string(),
+                ),
+            ] {
+                xhr.set_request_header(name, &value).map_err(js_err)?;
+            }
+
+            let on_progress = Closure::<dyn Fn(ProgressEvent)>::new(move |ev: ProgressEvent| {
+                on_progress(ev.loaded() as u64)
+            });
+            xhr.upload()
+                .map_err(js_err)?
+                .set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+
+            let (tx, rx) = oneshot::channel();
+            let tx = Rc::new(RefCell::new(Some(tx)));
+            let on_done = Closure::<dyn Fn()>::new(move || {
+                if let Some(tx) = tx.borrow_mut().take() {
+                    _ = tx.send(());
+                }
+            });
+            xhr.set_onloadend(Some(on_done.as_ref().unchecked_ref()));
+
+            xhr.send_with_opt_blob(Some(chunk.as_ref()))
+                .map_err(js_err)?;
+            _ = rx.await;
+            // the callbacks must outlive the request
+            drop((on_progress, on_done));
+
+            let status = xhr.status().map_err(js_err)?;
+            if status != 204 {
+                return Err(format!("chunk upload failed ({status})"));
+            }
+            parse_offset(xhr.get_response_header("Upload-Offset").ok().flatten())
+        }
     }
 }
 
-#[cfg(not(feature = "cloudflare"))]
+#[cfg(not(feature = "stream-api"))]
 mod mock_impl {
+    use super::super::resumable::{wait_while_paused, UploadProgress};
     use super::UploadInfo;
     use leptos::prelude::*;
-    use yral_canisters_common::Canisters;
 
     #[cfg(feature = "ssr")]
     pub mod server_func {
         use candid::Principal;
         use leptos::prelude::*;
         use std::time::Duration;
+        use yral_canisters_client::individual_user_template::PostDetailsFromFrontend;
+        use yral_canisters_common::Canisters;
 
         use super::UploadInfo;
 
@@ -165,6 +508,8 @@ mod mock_impl {
             _hashtags: Vec<String>,
             _description: String,
             _file_name: String,
+            _upload_length: u64,
+            _thumbnail_pct: f32,
         ) -> Result<UploadInfo, ServerFnError> {
             Ok(UploadInfo {
                 uid: "mock".into(),
@@ -176,27 +521,45 @@ mod mock_impl {
             tokio::time::sleep(Duration::from_secs(2)).await;
             Ok("ready".into())
         }
+
+        /// No processed video to sample, the detector has nothing to flag
+        pub async fn sample_video_frames_impl(
+            _uid: &str,
+            _count: usize,
+        ) -> Result<Vec<String>, ServerFnError> {
+            Ok(vec![])
+        }
+
+        pub async fn restrict_video_impl(_uid: &str) -> Result<(), ServerFnError> {
+            Ok(())
+        }
+
+        pub async fn add_post_impl(
+            _canisters: &Canisters<true>,
+            _details: PostDetailsFromFrontend,
+        ) -> Result<u64, ServerFnError> {
+            Ok(0)
+        }
     }
 
+    /// Pretends to upload `file` in ten chunks
     pub async fn upload_video_stream(
         _upload_res: &UploadInfo,
-        _file: &gloo::file::File,
-    ) -> Result<(), gloo::net::Error> {
+        file: &gloo::file::File,
+        progress: RwSignal<UploadProgress>,
+        paused: Signal<bool>,
+    ) -> Result<(), String> {
         use gloo::timers::future::TimeoutFuture;
-        TimeoutFuture::new(1000).await;
-        Ok(())
-    }
 
-    pub async fn publish_video(
-        _canisters: Canisters<true>,
-        _hashtags: Vec<String>,
-        _description: String,
-        _uid: String,
-        _enable_hot_or_not: bool,
-        _is_nsfw: bool,
-    ) -> Result<u64, ServerFnError> {
-        use gloo::timers::future::TimeoutFuture;
-        TimeoutFuture::new(1000).await;
-        Ok(0)
+        let total = file.size();
+        for step in 0..=10 {
+            wait_while_paused(paused).await;
+            progress.set(UploadProgress {
+                uploaded: total * step / 10,
+                total,
+            });
+            TimeoutFuture::new(100).await;
+        }
+        Ok(())
     }
 }
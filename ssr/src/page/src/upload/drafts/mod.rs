@@ -0,0 +1,126 @@
+#[cfg(feature = "ssr")]
+mod server_impl;
+
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use yral_canisters_common::Canisters;
+
+use crate::search::index_published_post;
+
+#[cfg(feature = "ssr")]
+pub use server_impl::run_publish_scheduler;
+
+/// Drafts kept per creator
+pub const MAX_DRAFTS: usize = 50;
+/// How far ahead a publish can be scheduled
+pub const MAX_SCHEDULE_AHEAD_MS: u64 = 3 * 24 * 60 * 60 * 1000;
+
+/// What happens to a video once it's processed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PublishMode {
+    #[default]
+    Now,
+    Draft,
+    /// ms since the unix epoch
+    Scheduled(u64),
+}
+
+/// An uploaded and processed video, waiting to be published
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    /// Cloudflare Stream video uid
+    pub uid: String,
+    pub hashtags: Vec<String>,
+    pub description: String,
+    pub enable_hot_or_not: bool,
+    pub is_nsfw: bool,
+    /// Set by the server, in ms since the unix epoch
+    pub created_at_ms: u64,
+    /// Set while the draft is scheduled, in ms since the unix epoch
+    pub publish_at_ms: Option<u64>,
+    /// Why the last scheduled publish failed
+    pub publish_error: Option<String>,
+}
+
+/// Drafts of the logged in creator, newest first
+#[server]
+pub async fn list_drafts(creator: Principal) -> Result<Vec<Draft>, ServerFnError> {
+    server_impl::list_drafts(creator).await
+}
+
+/// Saves or updates a draft of the logged in creator
+/// a draft with `publish_at_ms` is published by the server, as the creator, at that time
+#[server]
+pub async fn save_draft(creator: Principal, draft: Draft) -> Result<(), ServerFnError> {
+    server_impl::save_draft(creator, draft).await
+}
+
+/// Deletes a draft, cancelling its scheduled publish
+/// the video stays on Cloudflare until it's cleaned up
+#[server]
+pub async fn delete_draft(creator: Principal, uid: String) -> Result<(), ServerFnError> {
+    server_impl::delete_draft(creator, uid).await
+}
+
+/// Publishes a saved draft and removes it, cancelling its scheduled publish
+#[server]
+pub async fn publish_saved_draft(creator: Principal, uid: String) -> Result<u64, ServerFnError> {
+    server_impl::publish_draft(creator, uid).await
+}
+
+/// Saves a draft's edits and publishes it right away
+pub async fn publish_draft(canisters: Canisters<true>, draft: Draft) -> Result<u64, ServerFnError> {
+    let creator = canisters.user_principal();
+    let uid = draft.uid.clone();
+    save_draft(
+        creator,
+        Draft {
+            publish_at_ms: None,
+            ..draft
+        },
+    )
+    .await?;
+    let post_id = publish_saved_draft(creator, uid).await?;
+    if let Err(e) = index_published_post(canisters.user_canister(), post_id).await {
+        log::warn!("failed to index published post: {e}");
+    }
+
+    Ok(post_id)
+}
+
+/// Parses the value of a `datetime-local` input, in the browser's timezone
+/// into ms since the unix epoch
+pub fn parse_local_datetime(value: &str) -> Option<u64> {
+    if value.is_empty() {
+        return None;
+    }
+    let ms = js_sys::Date::new(&value.into()).get_time();
+    (!ms.is_nan()).then_some(ms as u64)
+}
+
+/// Formats ms since the unix epoch as the value of a `datetime-local` input
+pub fn format_local_datetime(ms: u64) -> String {
+    let date = js_sys::Date::new(&(ms as f64).into());
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes(),
+    )
+}
+
+/// Checks a publish time picked by the creator
+pub fn schedule_validator(publish_at_ms: Option<u64>) -> Result<u64, String> {
+    let publish_at_ms = publish_at_ms.ok_or("Pick a time to publish at")?;
+    let now = js_sys::Date::now() as u64;
+    if publish_at_ms <= now {
+        return Err("Publish time must be in the future".into());
+    }
+    if publish_at_ms > now + MAX_SCHEDULE_AHEAD_MS {
+        return Err("Publish time must be within the next 3 days".into());
+    }
+    Ok(publish_at_ms)
+}
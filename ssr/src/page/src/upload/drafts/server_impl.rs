@@ -0,0 +1,348 @@
+use auth::server_impl::{delegated_identity_for, extract_identity_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use state::search::SearchIndexImpl;
+use web_time::{Duration, SystemTime};
+use yral_canisters_common::Canisters;
+
+use super::{Draft, MAX_DRAFTS, MAX_SCHEDULE_AHEAD_MS};
+use crate::search::server_impl::index_post_details;
+use crate::upload::{
+    hot_or_not::server_impl::account_ineligibility,
+    moderation::server_impl::moderate_video,
+    server_impl::{authenticated_creator, check_upload_owner, publish_checked},
+};
+
+/// Every pending publish across creators, scored by when it's due
+const SCHEDULED_PUBLISHES_KEY: &str = "scheduled-publishes";
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+/// Due publishes taken per run, the rest wait for the next one
+const MAX_PUBLISHES_PER_RUN: usize = 50;
+/// How long a server has to publish a job it claimed before another may retry it
+const PUBLISH_CLAIM_TTL_MS: u64 = 5 * 60 * 1000;
+/// Lifetime of the delegation a scheduled publish acts with
+const PUBLISH_DELEGATION_MAX_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ScheduledPublish {
+    creator: Principal,
+    uid: String,
+    publish_at_ms: u64,
+}
+
+fn job_id(creator: Principal, uid: &str) -> String {
+    format!("{}:{uid}", creator.to_text())
+}
+
+fn job_key(id: &str) -> String {
+    format!("scheduled-publish:{id}")
+}
+
+fn job_claim_key(id: &str) -> String {
+    format!("scheduled-publish-claim:{id}")
+}
+
+/// Uids of the drafts of `creator`, scored by when they were created
+fn drafts_key(creator: Principal) -> String {
+    format!("draft-uids:{}", creator.to_text())
+}
+
+/// Each draft is stored on its own, so saving one can't overwrite another
+fn draft_key(creator: Principal, uid: &str) -> String {
+    format!("draft:{}:{uid}", creator.to_text())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Newest first
+async fn read_drafts(kv: &KVStoreImpl, creator: Principal) -> Result<Vec<Draft>, ServerFnError> {
+    let uids = kv.set_range(drafts_key(creator), 0, MAX_DRAFTS).await?;
+    let mut drafts = Vec::with_capacity(uids.len());
+    for uid in uids {
+        if let Some(draft) = read_draft(kv, creator, &uid).await? {
+            drafts.push(draft);
+        }
+    }
+    Ok(drafts)
+}
+
+async fn read_draft(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    uid: &str,
+) -> Result<Option<Draft>, ServerFnError> {
+    Ok(kv.read_json(draft_key(creator, uid)).await?)
+}
+
+async fn write_draft(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    draft: &Draft,
+) -> Result<(), ServerFnError> {
+    kv.write_json(draft_key(creator, &draft.uid), draft).await?;
+    kv.set_add(
+        drafts_key(creator),
+        draft.uid.clone(),
+        draft.created_at_ms as f64,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn remove_draft(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    uid: &str,
+) -> Result<(), ServerFnError> {
+    kv.set_remove(drafts_key(creator), uid.into()).await?;
+    kv.delete(draft_key(creator, uid)).await?;
+    Ok(())
+}
+
+/// Replaces any earlier schedule of the same draft
+async fn schedule(kv: &KVStoreImpl, job: ScheduledPublish) -> Result<(), ServerFnError> {
+    let id = job_id(job.creator, &job.uid);
+    kv.write_json(job_key(&id), &job).await?;
+    kv.set_add(SCHEDULED_PUBLISHES_KEY.into(), id, job.publish_at_ms as f64)
+        .await?;
+    Ok(())
+}
+
+async fn unschedule(kv: &KVStoreImpl, creator: Principal, uid: &str) -> Result<(), ServerFnError> {
+    let id = job_id(creator, uid);
+    kv.set_remove(SCHEDULED_PUBLISHES_KEY.into(), id.clone())
+        .await?;
+    kv.delete(job_key(&id)).await?;
+    Ok(())
+}
+
+pub async fn list_drafts(creator: Principal) -> Result<Vec<Draft>, ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    read_drafts(&kv, creator).await
+}
+
+pub async fn save_draft(creator: Principal, mut draft: Draft) -> Result<(), ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    if draft.description.len() < 10 {
+        return Err(ServerFnError::Args(
+            "Description must be at least 10 characters".into(),
+        ));
+    }
+    if draft.hashtags.is_empty() || draft.hashtags.len() > 8 {
+        return Err(ServerFnError::Args(
+            "Drafts need between 1 and 8 hashtags".into(),
+        ));
+    }
+
+    let kv: KVStoreImpl = expect_context();
+    check_upload_owner(&kv, creator, &draft.uid).await?;
+    let verdict = moderate_video(&kv, &draft.uid).await?;
+    verdict.check_publishable()?;
+    draft.is_nsfw = verdict.is_nsfw(draft.is_nsfw);
+    if draft.enable_hot_or_not {
+        draft.enable_hot_or_not =
+            !draft.is_nsfw && account_ineligibility(&kv, creator).await?.is_none();
+    }
+
+    let existing = read_draft(&kv, creator, &draft.uid).await?;
+    if existing.is_none() && kv.set_len(drafts_key(creator)).await? >= MAX_DRAFTS {
+        return Err(ServerFnError::new(
+            "Too many drafts, publish or delete some first",
+        ));
+    }
+    draft.created_at_ms = existing.map_or_else(now_ms, |d| d.created_at_ms);
+    draft.publish_error = None;
+
+    if let Some(publish_at_ms) = draft.publish_at_ms {
+        let now = now_ms();
+        if publish_at_ms <= now || publish_at_ms > now + MAX_SCHEDULE_AHEAD_MS {
+            return Err(ServerFnError::Args(
+                "Publish time must be within the next 3 days".into(),
+            ));
+        }
+        schedule(
+            &kv,
+            ScheduledPublish {
+                creator,
+                uid: draft.uid.clone(),
+                publish_at_ms,
+            },
+        )
+        .await?;
+    } else {
+        unschedule(&kv, creator, &draft.uid).await?;
+    }
+
+    write_draft(&kv, creator, &draft).await
+}
+
+pub async fn delete_draft(creator: Principal, uid: String) -> Result<(), ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    unschedule(&kv, creator, &uid).await?;
+    remove_draft(&kv, creator, &uid).await
+}
+
+/// Publishes a saved draft as the logged in creator and removes it
+/// the stored draft is published, not the device's copy of it
+pub async fn publish_draft(creator: Principal, uid: String) -> Result<u64, ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    let draft = read_draft(&kv, creator, &uid)
+        .await?
+        .ok_or_else(|| ServerFnError::new("draft not found"))?;
+    // the scheduler must not publish it as well
+    unschedule(&kv, creator, &uid).await?;
+
+    let identity = extract_identity_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+    let canisters = Canisters::authenticate_with_network(identity, None).await?;
+    // moderated when the draft was saved
+    let post_id = publish_checked(
+        &kv,
+        &canisters,
+        draft.hashtags,
+        draft.description,
+        draft.uid,
+        draft.enable_hot_or_not,
+        draft.is_nsfw,
+    )
+    .await?;
+    // a leftover draft can't be published again, see `publish_checked`
+    if let Err(e) = remove_draft(&kv, creator, &uid).await {
+        log::warn!("failed to remove published draft {uid}: {e}");
+    }
+
+    Ok(post_id)
+}
+
+async fn publish_scheduled(
+    kv: &KVStoreImpl,
+    search_index: &SearchIndexImpl,
+    job: &ScheduledPublish,
+    draft: Draft,
+) -> Result<u64, ServerFnError> {
+    // minted when the job runs, so no credentials are kept with it
+    let identity = delegated_identity_for(kv, job.creator, PUBLISH_DELEGATION_MAX_AGE)
+        .await?
+        .ok_or_else(|| ServerFnError::new("creator's identity not found"))?;
+    let canisters = Canisters::authenticate_with_network(identity, None).await?;
+    let user_canister = canisters.user_canister();
+    // classified when the draft was saved
+    let post_id = publish_checked(
+        kv,
+        &canisters,
+        draft.hashtags,
+        draft.description,
+        draft.uid,
+        draft.enable_hot_or_not,
+        draft.is_nsfw,
+    )
+    .await?;
+
+    // the post is live either way, indexing only helps search
+    match canisters.get_post_details(user_canister, post_id).await {
+        Ok(Some(post)) => {
+            if let Err(e) = index_post_details(search_index, post).await {
+                log::warn!("failed to index scheduled post: {e}");
+            }
+        }
+        Ok(None) => log::warn!("scheduled post {post_id} not found for indexing"),
+        Err(e) => log::warn!("failed to fetch scheduled post for indexing: {e}"),
+    }
+
+    Ok(post_id)
+}
+
+/// Publishes a due job and updates the creator's draft with the result
+/// the job is removed before publishing, a failed or interrupted run is never retried
+/// and leaves the draft for the creator to publish by hand
+async fn run_job(
+    kv: &KVStoreImpl,
+    search_index: &SearchIndexImpl,
+    id: &str,
+) -> Result<(), ServerFnError> {
+    if !kv.claim(job_claim_key(id), PUBLISH_CLAIM_TTL_MS).await? {
+        // another server is publishing it
+        return Ok(());
+    }
+    let Some(job) = kv.read_json::<ScheduledPublish>(job_key(id)).await? else {
+        kv.set_remove(SCHEDULED_PUBLISHES_KEY.into(), id.into())
+            .await?;
+        return Ok(());
+    };
+    if job.publish_at_ms > now_ms() {
+        // rescheduled since it was listed
+        kv.delete(job_claim_key(id)).await?;
+        return Ok(());
+    }
+    unschedule(kv, job.creator, &job.uid).await?;
+    kv.delete(job_claim_key(id)).await?;
+
+    let Some(draft) = read_draft(kv, job.creator, &job.uid).await? else {
+        log::warn!("scheduled draft {} was deleted", job.uid);
+        return Ok(());
+    };
+    match publish_scheduled(kv, search_index, &job, draft).await {
+        Ok(post_id) => {
+            log::info!("published scheduled post {post_id} of {}", job.creator);
+            remove_draft(kv, job.creator, &job.uid).await?;
+        }
+        Err(e) => {
+            log::warn!("scheduled publish of {} failed: {e}", job.uid);
+            // read again, the creator may have edited it meanwhile
+            let Some(mut draft) = read_draft(kv, job.creator, &job.uid).await? else {
+                return Ok(());
+            };
+            // back to a plain draft, unless it was rescheduled meanwhile
+            if draft.publish_at_ms == Some(job.publish_at_ms) {
+                draft.publish_at_ms = None;
+            }
+            draft.publish_error = Some(e.to_string());
+            write_draft(kv, job.creator, &draft).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn publish_due(
+    kv: &KVStoreImpl,
+    search_index: &SearchIndexImpl,
+) -> Result<(), ServerFnError> {
+    let due = kv
+        .set_range_until(
+            SCHEDULED_PUBLISHES_KEY.into(),
+            now_ms() as f64,
+            MAX_PUBLISHES_PER_RUN,
+        )
+        .await?;
+    for id in due {
+        // one failing job doesn't hold up the others
+        if let Err(e) = run_job(kv, search_index, &id).await {
+            log::warn!("failed to run scheduled publish {id}: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Publishes scheduled drafts once they're due
+/// a local stand-in for a job queue, the jobs are kept in the KV store so they survive restarts
+pub async fn run_publish_scheduler(kv: KVStoreImpl, search_index: SearchIndexImpl) {
+    let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+        if let Err(e) = publish_due(&kv, &search_index).await {
+            log::warn!("failed to run scheduled publishes: {e}");
+        }
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(feature = "ssr")]
+pub(crate) mod server_impl;
+
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// How old an account must be before others can bet on its posts
+pub const MIN_ACCOUNT_AGE_MS: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Why a post can't take part in Hot or Not
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum HotOrNotIneligible {
+    #[error("Log in to let others bet on your videos")]
+    NotRegistered,
+    #[error("Your account must be a week old before others can bet on your videos")]
+    AccountTooNew,
+    #[error("NSFW videos can't take part in Hot or Not")]
+    ContentPolicy,
+    #[error("Hot or Not can only be turned on for videos published with it")]
+    PublishedWithout,
+}
+
+/// Betting on a post, as seen by its creator
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HotOrNotStatus {
+    /// Published without Hot or Not
+    Off,
+    Open,
+    /// The betting window is over
+    Closed,
+    /// The creator opted out after publishing
+    OptedOut,
+}
+
+impl HotOrNotStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Hot or Not off",
+            Self::Open => "Betting open",
+            Self::Closed => "Betting closed",
+            Self::OptedOut => "Betting paused",
+        }
+    }
+
+    pub fn participating(&self) -> bool {
+        matches!(self, Self::Open | Self::Closed)
+    }
+}
+
+/// Whether the account of `creator` may put posts up for Hot or Not
+#[server]
+pub async fn hot_or_not_eligibility(
+    creator: Principal,
+) -> Result<Option<HotOrNotIneligible>, ServerFnError> {
+    server_impl::hot_or_not_eligibility(creator).await
+}
+
+/// Opts a published post of `creator` in or out of Hot or Not
+/// opting out closes betting to new votes, placed ones still settle
+/// only posts published with Hot or Not can opt back in
+#[server]
+pub async fn set_hot_or_not_participation(
+    creator: Principal,
+    post_id: u64,
+    enabled: bool,
+) -> Result<(), ServerFnError> {
+    server_impl::set_hot_or_not_participation(creator, post_id, enabled).await
+}
+
+/// Status of a post published with Hot or Not
+#[server]
+pub async fn hot_or_not_status(
+    canister_id: Principal,
+    post_id: u64,
+) -> Result<HotOrNotStatus, ServerFnError> {
+    server_impl::hot_or_not_status((canister_id, post_id)).await
+}
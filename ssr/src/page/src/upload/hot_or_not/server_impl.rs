@@ -0,0 +1,231 @@
+use auth::server_impl::{record_registered_at, registered_at_ms, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use state::canisters::unauth_canisters;
+use utils::types::PostId;
+use web_time::SystemTime;
+use yral_canisters_client::individual_user_template::{
+    BettingStatus, Result13, Result14, SessionType,
+};
+use yral_canisters_common::{utils::posts::PostDetails, Canisters};
+
+use super::{HotOrNotIneligible, HotOrNotStatus, MIN_ACCOUNT_AGE_MS};
+use crate::upload::{
+    moderation::server_impl::cached_moderation, server_impl::authenticated_creator,
+};
+
+fn opted_out_key((canister_id, post_id): PostId) -> String {
+    format!("hot-or-not-opted-out:{}:{post_id}", canister_id.to_text())
+}
+
+fn eligibility_key(creator: Principal) -> String {
+    format!("hot-or-not-eligibility:{}", creator.to_text())
+}
+
+/// Posts paged through when backfilling an account's registration time
+const BACKFILL_PAGE_SIZE: u64 = 100;
+const MAX_BACKFILL_PAGES: u64 = 10;
+/// How long an account check is reused when placing bets
+const ELIGIBILITY_TTL_MS: u64 = 10 * 60 * 1000;
+
+/// Result of [`account_ineligibility`], cached so bets don't query the canisters each time
+#[derive(Serialize, Deserialize)]
+struct CachedEligibility {
+    checked_at_ms: u64,
+    ineligible: Option<HotOrNotIneligible>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// When the account registered
+/// accounts that registered before this was tracked are backfilled from their oldest post,
+/// those without posts have nothing to date them by
+async fn account_registered_at(
+    kv: &KVStoreImpl,
+    canisters: &Canisters<false>,
+    user_canister: Principal,
+    creator: Principal,
+) -> Result<Option<u64>, ServerFnError> {
+    if let Some(registered_at) = registered_at_ms(kv, creator).await? {
+        return Ok(Some(registered_at));
+    }
+
+    let user = canisters.individual_user(user_canister).await;
+    let mut oldest_post_ms = None;
+    for page in 0..MAX_BACKFILL_PAGES {
+        let posts = match user
+            .get_posts_of_this_user_profile_with_pagination_cursor(
+                page * BACKFILL_PAGE_SIZE,
+                BACKFILL_PAGE_SIZE,
+            )
+            .await?
+        {
+            Result13::Ok(posts) => posts,
+            Result13::Err(_) => break,
+        };
+        let end = posts.len() < BACKFILL_PAGE_SIZE as usize;
+        // newest first, the last page holds the oldest
+        oldest_post_ms = posts
+            .iter()
+            .map(|p| p.created_at.secs_since_epoch * 1000)
+            .chain(oldest_post_ms)
+            .min();
+        if end {
+            break;
+        }
+    }
+
+    match oldest_post_ms {
+        Some(at) => Ok(Some(record_registered_at(kv, creator, at).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Account level checks, the content of each post is checked on its own
+pub(crate) async fn account_ineligibility(
+    kv: &KVStoreImpl,
+    creator: Principal,
+) -> Result<Option<HotOrNotIneligible>, ServerFnError> {
+    let canisters = unauth_canisters();
+    let Some(user_canister) = canisters
+        .get_individual_canister_by_user_principal(creator)
+        .await?
+    else {
+        return Ok(Some(HotOrNotIneligible::NotRegistered));
+    };
+    let session = canisters
+        .individual_user(user_canister)
+        .await
+        .get_session_type()
+        .await?;
+    if !matches!(session, Result14::Ok(SessionType::RegisteredSession)) {
+        return Ok(Some(HotOrNotIneligible::NotRegistered));
+    }
+
+    let registered_at = account_registered_at(kv, &canisters, user_canister, creator).await?;
+    if registered_at.is_none_or(|at| at + MIN_ACCOUNT_AGE_MS > now_ms()) {
+        return Ok(Some(HotOrNotIneligible::AccountTooNew));
+    }
+
+    Ok(None)
+}
+
+/// [`account_ineligibility`], reusing a recent result
+pub(crate) async fn cached_account_ineligibility(
+    kv: &KVStoreImpl,
+    creator: Principal,
+) -> Result<Option<HotOrNotIneligible>, ServerFnError> {
+    let key = eligibility_key(creator);
+    if let Some(cached) = kv.read_json::<CachedEligibility>(key.clone()).await? {
+        if cached.checked_at_ms + ELIGIBILITY_TTL_MS > now_ms() {
+            return Ok(cached.ineligible);
+        }
+    }
+    let ineligible = account_ineligibility(kv, creator).await?;
+    kv.write_json(
+        key,
+        &CachedEligibility {
+            checked_at_ms: now_ms(),
+            ineligible,
+        },
+    )
+    .await?;
+    Ok(ineligible)
+}
+
+/// Whether a post with the given video may take part, for an eligible account
+pub(crate) async fn content_allows_hot_or_not(
+    kv: &KVStoreImpl,
+    uid: &str,
+    is_nsfw: bool,
+) -> Result<bool, ServerFnError> {
+    let verdict = cached_moderation(kv, uid).await?.unwrap_or_default();
+    Ok(!verdict.csam_detected && !verdict.is_nsfw(is_nsfw))
+}
+
+pub(crate) async fn is_opted_out(kv: &KVStoreImpl, post: PostId) -> Result<bool, ServerFnError> {
+    Ok(kv.read_json(opted_out_key(post)).await?.unwrap_or_default())
+}
+
+/// Whether bets may be placed on `post`, on top of its betting status
+/// the creator may have opted out, lost eligibility or had the video flagged since publishing
+pub(crate) async fn post_allows_bets(
+    kv: &KVStoreImpl,
+    post: &PostDetails,
+) -> Result<bool, ServerFnError> {
+    if is_opted_out(kv, (post.canister_id, post.post_id)).await? {
+        return Ok(false);
+    }
+    if !content_allows_hot_or_not(kv, &post.uid, post.is_nsfw).await? {
+        return Ok(false);
+    }
+    Ok(cached_account_ineligibility(kv, post.poster_principal)
+        .await?
+        .is_none())
+}
+
+pub async fn hot_or_not_eligibility(
+    creator: Principal,
+) -> Result<Option<HotOrNotIneligible>, ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    account_ineligibility(&kv, creator).await
+}
+
+pub async fn set_hot_or_not_participation(
+    creator: Principal,
+    post_id: u64,
+    enabled: bool,
+) -> Result<(), ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    let canisters = unauth_canisters();
+    let user_canister = canisters
+        .get_individual_canister_by_user_principal(creator)
+        .await?
+        .ok_or_else(|| ServerFnError::new("Failed to get user canister"))?;
+
+    if enabled {
+        let post = canisters
+            .get_post_details(user_canister, post_id)
+            .await?
+            .ok_or_else(|| ServerFnError::new("post not found"))?;
+        // the canister only opens betting on posts published with consent,
+        // opting in afterwards can only undo an opt out
+        if post.hot_or_not_feed_ranking_score.is_none() {
+            return Err(ServerFnError::new(HotOrNotIneligible::PublishedWithout));
+        }
+        if let Some(reason) = account_ineligibility(&kv, creator).await? {
+            return Err(ServerFnError::new(reason));
+        }
+        if !content_allows_hot_or_not(&kv, &post.uid, post.is_nsfw).await? {
+            return Err(ServerFnError::new(HotOrNotIneligible::ContentPolicy));
+        }
+    }
+
+    kv.write_json(opted_out_key((user_canister, post_id)), &!enabled)
+        .await?;
+    Ok(())
+}
+
+pub async fn hot_or_not_status(post: PostId) -> Result<HotOrNotStatus, ServerFnError> {
+    let kv: KVStoreImpl = expect_context();
+    if is_opted_out(&kv, post).await? {
+        return Ok(HotOrNotStatus::OptedOut);
+    }
+    let status = unauth_canisters()
+        .individual_user(post.0)
+        .await
+        .get_hot_or_not_bet_details_for_this_post(post.1)
+        .await?;
+    Ok(match status {
+        BettingStatus::BettingOpen { .. } => HotOrNotStatus::Open,
+        _ => HotOrNotStatus::Closed,
+    })
+}
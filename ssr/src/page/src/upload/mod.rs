@@ -1,5 +1,13 @@
 mod cf_upload;
-mod validators;
+pub mod drafts;
+pub mod hot_or_not;
+pub mod moderation;
+#[cfg(feature = "hydrate")]
+mod preprocess;
+mod resumable;
+#[cfg(feature = "ssr")]
+mod server_impl;
+pub(crate) mod validators;
 mod video_upload;
 use leptos_meta::*;
 
@@ -17,7 +25,11 @@ use leptos::{
     prelude::*,
 };
 
+use cf_upload::UploadInfo;
+use drafts::{parse_local_datetime, schedule_validator, PublishMode};
+use hot_or_not::hot_or_not_eligibility;
 use leptos_router::components::Redirect;
+use resumable::use_pending_upload;
 use validators::{description_validator, hashtags_validator};
 use video_upload::{PreVideoUpload, VideoUploader};
 
@@ -28,6 +40,11 @@ struct UploadParams {
     description: String,
     enable_hot_or_not: bool,
     is_nsfw: bool,
+    /// Cover frame, as a percentage of the video's duration
+    thumbnail_pct: f32,
+    publish: PublishMode,
+    /// Upload started in an earlier visit, continued from where it stopped
+    resume: Option<UploadInfo>,
 }
 
 #[component]
@@ -38,6 +55,10 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>, LocalStorage>
     let hashtags_err = RwSignal::new(String::new());
     let hashtags_err_memo = Memo::new(move |_| hashtags_err());
     let file_blob = RwSignal::new_local(None::<FileWithUrl>);
+    let cover_pct = RwSignal::new(0.0f32);
+    let scheduling = RwSignal::new(false);
+    let publish_mode = RwSignal::new(PublishMode::Now);
+    let schedule_err = RwSignal::new(String::new());
     let desc = NodeRef::<Textarea>::new();
     let invalid_form = Memo::new(move |_| {
         // Description error
@@ -50,11 +71,24 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>, LocalStorage>
                 || hashtags.with(|hashtags| hashtags.is_empty())
                 // Description is empty
                 || desc.get().map(|d| d.value().is_empty()).unwrap_or(true)
+                // Publish time is missing or invalid
+                || scheduling() && !matches!(publish_mode(), PublishMode::Scheduled(_))
     });
     let hashtag_inp = NodeRef::<Input>::new();
     let enable_hot_or_not = NodeRef::<Input>::new();
     let is_nsfw = NodeRef::<Input>::new();
     let canister_store = auth_canisters_store();
+    // `None` while unknown, `Some(None)` if the creator may opt in
+    let hot_or_not_eligibility = LocalResource::new(move || {
+        let creator = canister_store.with(|c| c.as_ref().map(|c| c.user_principal()));
+        async move { hot_or_not_eligibility(creator?).await.ok() }
+    });
+    let hot_or_not_eligible = move || {
+        hot_or_not_eligibility
+            .get()
+            .and_then(|e| e.take())
+            .is_some_and(|e| e.is_none())
+    };
 
     VideoUploadInitiated.send_event();
 
@@ -75,14 +109,45 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>, LocalStorage>
             file_blob,
             hashtags,
             description,
-            enable_hot_or_not: false,
+            enable_hot_or_not: hot_or_not_eligible()
+                && enable_hot_or_not
+                    .get_untracked()
+                    .map(|v| v.checked())
+                    .unwrap_or_default(),
             is_nsfw: is_nsfw
                 .get_untracked()
                 .map(|v| v.checked())
                 .unwrap_or_default(),
+            thumbnail_pct: cover_pct.get_untracked(),
+            publish: publish_mode.get_untracked(),
+            resume: None,
         }));
     };
 
+    // picking the file of an unfinished upload again resumes it
+    let (pending_upload, set_pending_upload) = use_pending_upload();
+    Effect::new(move |_| {
+        let Some(file_blob) = file_blob.get() else {
+            return;
+        };
+        let Some(pending) = pending_upload.get_untracked() else {
+            return;
+        };
+        if !pending.matches(&file_blob.file) {
+            return;
+        }
+        trigger_upload.set(Some(UploadParams {
+            file_blob,
+            hashtags: pending.hashtags,
+            description: pending.description,
+            enable_hot_or_not: pending.enable_hot_or_not,
+            is_nsfw: pending.is_nsfw,
+            thumbnail_pct: cover_pct.get_untracked(),
+            publish: pending.publish,
+            resume: Some(pending.info),
+        }));
+    });
+
     let hashtag_on_input = move |hts| match hashtags_validator(hts) {
         Ok(hts) => {
             hashtags.set(hts);
@@ -103,8 +168,28 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>, LocalStorage>
     });
 
     view! {
-        <PreVideoUpload file_blob=file_blob.write_only() />
+        <PreVideoUpload file_blob=file_blob.write_only() cover_pct />
         <div class="flex flex-col gap-4 lg:basis-7/12">
+            {move || {
+                pending_upload
+                    .get()
+                    .map(|pending| {
+                        view! {
+                            <div class="flex flex-row items-center gap-4 p-4 bg-neutral-800 rounded-md">
+                                <span class="grow text-sm text-white/80">
+                                    "Your upload of " {pending.file_name}
+                                    " didn't finish. Select the same video to resume it."
+                                </span>
+                                <button
+                                    class="px-4 py-1 rounded-full bg-white/10 text-sm"
+                                    on:click=move |_| set_pending_upload.set(None)
+                                >
+                                    Discard
+                                </button>
+                            </div>
+                        }
+                    })
+            }}
             <div class="flex flex-col gap-y-2">
                 <Show when=move || { description_err.with(| description_err | ! description_err.is_empty()) }>
                     <span class="text-red-500 text-sm">{desc_err_memo()}</span>
@@ -144,8 +229,79 @@ fn PreUploadView(trigger_upload: WriteSignal<Option<UploadParams>, LocalStorage>
                 />
             </div>
             <div class="flex flex-col gap-y-2">
-                // <ToggleWithLabel node_ref=enable_hot_or_not lab="Participate in Hot or Not"/>
-                <ToggleWithLabel lab="NSFW" />
+                <Suspense>
+                    {move || {
+                        hot_or_not_eligibility
+                            .get()
+                            .and_then(|e| e.take())
+                            .map(|ineligible| match ineligible {
+                                None => {
+                                    view! {
+                                        <ToggleWithLabel
+                                            node_ref=enable_hot_or_not
+                                            lab="Participate in Hot or Not"
+                                        />
+                                    }
+                                        .into_any()
+                                }
+                                Some(reason) => {
+                                    view! {
+                                        <span class="text-sm text-neutral-500">
+                                            {reason.to_string()}
+                                        </span>
+                                    }
+                                        .into_any()
+                                }
+                            })
+                    }}
+                </Suspense>
+                <ToggleWithLabel node_ref=is_nsfw lab="NSFW" />
+            </div>
+            <div class="flex flex-col gap-y-2">
+                <Show
+                    when=move || { schedule_err.with(|schedule_err| !schedule_err.is_empty()) }
+                    fallback=|| {
+                        view! { <h3 class="font-semibold text-neutral-600">When to publish</h3> }
+                    }
+                >
+                    <h3 class="text-red-500 font-semibold">{schedule_err}</h3>
+                </Show>
+                <select
+                    class="p-4 bg-neutral-800 rounded-md"
+                    on:change=move |ev| {
+                        let mode = event_target_value(&ev);
+                        scheduling.set(mode == "schedule");
+                        schedule_err.set(String::new());
+                        publish_mode
+                            .set(
+                                if mode == "draft" { PublishMode::Draft } else { PublishMode::Now },
+                            );
+                    }
+                >
+                    <option value="now" selected>
+                        Publish now
+                    </option>
+                    <option value="draft">Save as draft</option>
+                    <option value="schedule">Schedule</option>
+                </select>
+                <Show when=scheduling>
+                    <input
+                        class="p-4 bg-neutral-800 rounded-md"
+                        type="datetime-local"
+                        on:input=move |ev| {
+                            match schedule_validator(parse_local_datetime(&event_target_value(&ev))) {
+                                Ok(at) => {
+                                    schedule_err.set(String::new());
+                                    publish_mode.set(PublishMode::Scheduled(at));
+                                }
+                                Err(e) => {
+                                    schedule_err.set(e);
+                                    publish_mode.set(PublishMode::Now);
+                                }
+                            }
+                        }
+                    />
+                </Show>
             </div>
             <button
                 on:click=move |_| on_submit()
@@ -0,0 +1,42 @@
+#[cfg(feature = "ssr")]
+pub(crate) mod server_impl;
+
+use candid::Principal;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Frames of a video run through the NSFW detector
+pub const SAMPLED_FRAMES: usize = 5;
+
+/// Verdict of the NSFW detector on an uploaded video
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VideoModeration {
+    pub is_nsfw: bool,
+    /// Such videos can never be published
+    pub csam_detected: bool,
+}
+
+impl VideoModeration {
+    /// Overrides the creator's own NSFW flag if the detector disagrees
+    pub fn is_nsfw(&self, declared: bool) -> bool {
+        declared || self.is_nsfw
+    }
+
+    pub fn check_publishable(&self) -> Result<(), ServerFnError> {
+        if self.csam_detected {
+            return Err(ServerFnError::new(
+                "This video violates our content policy and can't be published",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Classifies a processed video of `creator`, the result is kept for later publishes
+#[server]
+pub async fn classify_video(
+    creator: Principal,
+    uid: String,
+) -> Result<VideoModeration, ServerFnError> {
+    server_impl::classify_video(creator, uid).await
+}
@@ -0,0 +1,57 @@
+use auth::server_impl::store::KVStoreImpl;
+use candid::Principal;
+use leptos::prelude::*;
+use utils::token::nsfw::detect_nsfw_img;
+
+use super::{VideoModeration, SAMPLED_FRAMES};
+use crate::upload::{
+    cf_upload::{restrict_video_impl, sample_video_frames_impl},
+    server_impl::{authenticated_creator, check_upload_owner},
+};
+
+fn moderation_key(uid: &str) -> String {
+    format!("video-moderation:{uid}")
+}
+
+/// Verdict of an earlier classification of the video
+pub(crate) async fn cached_moderation(
+    kv: &KVStoreImpl,
+    uid: &str,
+) -> Result<Option<VideoModeration>, ServerFnError> {
+    Ok(kv.read_json(moderation_key(uid)).await?)
+}
+
+/// Classifies the video unless it already was
+/// must be called while handling a request, the detector channel comes from its context
+pub(crate) async fn moderate_video(
+    kv: &KVStoreImpl,
+    uid: &str,
+) -> Result<VideoModeration, ServerFnError> {
+    if let Some(verdict) = cached_moderation(kv, uid).await? {
+        return Ok(verdict);
+    }
+
+    let mut verdict = VideoModeration::default();
+    for frame in sample_video_frames_impl(uid, SAMPLED_FRAMES).await? {
+        let info = detect_nsfw_img(frame).await?;
+        verdict.is_nsfw |= info.is_nsfw;
+        verdict.csam_detected |= info.csam_detected;
+    }
+    if verdict.csam_detected {
+        log::warn!("CSAM detected in video {uid}, restricting playback");
+        restrict_video_impl(uid).await?;
+    }
+
+    kv.write_json(moderation_key(uid), &verdict).await?;
+    Ok(verdict)
+}
+
+pub async fn classify_video(
+    creator: Principal,
+    uid: String,
+) -> Result<VideoModeration, ServerFnError> {
+    let creator = authenticated_creator(creator).await?;
+    let kv: KVStoreImpl = expect_context();
+    check_upload_owner(&kv, creator, &uid).await?;
+    moderate_video(&kv, &uid).await
+}
@@ -0,0 +1,141 @@
+use std::future::Future;
+
+use futures::channel::oneshot;
+use gloo::events::EventListener;
+use leptos::prelude::*;
+use utils::web::FileWithUrl;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    BlobEvent, EventTarget, File, FilePropertyBag, HtmlVideoElement, MediaRecorder,
+    MediaRecorderOptions, MediaStream,
+};
+
+/// Formats tried in order for the trimmed video
+const RECORDER_MIME_TYPES: [&str; 3] = ["video/webm;codecs=vp9,opus", "video/webm", "video/mp4"];
+
+fn js_err(e: JsValue) -> String {
+    e.dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| e.as_string())
+        .unwrap_or_else(|| format!("{e:?}"))
+}
+
+/// Resolves on the next `event` fired by `target`
+/// the listener is attached right away, so the event can't be missed
+fn next_event(target: &EventTarget, event: &'static str) -> impl Future<Output = ()> {
+    let (tx, rx) = oneshot::channel();
+    let listener = EventListener::once(target, event, move |_| _ = tx.send(()));
+    async move {
+        _ = rx.await;
+        drop(listener);
+    }
+}
+
+fn capture_stream(video: &HtmlVideoElement) -> Result<MediaStream, String> {
+    // Firefox only has the prefixed version
+    let capture = ["captureStream", "mozCaptureStream"]
+        .into_iter()
+        .find_map(|name| {
+            js_sys::Reflect::get(video, &name.into())
+                .ok()?
+                .dyn_into::<js_sys::Function>()
+                .ok()
+        })
+        .ok_or("Trimming videos isn't supported on this browser")?;
+    capture
+        .call0(video)
+        .map_err(js_err)?
+        .dyn_into::<MediaStream>()
+        .map_err(js_err)
+}
+
+/// Keeps `duration` seconds of `file` starting at `start`
+/// browsers can't cut a video without re-encoding it, so the clip is played
+/// and recorded in real time
+pub async fn trim_video(
+    file: &FileWithUrl,
+    start: f64,
+    duration: f64,
+) -> Result<FileWithUrl, String> {
+    let mime_type = RECORDER_MIME_TYPES
+        .into_iter()
+        .find(|t| MediaRecorder::is_type_supported(t))
+        .ok_or("Trimming videos isn't supported on this browser")?;
+
+    let video: HtmlVideoElement = document()
+        .create_element("video")
+        .map_err(js_err)?
+        .unchecked_into();
+    // muting the element doesn't mute the captured audio
+    video.set_muted(true);
+    video.set_preload("auto");
+    let loaded = next_event(&video, "loadedmetadata");
+    video.set_src(&file.url);
+    loaded.await;
+    let seeked = next_event(&video, "seeked");
+    video.set_current_time(start);
+    seeked.await;
+
+    let stream = capture_stream(&video)?;
+    let options = MediaRecorderOptions::new();
+    options.set_mime_type(mime_type);
+    let recorder =
+        MediaRecorder::new_with_media_stream_and_media_recorder_options(&stream, &options)
+            .map_err(js_err)?;
+
+    let chunks = js_sys::Array::new();
+    let _on_data = EventListener::new(&recorder, "dataavailable", {
+        let chunks = chunks.clone();
+        move |ev| {
+            if let Some(data) = ev.dyn_ref::<BlobEvent>().and_then(|ev| ev.data()) {
+                chunks.push(&data);
+            }
+        }
+    });
+    let end = start + duration;
+    let stop = {
+        let (video, recorder) = (video.clone(), recorder.clone());
+        move || {
+            _ = video.pause();
+            _ = recorder.stop();
+        }
+    };
+    let _on_time = EventListener::new(&video, "timeupdate", {
+        let (video, stop) = (video.clone(), stop.clone());
+        move |_| {
+            if video.current_time() >= end {
+                stop();
+            }
+        }
+    });
+    let _on_end = EventListener::once(&video, "ended", move |_| stop());
+    let stopped = next_event(&recorder, "stop");
+
+    recorder.start().map_err(js_err)?;
+    JsFuture::from(video.play().map_err(js_err)?)
+        .await
+        .map_err(js_err)?;
+    stopped.await;
+
+    video.remove_attribute("src").map_err(js_err)?;
+    video.load();
+
+    let (container, ext) = if mime_type.starts_with("video/mp4") {
+        ("video/mp4", "mp4")
+    } else {
+        ("video/webm", "webm")
+    };
+    let file_name = file.file.name();
+    let stem = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(&file_name);
+    let props = FilePropertyBag::new();
+    props.set_type(container);
+    let trimmed =
+        File::new_with_blob_sequence_and_options(&chunks, &format!("{stem}-trimmed.{ext}"), &props)
+            .map_err(js_err)?;
+
+    Ok(FileWithUrl::new(trimmed.into()))
+}
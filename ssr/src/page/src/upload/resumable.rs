@@ -0,0 +1,61 @@
+use codee::string::JsonSerdeCodec;
+use consts::PENDING_UPLOAD_STORE;
+use gloo::timers::future::TimeoutFuture;
+use leptos::prelude::*;
+use leptos_use::storage::use_local_storage;
+use serde::{Deserialize, Serialize};
+
+use super::{cf_upload::UploadInfo, drafts::PublishMode};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UploadProgress {
+    pub uploaded: u64,
+    pub total: u64,
+}
+
+impl UploadProgress {
+    /// Fraction of the file uploaded, between 0 and 1
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.uploaded as f64 / self.total as f64
+    }
+}
+
+/// Upload saved to local storage so it can be resumed after a reload
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PendingUpload {
+    pub info: UploadInfo,
+    /// The same file has to be picked again to resume
+    pub file_name: String,
+    pub file_size: u64,
+    pub hashtags: Vec<String>,
+    pub description: String,
+    pub enable_hot_or_not: bool,
+    pub is_nsfw: bool,
+    #[serde(default)]
+    pub publish: PublishMode,
+}
+
+impl PendingUpload {
+    pub fn matches(&self, file: &gloo::file::File) -> bool {
+        self.file_name == file.name() && self.file_size == file.size()
+    }
+}
+
+pub fn use_pending_upload() -> (
+    Signal<Option<PendingUpload>>,
+    WriteSignal<Option<PendingUpload>>,
+) {
+    let (pending, set_pending, _) =
+        use_local_storage::<Option<PendingUpload>, JsonSerdeCodec>(PENDING_UPLOAD_STORE);
+    (pending, set_pending)
+}
+
+/// Waits for a paused upload to be resumed
+pub async fn wait_while_paused(paused: Signal<bool>) {
+    while paused.get_untracked() {
+        TimeoutFuture::new(300).await;
+    }
+}
@@ -0,0 +1,198 @@
+use auth::server_impl::{extract_principal_impl, store::KVStoreImpl};
+use candid::Principal;
+use leptos::prelude::*;
+use web_time::SystemTime;
+use yral_canisters_client::individual_user_template::PostDetailsFromFrontend;
+use yral_canisters_common::Canisters;
+
+use super::{
+    cf_upload::add_post_impl, hot_or_not::server_impl::account_ineligibility,
+    moderation::server_impl::cached_moderation,
+};
+
+/// Direct upload URLs a creator can request per day
+pub const MAX_UPLOADS_PER_DAY: u32 = 20;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Uploads `creator` started on `day`, days since the unix epoch
+fn upload_quota_key(creator: Principal, day: u64) -> String {
+    format!("upload-quota:{}:{day}", creator.to_text())
+}
+
+/// Who requested the upload of a video
+fn upload_owner_key(uid: &str) -> String {
+    format!("upload-owner:{uid}")
+}
+
+/// Videos `creator` published, so none is posted twice
+fn published_uploads_key(creator: Principal) -> String {
+    format!("published-uploads:{}", creator.to_text())
+}
+
+/// The caller, from the refresh token cookie
+/// rejects the request if it doesn't match the `claimed` creator
+pub async fn authenticated_creator(claimed: Principal) -> Result<Principal, ServerFnError> {
+    let creator = extract_principal_impl()
+        .await?
+        .ok_or_else(|| ServerFnError::new("not logged in"))?;
+
+    if creator == Principal::anonymous() {
+        return Err(ServerFnError::new("not logged in"));
+    }
+    if creator != claimed {
+        return Err(ServerFnError::new(
+            "creator does not match the authenticated user",
+        ));
+    }
+
+    Ok(creator)
+}
+
+pub async fn record_upload_owner(creator: Principal, uid: &str) -> Result<(), ServerFnError> {
+    let kv: KVStoreImpl = expect_context();
+    kv.write_json(upload_owner_key(uid), &creator).await?;
+    Ok(())
+}
+
+/// Rejects videos `creator` didn't upload
+pub async fn check_upload_owner(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    uid: &str,
+) -> Result<(), ServerFnError> {
+    let owner: Option<Principal> = kv.read_json(upload_owner_key(uid)).await?;
+    if owner != Some(creator) {
+        return Err(ServerFnError::new("video was not uploaded by this creator"));
+    }
+    Ok(())
+}
+
+/// Publishes a video as the creator `canisters` is authenticated as
+/// the video must already be moderated, its verdict decides the NSFW flag and blocks CSAM
+pub(crate) async fn publish_checked(
+    kv: &KVStoreImpl,
+    canisters: &Canisters<true>,
+    hashtags: Vec<String>,
+    description: String,
+    uid: String,
+    enable_hot_or_not: bool,
+    is_nsfw: bool,
+) -> Result<u64, ServerFnError> {
+    check_upload_owner(kv, canisters.user_principal(), &uid).await?;
+    let verdict = cached_moderation(kv, &uid)
+        .await?
+        .ok_or_else(|| ServerFnError::new("video was never classified"))?;
+    verdict.check_publishable()?;
+    let is_nsfw = verdict.is_nsfw(is_nsfw);
+    // NSFW posts can't be bet on, nor can posts of accounts that aren't eligible
+    let enable_hot_or_not = enable_hot_or_not
+        && !is_nsfw
+        && account_ineligibility(kv, canisters.user_principal())
+            .await?
+            .is_none();
+
+    let creator = canisters.user_principal();
+    mark_published(kv, creator, &uid).await?;
+    let res = add_post_impl(
+        canisters,
+        PostDetailsFromFrontend {
+            hashtags,
+            description,
+            video_uid: uid.clone(),
+            creator_consent_for_inclusion_in_hot_or_not: enable_hot_or_not,
+            is_nsfw,
+        },
+    )
+    .await;
+    if res.is_err() {
+        // not posted, it can be published again
+        if let Err(e) = kv.set_remove(published_uploads_key(creator), uid).await {
+            log::warn!("failed to release published upload: {e}");
+        }
+    }
+    res
+}
+
+/// Marked before the post is added, a retry after a crash or a failed cleanup is rejected
+async fn mark_published(
+    kv: &KVStoreImpl,
+    creator: Principal,
+    uid: &str,
+) -> Result<(), ServerFnError> {
+    let published_at = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as f64;
+    if !kv
+        .set_add(published_uploads_key(creator), uid.into(), published_at)
+        .await?
+    {
+        return Err(ServerFnError::new("video is already published"));
+    }
+    Ok(())
+}
+
+/// Counts an upload against the creator's daily quota
+pub async fn consume_upload_quota(creator: Principal) -> Result<(), ServerFnError> {
+    let kv: KVStoreImpl = expect_context();
+    consume_quota(&kv, creator).await
+}
+
+async fn consume_quota(kv: &KVStoreImpl, creator: Principal) -> Result<(), ServerFnError> {
+    let today = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY;
+
+    // counted atomically, concurrent requests can't both take the last upload
+    let used = kv.incr(upload_quota_key(creator, today), 1).await?;
+    if used > MAX_UPLOADS_PER_DAY as i64 {
+        return Err(ServerFnError::new(
+            "Daily upload limit reached, please try again tomorrow",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{block_on, test_kv};
+
+    #[test]
+    fn a_video_is_only_published_once() {
+        let kv = test_kv("published-uploads");
+        let creator = Principal::from_slice(&[1]);
+        block_on(async {
+            mark_published(&kv, creator, "video").await.unwrap();
+            assert!(mark_published(&kv, creator, "video").await.is_err());
+            mark_published(&kv, creator, "other").await.unwrap();
+        });
+    }
+
+    #[test]
+    fn concurrent_uploads_stop_at_the_daily_quota() {
+        let kv = test_kv("upload-quota");
+        let creator = Principal::from_slice(&[1]);
+        block_on(async {
+            let attempts = (0..MAX_UPLOADS_PER_DAY + 5)
+                .map(|_| {
+                    let kv = kv.clone();
+                    tokio::spawn(async move { consume_quota(&kv, creator).await.is_ok() })
+                })
+                .collect::<Vec<_>>();
+            let mut allowed = 0;
+            for attempt in attempts {
+                allowed += attempt.await.unwrap() as u32;
+            }
+            assert_eq!(allowed, MAX_UPLOADS_PER_DAY);
+
+            // other creators have their own quota
+            assert!(consume_quota(&kv, Principal::from_slice(&[2]))
+                .await
+                .is_ok());
+        });
+    }
+}
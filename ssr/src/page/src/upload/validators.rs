@@ -31,3 +31,137 @@ pub fn hashtags_validator(hashtags: String) -> Result<Vec<String>, String> {
 
     Ok(hashtags)
 }
+
+pub const MAX_VIDEO_DURATION_SECS: f64 = 60.0;
+const MIN_VIDEO_DURATION_SECS: f64 = 3.0;
+pub const MAX_VIDEO_SIZE_BYTES: u64 = 500 * 1024 * 1024;
+/// Shortest side of the frame, in pixels
+const MIN_VIDEO_RESOLUTION: u32 = 240;
+/// Longest side of the frame, in pixels
+const MAX_VIDEO_RESOLUTION: u32 = 4096;
+/// Widest frame allowed either way, portrait or landscape
+const MAX_ASPECT_RATIO: f64 = 21.0 / 9.0;
+const VIDEO_CONTAINERS: [(&str, &str); 4] = [
+    ("video/mp4", "mp4"),
+    ("video/quicktime", "mov"),
+    ("video/webm", "webm"),
+    ("video/x-matroska", "mkv"),
+];
+
+/// What the browser could read of a selected video
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VideoMeta {
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub duration: f64,
+    /// 0 when the browser can't decode the video track
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Checks a video before it's uploaded
+/// videos longer than [MAX_VIDEO_DURATION_SECS] pass, they're trimmed before upload
+pub fn video_validator(meta: &VideoMeta) -> Result<(), String> {
+    let known_container = VIDEO_CONTAINERS.iter().any(|(mime, ext)| {
+        meta.mime_type == *mime
+            || meta.mime_type.is_empty()
+                && meta
+                    .file_name
+                    .rsplit_once('.')
+                    .is_some_and(|(_, e)| e.eq_ignore_ascii_case(ext))
+    });
+    if !known_container {
+        return Err("Only MP4, MOV, WebM and MKV videos are supported".into());
+    }
+    if meta.size > MAX_VIDEO_SIZE_BYTES {
+        return Err("Video must be smaller than 500MB".into());
+    }
+    if meta.width == 0 || meta.height == 0 {
+        return Err("This video's format can't be played, please try a different one".into());
+    }
+    // recorded webm files don't report a duration until they're played through
+    if meta.duration.is_finite() && meta.duration < MIN_VIDEO_DURATION_SECS {
+        return Err("Video must be at least 3 seconds long".into());
+    }
+
+    let (short, long) = (meta.width.min(meta.height), meta.width.max(meta.height));
+    if short < MIN_VIDEO_RESOLUTION {
+        return Err("Video resolution must be at least 240p".into());
+    }
+    if long > MAX_VIDEO_RESOLUTION {
+        return Err("Video resolution must be at most 4K".into());
+    }
+    if long as f64 / short as f64 > MAX_ASPECT_RATIO {
+        return Err("Video is too wide or too tall, use an aspect ratio up to 21:9".into());
+    }
+
+    Ok(())
+}
+
+/// Videos longer than the limit are trimmed before upload
+pub fn needs_trim(duration: f64) -> bool {
+    duration.is_finite() && duration > MAX_VIDEO_DURATION_SECS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(file_name: &str, mime_type: &str, duration: f64, width: u32, height: u32) -> VideoMeta {
+        VideoMeta {
+            file_name: file_name.into(),
+            mime_type: mime_type.into(),
+            size: 10 * 1024 * 1024,
+            duration,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn video_validator_accepts_only_playable_videos_within_limits() {
+        let cases = [
+            (meta("a.mp4", "video/mp4", 30.0, 1080, 1920), true),
+            (meta("a.mov", "video/quicktime", 30.0, 720, 1280), true),
+            // the extension is only used when the browser has no mime type
+            (meta("a.MKV", "", 30.0, 1920, 1080), true),
+            (meta("a.mp4", "video/avi", 30.0, 1080, 1920), false),
+            (meta("a.avi", "", 30.0, 1080, 1920), false),
+            // undecodable video track
+            (meta("a.mp4", "video/mp4", 30.0, 0, 0), false),
+            (meta("a.mp4", "video/mp4", 2.0, 1080, 1920), false),
+            // long videos are trimmed, unknown durations are checked later
+            (meta("a.mp4", "video/mp4", 120.0, 1080, 1920), true),
+            (
+                meta("a.webm", "video/webm", f64::INFINITY, 1080, 1920),
+                true,
+            ),
+            (meta("a.mp4", "video/mp4", 30.0, 200, 400), false),
+            (meta("a.mp4", "video/mp4", 30.0, 2160, 5000), false),
+            (meta("a.mp4", "video/mp4", 30.0, 240, 1000), false),
+            (meta("a.mp4", "video/mp4", 30.0, 900, 2100), true),
+        ];
+        for (meta, valid) in cases {
+            assert_eq!(video_validator(&meta).is_ok(), valid, "{meta:?}");
+        }
+
+        let mut too_large = meta("a.mp4", "video/mp4", 30.0, 1080, 1920);
+        too_large.size = MAX_VIDEO_SIZE_BYTES + 1;
+        assert!(video_validator(&too_large).is_err());
+    }
+
+    #[test]
+    fn only_videos_over_the_limit_are_trimmed() {
+        let cases = [
+            (30.0, false),
+            (MAX_VIDEO_DURATION_SECS, false),
+            (MAX_VIDEO_DURATION_SECS + 0.1, true),
+            (f64::INFINITY, false),
+            (f64::NAN, false),
+        ];
+        for (duration, trim) in cases {
+            assert_eq!(needs_trim(duration), trim, "{duration}");
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use super::{
     cf_upload::{get_upload_info, get_video_status, publish_video, upload_video_stream},
+    drafts::{save_draft, Draft, PublishMode},
+    moderation::{classify_video, VideoModeration},
+    resumable::{use_pending_upload, PendingUpload, UploadProgress},
+    validators::{needs_trim, video_validator, VideoMeta, MAX_VIDEO_DURATION_SECS},
     UploadParams,
 };
+use crate::search::index_published_post;
 use component::modal::Modal;
 use futures::StreamExt;
 use gloo::timers::future::IntervalStream;
 use ic_agent::Identity;
 use leptos::{
-    ev::durationchange,
+    ev::{error, loadedmetadata},
     html::{Input, Video},
     prelude::*,
 };
@@ -27,6 +32,9 @@ use utils::{
 use web_time::SystemTime;
 use yral_canisters_common::Canisters;
 
+const DURATION_REMINDER: &str =
+    "Videos can be up to 60 seconds long, longer ones can be trimmed once selected";
+
 #[component]
 pub fn DropBox() -> impl IntoView {
     view! {
@@ -42,13 +50,44 @@ pub fn DropBox() -> impl IntoView {
 }
 
 #[component]
-pub fn PreVideoUpload(file_blob: WriteSignal<Option<FileWithUrl>, LocalStorage>) -> impl IntoView {
+pub fn PreVideoUpload(
+    file_blob: WriteSignal<Option<FileWithUrl>, LocalStorage>,
+    /// Frame used as the cover, as a percentage of the video's duration
+    cover_pct: RwSignal<f32>,
+) -> impl IntoView {
     let file_ref = NodeRef::<Input>::new();
     let file = RwSignal::new_local(None::<FileWithUrl>);
     let video_ref = NodeRef::<Video>::new();
     let modal_show = RwSignal::new(false);
+    let modal_msg = RwSignal::new(String::from(DURATION_REMINDER));
+    let duration = RwSignal::new(0.0f64);
+    let trim_start = RwSignal::new(0.0f64);
+    let trimming = RwSignal::new(false);
+    let ready = RwSignal::new(false);
     let canister_store = auth_canisters_store();
 
+    let reject = move |err: String| {
+        VideoUploadUnsuccessful.send_event(err.clone(), 0, false, false, canister_store);
+        modal_msg.set(err);
+        modal_show.set(true);
+        file.set(None);
+        file_blob.set(None);
+        if let Some(f) = file_ref.get_untracked() {
+            f.set_value("");
+        }
+    };
+
+    // shows a frame without the looping preview moving away from it
+    let seek_preview = move |time: f64| {
+        let Some(video) = video_ref.get_untracked() else {
+            return;
+        };
+        _ = video.pause();
+        if time.is_finite() {
+            video.set_current_time(time);
+        }
+    };
+
     #[cfg(feature = "hydrate")]
     {
         use leptos::ev::change;
@@ -58,6 +97,9 @@ pub fn PreVideoUpload(file_blob: WriteSignal<Option<FileWithUrl>, LocalStorage>)
             ev.target().and_then(move |target| {
                 let input: &HtmlInputElement = target.dyn_ref()?;
                 let inp_file = input.files()?.get(0)?;
+                ready.set(false);
+                trim_start.set(0.0);
+                cover_pct.set(0.0);
                 file.set(Some(FileWithUrl::new(inp_file.into())));
 
                 VideoUploadVideoSelected.send_event(canister_store);
@@ -66,30 +108,70 @@ pub fn PreVideoUpload(file_blob: WriteSignal<Option<FileWithUrl>, LocalStorage>)
         });
     }
 
-    _ = use_event_listener(video_ref, durationchange, move |_| {
-        let duration = video_ref
-            .get_untracked()
-            .map(|v| v.duration())
-            .unwrap_or_default();
+    _ = use_event_listener(video_ref, loadedmetadata, move |_| {
+        let Some(video) = video_ref.get_untracked() else {
+            return;
+        };
         let Some(vid_file) = file.get_untracked() else {
             return;
         };
-        if duration <= 60.0 || duration.is_nan() {
-            modal_show.set(false);
-            file_blob.set(Some(vid_file));
+        let meta = VideoMeta {
+            file_name: vid_file.file.name(),
+            mime_type: vid_file.file.raw_mime_type(),
+            size: vid_file.file.size(),
+            duration: video.duration(),
+            width: video.video_width(),
+            height: video.video_height(),
+        };
+        if let Err(e) = video_validator(&meta) {
+            reject(e);
             return;
         }
 
-        modal_show.set(true);
-        file.set(None);
-        file_blob.set(None);
-        if let Some(f) = file_ref.get_untracked() {
-            f.set_value("");
+        duration.set(meta.duration);
+        if needs_trim(meta.duration) {
+            file_blob.set(None);
+            return;
+        }
+        modal_show.set(false);
+        ready.set(true);
+        file_blob.set(Some(vid_file));
+    });
+
+    _ = use_event_listener(video_ref, error, move |_| {
+        if file.with_untracked(|f| f.is_some()) {
+            reject("This video's format can't be played, please try a different one".into());
         }
     });
 
+    let on_trim = move |_| {
+        let Some(vid_file) = file.get_untracked() else {
+            return;
+        };
+        trimming.set(true);
+        #[cfg(feature = "hydrate")]
+        leptos::task::spawn_local(async move {
+            let res = super::preprocess::trim_video(
+                &vid_file,
+                trim_start.get_untracked(),
+                MAX_VIDEO_DURATION_SECS,
+            )
+            .await;
+            trimming.set(false);
+            match res {
+                Ok(trimmed) => file.set(Some(trimmed)),
+                Err(e) => reject(e),
+            }
+        });
+        #[cfg(not(feature = "hydrate"))]
+        {
+            _ = vid_file;
+            trimming.set(false);
+        }
+    };
+
     view! {
-        <div class="flex items-center self-center justify-center w-3/4 mb-8 lg:mb-0 lg:pb-12 lg:w-1/2 lg:max-h-full lg:px-8">
+        <div class="flex flex-col gap-4 items-center self-center justify-center w-3/4 mb-8 lg:mb-0 lg:pb-12 lg:w-1/2 lg:max-h-full lg:px-8">
             <label
                 for="dropzone-file"
                 class="flex justify-start flex-col h-full w-full cursor-pointer"
@@ -111,18 +193,80 @@ pub fn PreVideoUpload(file_blob: WriteSignal<Option<FileWithUrl>, LocalStorage>)
                     }
                 ></video>
                 <input
-                    on:click=move |_| modal_show.set(true)
+                    on:click=move |_| {
+                        modal_msg.set(DURATION_REMINDER.into());
+                        modal_show.set(true);
+                    }
                     id="dropzone-file"
                     node_ref=file_ref
                     type="file"
-                    accept="video/*"
+                    accept="video/mp4,video/quicktime,video/webm,video/x-matroska"
                     class="hidden w-0 h-0"
                 />
             </label>
+            <Show when=move || file.with(|f| f.is_some()) && needs_trim(duration())>
+                <div class="flex flex-col gap-2 w-full text-sm text-white/80">
+                    <span>
+                        "Your video is longer than 60 seconds, pick the part you want to keep"
+                    </span>
+                    <input
+                        type="range"
+                        min=0
+                        max=move || duration() - MAX_VIDEO_DURATION_SECS
+                        step=0.1
+                        disabled=trimming
+                        prop:value=move || trim_start().to_string()
+                        on:input=move |ev| {
+                            let start = event_target_value(&ev).parse().unwrap_or_default();
+                            trim_start.set(start);
+                            seek_preview(start);
+                        }
+                    />
+                    <span>
+                        {move || {
+                            format!(
+                                "{:.1}s - {:.1}s",
+                                trim_start(),
+                                trim_start() + MAX_VIDEO_DURATION_SECS,
+                            )
+                        }}
+                    </span>
+                    <button
+                        class="py-2 rounded-full bg-primary-600 font-bold disabled:bg-primary-400"
+                        disabled=trimming
+                        on:click=on_trim
+                    >
+                        {move || {
+                            if trimming() {
+                                "Trimming, this takes as long as the clip..."
+                            } else {
+                                "Trim to 60 seconds"
+                            }
+                        }}
+                    </button>
+                </div>
+            </Show>
+            <Show when=ready>
+                <label class="flex flex-col gap-2 w-full text-sm text-white/80">
+                    "Cover frame"
+                    <input
+                        type="range"
+                        min=0
+                        max=100
+                        step=1
+                        prop:value=move || cover_pct().to_string()
+                        on:input=move |ev| {
+                            let pct: f32 = event_target_value(&ev).parse().unwrap_or_default();
+                            cover_pct.set(pct);
+                            seek_preview(duration.get_untracked() * pct as f64 / 100.0);
+                        }
+                    />
+                </label>
+            </Show>
         </div>
         <Modal show=modal_show>
             <span class="text-lg md:text-xl text-white h-full items-center py-10 text-center w-full flex flex-col justify-center">
-                Please ensure that the video is shorter than 60 seconds
+                {modal_msg}
             </span>
         </Modal>
     }
@@ -133,6 +277,9 @@ pub fn ProgressItem(
     #[prop(into)] initial_text: String,
     #[prop(into)] done_text: String,
     #[prop(into)] loading: Signal<bool>,
+    /// Fraction done, shown while loading
+    #[prop(optional, into)]
+    progress: Option<Signal<f64>>,
 ) -> impl IntoView {
     view! {
         <Show
@@ -147,6 +294,14 @@ pub fn ProgressItem(
 
             <Icon attr:class="w-10 h-10 text-primary-600 animate-spin" icon=icondata::CgSpinnerTwo />
             <span class="text-white text-lg font-semibold">{initial_text.clone()}</span>
+            {progress
+                .map(|progress| {
+                    view! {
+                        <span class="text-white/60 text-lg">
+                            {move || format!("{:.0}%", progress() * 100.0)}
+                        </span>
+                    }
+                })}
         </Show>
     }
 }
@@ -170,43 +325,68 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
     let canister_store = auth_canisters_store();
 
     let up_desc = description.clone();
+    let progress = RwSignal::new(UploadProgress::default());
+    let paused = RwSignal::new(false);
+    let (_, set_pending_upload) = use_pending_upload();
+    let resume = params.resume;
+    let thumbnail_pct = params.thumbnail_pct;
+    let publish = params.publish;
+    let moderation = RwSignal::new(VideoModeration::default());
 
     let upload_action = LocalResource::new(move || {
         let cans = canister_store().map(MockPartialEq);
         let hashtags = up_hashtags.clone();
         let description = up_desc.clone();
         let file_blob = file_blob.clone();
+        let resume = resume.clone();
         async move {
             let cans = cans?.0;
-            let creator_principal = cans.identity().sender().unwrap();
-            let time_ms = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis();
-
-            // TODO: authenticated call
-            let res = get_upload_info(
-                creator_principal,
-                hashtags,
-                description,
-                time_ms.to_string(),
-            )
-            .await;
+            let upload_info = if let Some(upload_info) = resume {
+                upload_info
+            } else {
+                let creator_principal = cans.identity().sender().unwrap();
+                let time_ms = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis();
+
+                // authenticated with the session cookie
+                let res = get_upload_info(
+                    creator_principal,
+                    hashtags.clone(),
+                    description.clone(),
+                    time_ms.to_string(),
+                    file_blob.size(),
+                    thumbnail_pct,
+                )
+                .await;
 
-            if res.is_err() {
-                let e = res.as_ref().err().unwrap().to_string();
-                VideoUploadUnsuccessful.send_event(
-                    e,
-                    hashtags_len,
-                    is_nsfw,
-                    enable_hot_or_not,
-                    canister_store,
-                );
-            }
+                if res.is_err() {
+                    let e = res.as_ref().err().unwrap().to_string();
+                    VideoUploadUnsuccessful.send_event(
+                        e,
+                        hashtags_len,
+                        is_nsfw,
+                        enable_hot_or_not,
+                        canister_store,
+                    );
+                }
 
-            let upload_info = try_or_redirect_opt!(res);
+                let upload_info = try_or_redirect_opt!(res);
+                set_pending_upload.set(Some(PendingUpload {
+                    info: upload_info.clone(),
+                    file_name: file_blob.name(),
+                    file_size: file_blob.size(),
+                    hashtags,
+                    description,
+                    enable_hot_or_not,
+                    is_nsfw,
+                    publish,
+                }));
+                upload_info
+            };
 
-            let res = upload_video_stream(&upload_info, &file_blob).await;
+            let res = upload_video_stream(&upload_info, &file_blob, progress, paused.into()).await;
 
             if res.is_err() {
                 let e = res.as_ref().err().unwrap().to_string();
@@ -246,6 +426,24 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
             }
             processing.set(false);
 
+            // the detector has the last word on what counts as NSFW
+            let res = classify_video(cans.user_principal(), upload_info.uid.clone())
+                .await
+                .and_then(|verdict| verdict.check_publishable().map(|_| verdict));
+
+            if res.is_err() {
+                let e = res.as_ref().err().unwrap().to_string();
+                VideoUploadUnsuccessful.send_event(
+                    e,
+                    hashtags_len,
+                    is_nsfw,
+                    enable_hot_or_not,
+                    canister_store,
+                );
+            }
+
+            moderation.set(try_or_redirect_opt!(res));
+
             Some(upload_info.uid)
         }
     });
@@ -257,14 +455,57 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
             let hashtags_len = hashtags.len();
             let description = description.clone();
             let uid = uid.clone();
+            let user_canister = canisters.user_canister();
+            let is_nsfw = moderation.get_untracked().is_nsfw(is_nsfw);
+            // NSFW posts can't be bet on
+            let enable_hot_or_not = enable_hot_or_not && !is_nsfw;
             async move {
+                if publish != PublishMode::Now {
+                    let publish_at_ms = match publish {
+                        PublishMode::Scheduled(at) => Some(at),
+                        _ => None,
+                    };
+                    let res = save_draft(
+                        canisters.user_principal(),
+                        Draft {
+                            uid,
+                            hashtags,
+                            description,
+                            enable_hot_or_not,
+                            is_nsfw,
+                            created_at_ms: 0,
+                            publish_at_ms,
+                            publish_error: None,
+                        },
+                    )
+                    .await;
+
+                    if res.is_err() {
+                        let e = res.as_ref().err().unwrap().to_string();
+                        VideoUploadUnsuccessful.send_event(
+                            e,
+                            hashtags_len,
+                            is_nsfw,
+                            enable_hot_or_not,
+                            canister_store,
+                        );
+                    }
+
+                    try_or_redirect_opt!(res);
+
+                    publishing.set(false);
+                    set_pending_upload.set(None);
+                    return Some(());
+                }
+
+                // checked against the moderation verdict again by the server
                 let res = publish_video(
-                    canisters,
+                    canisters.user_principal(),
                     hashtags,
                     description,
                     uid.clone(),
-                    params.enable_hot_or_not,
-                    params.is_nsfw,
+                    enable_hot_or_not,
+                    is_nsfw,
                 )
                 .await;
 
@@ -282,8 +523,12 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
                 try_or_redirect_opt!(res);
 
                 publishing.set(false);
+                set_pending_upload.set(None);
 
                 let post_id = res.unwrap();
+                if let Err(e) = index_published_post(user_canister, post_id).await {
+                    log::warn!("failed to index published post: {e}");
+                }
                 VideoUploadSuccessful.send_event(
                     uid,
                     hashtags_len,
@@ -311,14 +556,39 @@ pub fn VideoUploader(params: UploadParams) -> impl IntoView {
             ></video>
         </div>
         <div class="flex flex-col basis-full lg:basis-7/12 gap-4 px-4">
-            <div class="flex flex-row gap-4">
-                <ProgressItem initial_text="Uploading" done_text="Uploaded" loading=uploading />
+            <div class="flex flex-row gap-4 items-center">
+                <ProgressItem
+                    initial_text="Uploading"
+                    done_text="Uploaded"
+                    loading=uploading
+                    progress=Signal::derive(move || progress.with(|p| p.fraction()))
+                />
+                <Show when=uploading>
+                    <button
+                        class="ml-auto px-4 py-1 rounded-full bg-white/10 text-sm"
+                        on:click=move |_| paused.update(|p| *p = !*p)
+                    >
+                        {move || if paused() { "Resume" } else { "Pause" }}
+                    </button>
+                </Show>
             </div>
             <div class="flex flex-row gap-4">
                 <ProgressItem initial_text="Processing" done_text="Processed" loading=processing />
             </div>
             <div class="flex flex-row gap-4">
-                <ProgressItem initial_text="Publishing" done_text="Published" loading=publishing />
+                <ProgressItem
+                    initial_text=match publish {
+                        PublishMode::Now => "Publishing",
+                        PublishMode::Draft => "Saving draft",
+                        PublishMode::Scheduled(_) => "Scheduling",
+                    }
+                    done_text=match publish {
+                        PublishMode::Now => "Published",
+                        PublishMode::Draft => "Saved to drafts",
+                        PublishMode::Scheduled(_) => "Scheduled",
+                    }
+                    loading=publishing
+                />
                 <Suspense>
                     {move || {
                         let uid = upload_action.get().map(|a| a.take())??;
@@ -3,6 +3,7 @@ pub mod tokens;
 pub mod transactions;
 pub mod txn;
 
+use crate::bet_outcomes::BetOutcomesCtx;
 use candid::Principal;
 use component::icons::notification_icon::NotificationIcon;
 use component::share_popup::ShareButtonWithFallbackPopup;
@@ -217,6 +218,8 @@ pub fn WalletImpl(principal: Principal) -> impl IntoView {
         },
     );
 
+    let bet_outcomes: BetOutcomesCtx = expect_context();
+
     let app_state = use_context::<AppState>();
     let page_title = app_state.unwrap().name.to_owned() + " - Wallet";
     view! {
@@ -245,6 +248,8 @@ pub fn WalletImpl(principal: Principal) -> impl IntoView {
                 </Suspense>
                 <Suspense>
                     {move || {
+                        // balances change when a bet settles
+                        bet_outcomes.balance_version.track();
                         let canister_id = try_or_redirect_opt!(canister_id.get() ?);
                         Some(
                             view! {
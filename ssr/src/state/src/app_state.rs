@@ -8,6 +8,34 @@ pub struct AppState {
     pub description: &'static str,
     pub theme_color: &'static str,
     pub assets_dir: &'static str,
+    pub gestures: GestureConfig,
+}
+
+/// Gestures recognized on top of the video player
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureConfig {
+    pub double_tap_like: bool,
+    pub long_press_pause: bool,
+    pub swipe_to_profile: bool,
+    /// Seconds skipped when tapping the left or right edge, 0 disables seeking
+    pub side_tap_seek_secs: f64,
+}
+
+impl GestureConfig {
+    pub const ALL: Self = Self {
+        double_tap_like: true,
+        long_press_pause: true,
+        swipe_to_profile: true,
+        side_tap_seek_secs: 5.0,
+    };
+
+    /// only tap to mute
+    pub const NONE: Self = Self {
+        double_tap_like: false,
+        long_press_pause: false,
+        swipe_to_profile: false,
+        side_tap_seek_secs: 0.0,
+    };
 }
 
 impl AppState {
@@ -20,6 +48,7 @@ impl AppState {
                 description: "Vote on the hottest content and earn rewards",
                 theme_color: "#FF4500",
                 assets_dir: "hotornot",
+                gestures: GestureConfig::ALL,
             },
             AppType::ICPump => Self {
                 app_type: AppType::ICPump,
@@ -28,6 +57,7 @@ impl AppState {
                 description: "Create and trade tokens on the Internet Computer",
                 theme_color: "#4CAF50",
                 assets_dir: "icpump",
+                gestures: GestureConfig::NONE,
             },
             AppType::YRAL => Self {
                 app_type: AppType::YRAL,
@@ -36,6 +66,7 @@ impl AppState {
                 description: "The First App to Host Creative Short Video Challenges",
                 theme_color: "#E20479",
                 assets_dir: "yral",
+                gestures: GestureConfig::ALL,
             },
             AppType::Pumpdump => Self {
                 app_type: AppType::Pumpdump,
@@ -44,6 +75,7 @@ impl AppState {
                 description: "Pump it, Dump it, Cash it",
                 theme_color: "#000000",
                 assets_dir: "pumpdump",
+                gestures: GestureConfig::NONE,
             },
         }
     }
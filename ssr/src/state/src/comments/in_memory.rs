@@ -0,0 +1,145 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use candid::Principal;
+use leptos::prelude::ServerFnError;
+
+use super::{Comment, CommentStore, PostKey};
+use crate::search::paginate;
+
+#[derive(Default)]
+struct Comments {
+    comments: Vec<Comment>,
+    likes: HashMap<(PostKey, String), HashSet<Principal>>,
+}
+
+impl Comments {
+    fn for_viewer(&self, comment: &Comment, viewer: Option<Principal>) -> Comment {
+        let likes = self.likes.get(&(comment.post, comment.id.clone()));
+        Comment {
+            likes: likes.map_or(0, |l| l.len() as u64),
+            liked_by_viewer: viewer.is_some_and(|v| likes.is_some_and(|l| l.contains(&v))),
+            ..comment.clone()
+        }
+    }
+}
+
+/// Comments kept in process memory, for tests and the local build
+#[derive(Clone, Default)]
+pub struct InMemoryCommentStore(Arc<RwLock<Comments>>);
+
+impl CommentStore for InMemoryCommentStore {
+    async fn add_comment(&self, comment: Comment) -> Result<(), ServerFnError> {
+        let Comments { comments, .. } = &mut *self.0.write().unwrap();
+        if let Some(parent_id) = comment.parent_id.as_ref() {
+            let parent = comments
+                .iter_mut()
+                .find(|c| c.post == comment.post && &c.id == parent_id)
+                .ok_or_else(|| ServerFnError::new("parent comment not found"))?;
+            parent.reply_count += 1;
+        }
+        comments.push(comment);
+        Ok(())
+    }
+
+    async fn get_comment(
+        &self,
+        post: PostKey,
+        id: &str,
+        viewer: Option<Principal>,
+    ) -> Result<Option<Comment>, ServerFnError> {
+        let store = self.0.read().unwrap();
+        Ok(store
+            .comments
+            .iter()
+            .find(|c| c.post == post && c.id == id)
+            .map(|c| store.for_viewer(c, viewer)))
+    }
+
+    async fn set_comment_like(
+        &self,
+        post: PostKey,
+        id: &str,
+        user: Principal,
+        liked: bool,
+    ) -> Result<u64, ServerFnError> {
+        let mut store = self.0.write().unwrap();
+        if !store.comments.iter().any(|c| c.post == post && c.id == id) {
+            return Err(ServerFnError::new("comment not found"));
+        }
+        let likes = store.likes.entry((post, id.to_string())).or_default();
+        if liked {
+            likes.insert(user);
+        } else {
+            likes.remove(&user);
+        }
+        Ok(likes.len() as u64)
+    }
+
+    async fn list_comments(
+        &self,
+        post: PostKey,
+        parent: Option<&str>,
+        start: usize,
+        end: usize,
+        viewer: Option<Principal>,
+    ) -> Result<(Vec<Comment>, bool), ServerFnError> {
+        let store = self.0.read().unwrap();
+        let listing = store
+            .comments
+            .iter()
+            .rev()
+            .filter(|c| c.post == post && c.parent_id.as_deref() == parent)
+            .map(|c| store.for_viewer(c, viewer))
+            .collect::<Vec<_>>();
+        Ok(paginate(&listing, start, end))
+    }
+
+    async fn delete_comment(&self, post: PostKey, id: &str) -> Result<(), ServerFnError> {
+        let Comments { comments, likes } = &mut *self.0.write().unwrap();
+        let Some(comment) = comments
+            .iter()
+            .find(|c| c.post == post && c.id == id)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let mut removed = vec![comment.id];
+        let mut idx = 0;
+        while idx < removed.len() {
+            let replies = comments
+                .iter()
+                .filter(|c| c.post == post && c.parent_id.as_ref() == Some(&removed[idx]))
+                .map(|c| c.id.clone())
+                .collect::<Vec<_>>();
+            removed.extend(replies);
+            idx += 1;
+        }
+        comments.retain(|c| c.post != post || !removed.contains(&c.id));
+        likes.retain(|(p, id), _| *p != post || !removed.contains(id));
+
+        if let Some(parent_id) = comment.parent_id {
+            if let Some(parent) = comments
+                .iter_mut()
+                .find(|c| c.post == post && c.id == parent_id)
+            {
+                parent.reply_count = parent.reply_count.saturating_sub(1);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::tests::{assert_store_behaviour, block_on};
+
+    #[test]
+    fn in_memory_store_threads_replies() {
+        block_on(assert_store_behaviour(InMemoryCommentStore::default()));
+    }
+}
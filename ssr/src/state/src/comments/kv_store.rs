@@ -0,0 +1,159 @@
+use auth::server_impl::store::KVStoreImpl;
+use candid::Principal;
+use leptos::prelude::ServerFnError;
+
+use super::{Comment, CommentStore, PostKey};
+
+/// Comments stored in the KV store
+/// every comment has its own entry, replies and likes are kept in sorted sets
+/// so concurrent comments and likes don't overwrite each other
+#[derive(Clone)]
+pub struct KVCommentStore(pub KVStoreImpl);
+
+fn post_prefix((canister_id, post_id): PostKey) -> String {
+    format!("{}:{post_id}", canister_id.to_text())
+}
+
+fn comment_key(post: PostKey, id: &str) -> String {
+    format!("comment:{}:{id}", post_prefix(post))
+}
+
+/// Ids of the replies to `parent`, ordered by when they were posted
+fn replies_key(post: PostKey, parent: Option<&str>) -> String {
+    format!(
+        "comment-replies:{}:{}",
+        post_prefix(post),
+        parent.unwrap_or("root")
+    )
+}
+
+fn likes_key(post: PostKey, id: &str) -> String {
+    format!("comment-likes:{}:{id}", post_prefix(post))
+}
+
+/// Orders comments of a post, timestamps can't tell apart comments posted in the same second
+fn seq_key(post: PostKey) -> String {
+    format!("comment-seq:{}", post_prefix(post))
+}
+
+impl CommentStore for KVCommentStore {
+    async fn add_comment(&self, comment: Comment) -> Result<(), ServerFnError> {
+        let parent_id = comment.parent_id.as_deref();
+        if let Some(parent_id) = parent_id {
+            if self
+                .get_comment(comment.post, parent_id, None)
+                .await?
+                .is_none()
+            {
+                return Err(ServerFnError::new("parent comment not found"));
+            }
+        }
+
+        self.0
+            .write_json(comment_key(comment.post, &comment.id), &comment)
+            .await?;
+        let seq = self.0.incr(seq_key(comment.post), 1).await?;
+        self.0
+            .set_add(
+                replies_key(comment.post, parent_id),
+                comment.id.clone(),
+                seq as f64,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_comment(
+        &self,
+        post: PostKey,
+        id: &str,
+        viewer: Option<Principal>,
+    ) -> Result<Option<Comment>, ServerFnError> {
+        let comment: Option<Comment> = self.0.read_json(comment_key(post, id)).await?;
+        let Some(mut comment) = comment else {
+            return Ok(None);
+        };
+        comment.reply_count = self.0.set_len(replies_key(post, Some(id))).await? as u64;
+        let likes = likes_key(post, id);
+        comment.likes = self.0.set_len(likes.clone()).await? as u64;
+        comment.liked_by_viewer = match viewer {
+            Some(viewer) => self.0.set_contains(likes, viewer.to_text()).await?,
+            None => false,
+        };
+        Ok(Some(comment))
+    }
+
+    async fn set_comment_like(
+        &self,
+        post: PostKey,
+        id: &str,
+        user: Principal,
+        liked: bool,
+    ) -> Result<u64, ServerFnError> {
+        let key = likes_key(post, id);
+        if liked {
+            self.0.set_add(key.clone(), user.to_text(), 0.0).await?;
+        } else {
+            self.0.set_remove(key.clone(), user.to_text()).await?;
+        }
+        Ok(self.0.set_len(key).await? as u64)
+    }
+
+    async fn list_comments(
+        &self,
+        post: PostKey,
+        parent: Option<&str>,
+        start: usize,
+        end: usize,
+        viewer: Option<Principal>,
+    ) -> Result<(Vec<Comment>, bool), ServerFnError> {
+        let key = replies_key(post, parent);
+        let total = self.0.set_len(key.clone()).await?;
+        let ids = self.0.set_range(key, start, end).await?;
+        let mut comments = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(comment) = self.get_comment(post, &id, viewer).await? {
+                comments.push(comment);
+            }
+        }
+        Ok((comments, end >= total))
+    }
+
+    async fn delete_comment(&self, post: PostKey, id: &str) -> Result<(), ServerFnError> {
+        let Some(comment) = self.get_comment(post, id, None).await? else {
+            return Ok(());
+        };
+        self.0
+            .set_remove(
+                replies_key(post, comment.parent_id.as_deref()),
+                comment.id.clone(),
+            )
+            .await?;
+
+        let mut removed = vec![comment.id];
+        while let Some(id) = removed.pop() {
+            let key = replies_key(post, Some(&id));
+            removed.extend(self.0.set_range(key.clone(), 0, usize::MAX).await?);
+            self.0.delete(key).await?;
+            self.0.delete(likes_key(post, &id)).await?;
+            self.0.delete(comment_key(post, &id)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use auth::server_impl::store::redb_kv::ReDBKV;
+
+    use super::*;
+    use crate::comments::tests::{assert_store_behaviour, block_on};
+
+    #[test]
+    fn kv_store_threads_replies() {
+        let path = std::env::temp_dir().join(format!("comments-kv-{}.db", std::process::id()));
+        _ = std::fs::remove_file(&path);
+        let store = KVCommentStore(KVStoreImpl::ReDB(ReDBKV::open(path).unwrap()));
+        block_on(assert_store_behaviour(store));
+    }
+}
@@ -0,0 +1,213 @@
+#[cfg(feature = "ssr")]
+pub mod in_memory;
+#[cfg(feature = "ssr")]
+pub mod kv_store;
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+
+use crate::search::PostKey;
+
+/// A comment or a reply as persisted by the comment store
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Comment {
+    pub id: String,
+    pub post: PostKey,
+    /// Comment this is a reply to, `None` for top level comments
+    pub parent_id: Option<String>,
+    pub author: Principal,
+    pub text: String,
+    /// Seconds since UNIX epoch
+    pub created_at: u64,
+    /// Filled in by the store when reading, like `reply_count`
+    pub likes: u64,
+    /// Whether the viewer the comment was read for liked it
+    pub liked_by_viewer: bool,
+    pub reply_count: u64,
+}
+
+#[cfg(feature = "ssr")]
+mod backend {
+    use candid::Principal;
+    use enum_dispatch::enum_dispatch;
+    use leptos::prelude::ServerFnError;
+
+    use super::{in_memory::InMemoryCommentStore, kv_store::KVCommentStore};
+    use super::{Comment, PostKey};
+
+    /// Storage for comments and their replies
+    /// listings are ordered newest first
+    #[enum_dispatch]
+    #[allow(async_fn_in_trait)]
+    pub trait CommentStore: Send {
+        /// Stores a new comment, counting it as a reply of its parent
+        async fn add_comment(&self, comment: Comment) -> Result<(), ServerFnError>;
+
+        async fn get_comment(
+            &self,
+            post: PostKey,
+            id: &str,
+            viewer: Option<Principal>,
+        ) -> Result<Option<Comment>, ServerFnError>;
+
+        /// Adds or removes `user`'s like, returns the updated like count
+        async fn set_comment_like(
+            &self,
+            post: PostKey,
+            id: &str,
+            user: Principal,
+            liked: bool,
+        ) -> Result<u64, ServerFnError>;
+
+        /// Replies to `parent`, or the top level comments if `parent` is `None`
+        /// returns the page and whether it's the last one
+        async fn list_comments(
+            &self,
+            post: PostKey,
+            parent: Option<&str>,
+            start: usize,
+            end: usize,
+            viewer: Option<Principal>,
+        ) -> Result<(Vec<Comment>, bool), ServerFnError>;
+
+        /// Removes a comment along with its replies
+        async fn delete_comment(&self, post: PostKey, id: &str) -> Result<(), ServerFnError>;
+    }
+
+    #[derive(Clone)]
+    #[enum_dispatch(CommentStore)]
+    pub enum CommentStoreImpl {
+        KV(KVCommentStore),
+        InMemory(InMemoryCommentStore),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub use backend::*;
+
+#[cfg(all(test, feature = "ssr"))]
+pub(crate) mod tests {
+    use candid::Principal;
+
+    use super::*;
+
+    pub fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_multi_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    fn user(n: u8) -> Principal {
+        Principal::from_slice(&[n])
+    }
+
+    fn comment(id: &str, parent_id: Option<&str>, created_at: u64) -> Comment {
+        Comment {
+            id: id.into(),
+            post: (Principal::anonymous(), 0),
+            parent_id: parent_id.map(String::from),
+            author: user(1),
+            text: id.into(),
+            created_at,
+            likes: 0,
+            liked_by_viewer: false,
+            reply_count: 0,
+        }
+    }
+
+    fn ids(comments: &[Comment]) -> Vec<&str> {
+        comments.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    /// Behaviour every store has to share
+    pub async fn assert_store_behaviour(store: impl CommentStore) {
+        let post = (Principal::anonymous(), 0);
+        store.add_comment(comment("a", None, 1)).await.unwrap();
+        store.add_comment(comment("b", None, 1)).await.unwrap();
+        store.add_comment(comment("c", None, 2)).await.unwrap();
+        store
+            .add_comment(comment("a1", Some("a"), 3))
+            .await
+            .unwrap();
+        store
+            .add_comment(comment("a2", Some("a"), 4))
+            .await
+            .unwrap();
+        store
+            .add_comment(comment("a1x", Some("a1"), 5))
+            .await
+            .unwrap();
+        assert!(store
+            .add_comment(comment("orphan", Some("missing"), 6))
+            .await
+            .is_err());
+
+        let (top, end) = store.list_comments(post, None, 0, 2, None).await.unwrap();
+        assert_eq!(ids(&top), ["c", "b"]);
+        assert!(!end);
+        let (top, end) = store.list_comments(post, None, 2, 4, None).await.unwrap();
+        assert_eq!(ids(&top), ["a"]);
+        assert!(end);
+        assert_eq!(top[0].reply_count, 2);
+
+        let (replies, _) = store
+            .list_comments(post, Some("a"), 0, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&replies), ["a2", "a1"]);
+
+        assert_eq!(
+            store
+                .set_comment_like(post, "b", user(2), true)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .set_comment_like(post, "b", user(2), true)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            store
+                .set_comment_like(post, "b", user(3), true)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            store
+                .set_comment_like(post, "b", user(2), false)
+                .await
+                .unwrap(),
+            1
+        );
+        let b = store.get_comment(post, "b", Some(user(3))).await.unwrap();
+        assert_eq!(b.map(|b| (b.likes, b.liked_by_viewer)), Some((1, true)));
+        let b = store.get_comment(post, "b", Some(user(2))).await.unwrap();
+        assert_eq!(b.map(|b| (b.likes, b.liked_by_viewer)), Some((1, false)));
+        let (top, _) = store
+            .list_comments(post, None, 0, 3, Some(user(3)))
+            .await
+            .unwrap();
+        assert!(top.iter().all(|c| c.liked_by_viewer == (c.id == "b")));
+
+        store.delete_comment(post, "a1").await.unwrap();
+        let a = store.get_comment(post, "a", None).await.unwrap().unwrap();
+        assert_eq!(a.reply_count, 1);
+        assert!(store.get_comment(post, "a1", None).await.unwrap().is_none());
+        assert!(store
+            .get_comment(post, "a1x", None)
+            .await
+            .unwrap()
+            .is_none());
+        let (replies, _) = store
+            .list_comments(post, Some("a"), 0, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&replies), ["a2"]);
+    }
+}
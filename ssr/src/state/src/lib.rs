@@ -5,8 +5,10 @@ pub mod app_type;
 pub mod audio_state;
 pub mod auth;
 pub mod canisters;
+pub mod comments;
 pub mod content_seed_client;
 pub mod local_storage;
+pub mod search;
 
 #[cfg(feature = "ssr")]
 pub mod server {
@@ -29,6 +31,8 @@ pub mod server {
         #[cfg(feature = "cloudflare")]
         pub cloudflare: gob_cloudflare::CloudflareAuth,
         pub kv: KVStoreImpl,
+        pub search_index: crate::search::SearchIndexImpl,
+        pub comment_store: crate::comments::CommentStoreImpl,
         pub routes: Vec<AxumRouteListing>,
         pub cookie_key: Key,
         #[cfg(feature = "oauth-ssr")]
@@ -0,0 +1,125 @@
+use std::{
+    cmp::Reverse,
+    sync::{Arc, RwLock},
+};
+
+use leptos::prelude::ServerFnError;
+
+use super::{paginate, tokenize, CreatorDoc, PostDoc, PostKey, SearchIndex};
+
+#[derive(Default)]
+struct Docs {
+    posts: Vec<PostDoc>,
+    creators: Vec<CreatorDoc>,
+}
+
+/// Index kept in process memory, for tests and the local build
+/// query words match on prefixes of indexed words
+#[derive(Clone, Default)]
+pub struct InMemorySearchIndex(Arc<RwLock<Docs>>);
+
+fn newest_first<'a>(posts: impl Iterator<Item = &'a PostDoc>) -> Vec<PostKey> {
+    let mut posts = posts.collect::<Vec<_>>();
+    posts.sort_by_key(|p| Reverse(p.created_at));
+    posts.into_iter().map(PostDoc::key).collect()
+}
+
+fn matches(query: &[String], tokens: &[String]) -> bool {
+    !query.is_empty()
+        && query
+            .iter()
+            .all(|q| tokens.iter().any(|t| t.starts_with(q.as_str())))
+}
+
+impl SearchIndex for InMemorySearchIndex {
+    async fn index_post(&self, doc: PostDoc) -> Result<(), ServerFnError> {
+        let mut docs = self.0.write().unwrap();
+        docs.posts.retain(|p| p.key() != doc.key());
+        docs.posts.push(doc);
+        Ok(())
+    }
+
+    async fn index_creator(&self, doc: CreatorDoc) -> Result<(), ServerFnError> {
+        let mut docs = self.0.write().unwrap();
+        docs.creators.retain(|c| c.principal != doc.principal);
+        docs.creators.push(doc);
+        Ok(())
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<PostKey>, bool), ServerFnError> {
+        let query = tokenize(query);
+        let docs = self.0.read().unwrap();
+        let hits = newest_first(docs.posts.iter().filter(|p| matches(&query, &p.tokens())));
+        Ok(paginate(&hits, start, end))
+    }
+
+    async fn search_creators(
+        &self,
+        query: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<CreatorDoc>, bool), ServerFnError> {
+        let query = tokenize(query);
+        let docs = self.0.read().unwrap();
+        let hits = docs
+            .creators
+            .iter()
+            .rev()
+            .filter(|c| matches(&query, &c.tokens()))
+            .cloned()
+            .collect::<Vec<_>>();
+        Ok(paginate(&hits, start, end))
+    }
+
+    async fn hashtag_posts(
+        &self,
+        hashtag: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<PostKey>, bool), ServerFnError> {
+        let docs = self.0.read().unwrap();
+        let hits = newest_first(
+            docs.posts
+                .iter()
+                .filter(|p| p.normalized_hashtags().iter().any(|h| h == hashtag)),
+        );
+        Ok(paginate(&hits, start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::tests::{assert_index_behaviour, block_on};
+
+    #[test]
+    fn in_memory_index_matches_words_and_hashtags() {
+        block_on(assert_index_behaviour(InMemorySearchIndex::default()));
+    }
+
+    #[test]
+    fn query_words_match_prefixes() {
+        let index = InMemorySearchIndex::default();
+        block_on(async {
+            index
+                .index_post(PostDoc {
+                    canister_id: candid::Principal::anonymous(),
+                    post_id: 0,
+                    description: "volleyball".into(),
+                    hashtags: vec![],
+                    created_at: 0,
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                index.search_posts("volley", 0, 10).await.unwrap().0.len(),
+                1
+            );
+        });
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+use auth::server_impl::store::KVStoreImpl;
+use candid::Principal;
+use leptos::prelude::ServerFnError;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use super::{paginate, tokenize, CreatorDoc, PostDoc, PostKey, SearchIndex};
+
+/// Multi word queries only look at this many of the newest entries of each word
+const MAX_INTERSECTED: usize = 2000;
+
+/// Inverted index stored in the KV store, one sorted set per word
+/// query words must match indexed words exactly
+#[derive(Clone)]
+pub struct KVSearchIndex(pub KVStoreImpl);
+
+fn post_key(token: &str) -> String {
+    format!("search:post:{token}")
+}
+
+fn creator_key(token: &str) -> String {
+    format!("search:creator:{token}")
+}
+
+fn creator_doc_key(principal: Principal) -> String {
+    format!("search:creator-doc:{}", principal.to_text())
+}
+
+fn hashtag_key(hashtag: &str) -> String {
+    format!("search:hashtag:{hashtag}")
+}
+
+fn post_member((canister_id, post_id): PostKey) -> String {
+    format!("{}:{post_id}", canister_id.to_text())
+}
+
+fn parse_post_member(member: &str) -> Result<PostKey, ServerFnError> {
+    let (canister_id, post_id) = member
+        .split_once(':')
+        .ok_or_else(|| ServerFnError::new(format!("bad index entry {member}")))?;
+    Ok((Principal::from_text(canister_id)?, post_id.parse()?))
+}
+
+impl KVSearchIndex {
+    /// Members of the first set present in all the others, newest first
+    async fn intersect(&self, keys: Vec<String>) -> Result<Vec<String>, ServerFnError> {
+        let mut sets = vec![];
+        for key in keys {
+            let members = self.0.set_range(key, 0, MAX_INTERSECTED).await?;
+            if members.is_empty() {
+                return Ok(vec![]);
+            }
+            sets.push(members);
+        }
+        let Some((first, rest)) = sets.split_first() else {
+            return Ok(vec![]);
+        };
+        let rest = rest
+            .iter()
+            .map(|s| s.iter().collect::<HashSet<_>>())
+            .collect::<Vec<_>>();
+        Ok(first
+            .iter()
+            .filter(|m| rest.iter().all(|s| s.contains(m)))
+            .cloned()
+            .collect())
+    }
+}
+
+impl SearchIndex for KVSearchIndex {
+    async fn index_post(&self, doc: PostDoc) -> Result<(), ServerFnError> {
+        let member = post_member(doc.key());
+        let score = doc.created_at as f64;
+        for token in doc.tokens() {
+            self.0
+                .set_add(post_key(&token), member.clone(), score)
+                .await?;
+        }
+        for hashtag in doc.normalized_hashtags() {
+            self.0
+                .set_add(hashtag_key(&hashtag), member.clone(), score)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn index_creator(&self, doc: CreatorDoc) -> Result<(), ServerFnError> {
+        let indexed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as f64;
+        self.0
+            .write_json(creator_doc_key(doc.principal), &doc)
+            .await?;
+        for token in doc.tokens() {
+            self.0
+                .set_add(creator_key(&token), doc.principal.to_text(), indexed_at)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<PostKey>, bool), ServerFnError> {
+        let keys = tokenize(query).iter().map(|t| post_key(t)).collect();
+        let (members, last) = paginate(&self.intersect(keys).await?, start, end);
+        let hits = members
+            .iter()
+            .map(|m| parse_post_member(m))
+            .collect::<Result<_, _>>()?;
+        Ok((hits, last))
+    }
+
+    async fn search_creators(
+        &self,
+        query: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<CreatorDoc>, bool), ServerFnError> {
+        let keys = tokenize(query).iter().map(|t| creator_key(t)).collect();
+        let (principals, last) = paginate(&self.intersect(keys).await?, start, end);
+        let mut hits = Vec::with_capacity(principals.len());
+        for principal in principals {
+            let principal = Principal::from_text(principal)?;
+            if let Some(doc) = self.0.read_json(creator_doc_key(principal)).await? {
+                hits.push(doc);
+            }
+        }
+        Ok((hits, last))
+    }
+
+    async fn hashtag_posts(
+        &self,
+        hashtag: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<(Vec<PostKey>, bool), ServerFnError> {
+        let key = hashtag_key(hashtag);
+        let total = self.0.set_len(key.clone()).await?;
+        let hits = self
+            .0
+            .set_range(key, start, end)
+            .await?
+            .iter()
+            .map(|m| parse_post_member(m))
+            .collect::<Result<_, _>>()?;
+        Ok((hits, end >= total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use auth::server_impl::store::redb_kv::ReDBKV;
+
+    use super::*;
+    use crate::search::tests::{assert_index_behaviour, block_on};
+
+    #[test]
+    fn kv_index_matches_words_and_hashtags() {
+        let path = std::env::temp_dir().join(format!("search-kv-{}.db", std::process::id()));
+        _ = std::fs::remove_file(&path);
+        let index = KVSearchIndex(KVStoreImpl::ReDB(ReDBKV::open(path).unwrap()));
+        block_on(assert_index_behaviour(index));
+    }
+
+    #[test]
+    fn post_members_round_trip() {
+        let key = (Principal::anonymous(), 42);
+        assert_eq!(parse_post_member(&post_member(key)).unwrap(), key);
+    }
+}
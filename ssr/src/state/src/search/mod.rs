@@ -0,0 +1,234 @@
+#[cfg(feature = "ssr")]
+pub mod in_memory;
+#[cfg(feature = "ssr")]
+pub mod kv_index;
+
+use candid::Principal;
+use serde::{Deserialize, Serialize};
+use yral_canisters_common::cursored_data::KeyedData;
+
+pub type PostKey = (Principal, u64);
+
+/// A post as seen by the search index
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PostDoc {
+    pub canister_id: Principal,
+    pub post_id: u64,
+    pub description: String,
+    pub hashtags: Vec<String>,
+    /// Seconds since UNIX epoch, results are ordered by this
+    pub created_at: u64,
+}
+
+impl PostDoc {
+    pub fn key(&self) -> PostKey {
+        (self.canister_id, self.post_id)
+    }
+
+    /// Normalized and deduplicated
+    pub fn normalized_hashtags(&self) -> Vec<String> {
+        let mut hashtags = self
+            .hashtags
+            .iter()
+            .map(|h| normalize_hashtag(h))
+            .filter(|h| !h.is_empty())
+            .collect::<Vec<_>>();
+        hashtags.sort();
+        hashtags.dedup();
+        hashtags
+    }
+
+    pub fn tokens(&self) -> Vec<String> {
+        let mut tokens = tokenize(&self.description);
+        tokens.extend(self.hashtags.iter().flat_map(|h| tokenize(h)));
+        tokens.sort();
+        tokens.dedup();
+        tokens
+    }
+}
+
+/// A creator as seen by the search index
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CreatorDoc {
+    pub principal: Principal,
+    pub canister_id: Principal,
+    pub display_name: String,
+    pub propic_url: String,
+}
+
+impl CreatorDoc {
+    pub fn tokens(&self) -> Vec<String> {
+        let mut tokens = tokenize(&self.display_name);
+        tokens.push(self.principal.to_text());
+        tokens
+    }
+}
+
+impl KeyedData for CreatorDoc {
+    type Key = Principal;
+
+    fn key(&self) -> Self::Key {
+        self.principal
+    }
+}
+
+/// Hashtags are matched case insensitively and without the leading `#`
+pub fn normalize_hashtag(hashtag: &str) -> String {
+    hashtag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Lowercased alphanumeric words, deduplicated
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|t| t.chars().count() >= 2)
+        .map(|t| t.to_lowercase())
+        .collect::<Vec<_>>();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
+
+/// Returns the requested page and whether it's the last one
+pub fn paginate<T: Clone>(items: &[T], start: usize, end: usize) -> (Vec<T>, bool) {
+    let start = start.min(items.len());
+    let end = end.min(items.len());
+    (items[start..end].to_vec(), end == items.len())
+}
+
+#[cfg(feature = "ssr")]
+mod backend {
+    use enum_dispatch::enum_dispatch;
+    use leptos::prelude::ServerFnError;
+
+    use super::{in_memory::InMemorySearchIndex, kv_index::KVSearchIndex};
+    use super::{CreatorDoc, PostDoc, PostKey};
+
+    /// Search backend for posts and creators
+    /// results are ordered newest first
+    #[enum_dispatch]
+    #[allow(async_fn_in_trait)]
+    pub trait SearchIndex: Send {
+        async fn index_post(&self, doc: PostDoc) -> Result<(), ServerFnError>;
+
+        async fn index_creator(&self, doc: CreatorDoc) -> Result<(), ServerFnError>;
+
+        async fn search_posts(
+            &self,
+            query: &str,
+            start: usize,
+            end: usize,
+        ) -> Result<(Vec<PostKey>, bool), ServerFnError>;
+
+        async fn search_creators(
+            &self,
+            query: &str,
+            start: usize,
+            end: usize,
+        ) -> Result<(Vec<CreatorDoc>, bool), ServerFnError>;
+
+        /// Posts tagged with the normalized `hashtag`
+        async fn hashtag_posts(
+            &self,
+            hashtag: &str,
+            start: usize,
+            end: usize,
+        ) -> Result<(Vec<PostKey>, bool), ServerFnError>;
+    }
+
+    #[derive(Clone)]
+    #[enum_dispatch(SearchIndex)]
+    pub enum SearchIndexImpl {
+        KV(KVSearchIndex),
+        InMemory(InMemorySearchIndex),
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub use backend::*;
+
+#[cfg(all(test, feature = "ssr"))]
+pub(crate) mod tests {
+    use candid::Principal;
+
+    use super::*;
+
+    pub fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_multi_thread()
+            .build()
+            .unwrap()
+            .block_on(f)
+    }
+
+    fn post(post_id: u64, description: &str, hashtags: &[&str], created_at: u64) -> PostDoc {
+        PostDoc {
+            canister_id: Principal::anonymous(),
+            post_id,
+            description: description.into(),
+            hashtags: hashtags.iter().map(|h| h.to_string()).collect(),
+            created_at,
+        }
+    }
+
+    fn creator(n: u8, display_name: &str) -> CreatorDoc {
+        CreatorDoc {
+            principal: Principal::from_slice(&[n]),
+            canister_id: Principal::anonymous(),
+            display_name: display_name.into(),
+            propic_url: String::new(),
+        }
+    }
+
+    /// Behaviour every index has to share, queries use whole words
+    pub async fn assert_index_behaviour(index: impl SearchIndex) {
+        let anon = Principal::anonymous();
+        index
+            .index_post(post(1, "sunset at the beach", &["#Travel"], 10))
+            .await
+            .unwrap();
+        index
+            .index_post(post(2, "beach volleyball", &["sports", "travel"], 20))
+            .await
+            .unwrap();
+        // reindexing doesn't duplicate
+        index
+            .index_post(post(1, "sunset at the beach", &["#Travel"], 10))
+            .await
+            .unwrap();
+
+        let (hits, last) = index.search_posts("beach", 0, 10).await.unwrap();
+        assert_eq!(hits, [(anon, 2), (anon, 1)]);
+        assert!(last);
+        let (hits, _) = index.search_posts("Beach SUNSET", 0, 10).await.unwrap();
+        assert_eq!(hits, [(anon, 1)]);
+        let (hits, last) = index.search_posts("beach", 0, 1).await.unwrap();
+        assert_eq!(hits, [(anon, 2)]);
+        assert!(!last);
+        assert!(index
+            .search_posts("mountain", 0, 10)
+            .await
+            .unwrap()
+            .0
+            .is_empty());
+
+        let (hits, last) = index.hashtag_posts("travel", 0, 10).await.unwrap();
+        assert_eq!(hits, [(anon, 2), (anon, 1)]);
+        assert!(last);
+        let (hits, _) = index.hashtag_posts("sports", 0, 10).await.unwrap();
+        assert_eq!(hits, [(anon, 2)]);
+
+        index.index_creator(creator(1, "Jane Doe")).await.unwrap();
+        index.index_creator(creator(2, "John Doe")).await.unwrap();
+        index.index_creator(creator(1, "Jane Doe")).await.unwrap();
+        let (hits, _) = index.search_creators("doe", 0, 10).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        let (hits, _) = index.search_creators("jane doe", 0, 10).await.unwrap();
+        assert_eq!(hits, [creator(1, "Jane Doe")]);
+    }
+
+    #[test]
+    fn hashtags_are_normalized() {
+        let doc = post(0, "", &["#Travel", "travel ", "", "#"], 0);
+        assert_eq!(doc.normalized_hashtags(), ["travel"]);
+    }
+}
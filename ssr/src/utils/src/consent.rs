@@ -0,0 +1,153 @@
+use std::{fmt, str::FromStr};
+
+use codee::string::FromToStringCodec;
+use consts::CONSENT_STORE;
+use leptos::prelude::*;
+use leptos_use::{use_cookie_with_options, SameSite, UseCookieOptions};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsentCategory {
+    /// GTM, gtag and GA4 events
+    Analytics,
+    /// Sentry in the browser
+    ErrorReporting,
+    /// Warehouse events, used to rank the feed
+    Personalization,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Consent {
+    pub analytics: bool,
+    pub error_reporting: bool,
+    pub personalization: bool,
+}
+
+impl Consent {
+    pub const ALL: Self = Self {
+        analytics: true,
+        error_reporting: true,
+        personalization: true,
+    };
+    /// Only what the app needs to work
+    pub const ESSENTIAL: Self = Self {
+        analytics: false,
+        error_reporting: false,
+        personalization: false,
+    };
+
+    pub fn allows(&self, category: ConsentCategory) -> bool {
+        match category {
+            ConsentCategory::Analytics => self.analytics,
+            ConsentCategory::ErrorReporting => self.error_reporting,
+            ConsentCategory::Personalization => self.personalization,
+        }
+    }
+
+    pub fn with(mut self, category: ConsentCategory, allowed: bool) -> Self {
+        match category {
+            ConsentCategory::Analytics => self.analytics = allowed,
+            ConsentCategory::ErrorReporting => self.error_reporting = allowed,
+            ConsentCategory::Personalization => self.personalization = allowed,
+        }
+        self
+    }
+}
+
+/// Stored in the cookie as one digit per category, e.g `101`
+impl fmt::Display for Consent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for allowed in [self.analytics, self.error_reporting, self.personalization] {
+            write!(f, "{}", u8::from(allowed))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Consent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let flags = s
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(format!("invalid consent flag {c}")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [analytics, error_reporting, personalization] = flags[..] else {
+            return Err(format!("invalid consent {s}"));
+        };
+        Ok(Self {
+            analytics,
+            error_reporting,
+            personalization,
+        })
+    }
+}
+
+fn consent_from_cookies(cookies: &str) -> Option<Consent> {
+    cookies.split(';').find_map(|cookie| {
+        cookie
+            .trim()
+            .strip_prefix(CONSENT_STORE)?
+            .strip_prefix('=')?
+            .parse()
+            .ok()
+    })
+}
+
+/// Consent is asked for again after this
+const CONSENT_MAX_AGE_MS: i64 = 365 * 24 * 60 * 60 * 1000;
+
+/// Consent given on this browser, `None` until the user makes a choice
+#[derive(Clone, Copy)]
+pub struct ConsentCtx {
+    pub consent: Signal<Option<Consent>>,
+    set_consent: WriteSignal<Option<Consent>>,
+}
+
+impl ConsentCtx {
+    /// Reads the consent cookie, must be called under a reactive owner
+    pub fn from_cookie() -> Self {
+        let (consent, set_consent) = use_cookie_with_options::<Consent, FromToStringCodec>(
+            CONSENT_STORE,
+            UseCookieOptions::default()
+                .path("/".to_string())
+                .max_age(CONSENT_MAX_AGE_MS)
+                .same_site(SameSite::Lax),
+        );
+        Self {
+            consent,
+            set_consent,
+        }
+    }
+
+    pub fn allows(&self, category: ConsentCategory) -> bool {
+        self.consent.with(|c| c.is_some_and(|c| c.allows(category)))
+    }
+
+    pub fn set(&self, consent: Consent) {
+        self.set_consent.set(Some(consent));
+    }
+}
+
+/// Consent read straight from the cookie, for code running outside the reactive tree
+#[cfg(feature = "hydrate")]
+pub fn consent_from_document() -> Option<Consent> {
+    let cookies = js_sys::Reflect::get(&document(), &"cookie".into())
+        .ok()?
+        .as_string()?;
+    consent_from_cookies(&cookies)
+}
+
+/// Consent sent along with the current request
+#[cfg(feature = "ssr")]
+pub async fn consent_from_request() -> Option<Consent> {
+    let headers: http::HeaderMap = leptos_axum::extract().await.ok()?;
+    headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .find_map(consent_from_cookies)
+}
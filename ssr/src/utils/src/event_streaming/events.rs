@@ -114,8 +114,11 @@ impl HistoryCtx {
 
 #[cfg(feature = "ga4")]
 use crate::event_streaming::{
-    send_event_ssr, send_event_ssr_spawn, send_event_warehouse_ssr_spawn, send_user_id,
+    ga4_client_id, send_event_ssr, send_event_ssr_spawn, send_event_warehouse_ssr_spawn,
+    send_user_id,
 };
+#[cfg(all(feature = "hydrate", feature = "ga4"))]
+use crate::event_streaming::{playback::PlaybackSession, queue::flush_before_unload};
 use crate::token::nsfw::NSFWInfo;
 use crate::user::{user_details_can_store_or_ret, user_details_or_ret};
 use leptos::html::Video;
@@ -169,117 +172,123 @@ impl VideoWatched {
 
             // video_viewed - analytics
             let (video_watched, set_video_watched) = signal(false);
-            let (full_video_watched, set_full_video_watched) = signal(false);
 
             let cans_store: RwSignal<Option<Canisters<true>>> = auth_canisters_store();
 
-            let post_for_time = vid_details;
-            let _ = use_event_listener(container_ref, ev::timeupdate, move |evt| {
+            // playback of the post in `session_post` - warehousing
+            let session = StoredValue::new(PlaybackSession::default());
+            let session_post = StoredValue::new(None::<PostDetails>);
+
+            let queue_playback_event = move |event_name: &str, extra: serde_json::Value| {
                 let user = user_details_can_store_or_ret!(cans_store);
-                let post_o = post_for_time();
+                let post_o = session_post.get_value();
                 let post = post_o.as_ref();
+                let nsfw_probability = post.map(|p| p.nsfw_probability);
+                let is_nsfw = nsfw_probability.map(|prob| prob > 0.5);
 
+                let mut params = json!({
+                    "publisher_user_id": post.map(|p| p.poster_principal),
+                    "user_id": user.details.principal,
+                    "is_loggedIn": is_connected.get_untracked(),
+                    "display_name": user.details.display_name,
+                    "canister_id": user.canister_id,
+                    "video_id": post.map(|p| p.uid.clone()),
+                    "video_category": "NA",
+                    "creator_category": "NA",
+                    "hashtag_count": post.map(|p| p.hastags.len()),
+                    "is_NSFW": is_nsfw,
+                    "is_hotorNot": post.map(|p| p.is_hot_or_not()),
+                    "feed_type": "NA",
+                    "view_count": post.map(|p| p.views),
+                    "like_count": post.map(|p| p.likes),
+                    "share_count": 0,
+                    "post_id": post.map(|p| p.post_id),
+                    "publisher_canister_id": post.map(|p| p.canister_id),
+                    "nsfw_probability": nsfw_probability,
+                });
+                if let (Some(params), serde_json::Value::Object(extra)) =
+                    (params.as_object_mut(), extra)
+                {
+                    params.extend(extra);
+                }
+                send_event_warehouse_ssr_spawn(event_name.to_string(), params.to_string());
+            };
+
+            let finish_session = move || {
+                let mut finished = None;
+                session.update_value(|s| finished = s.finish());
+                let Some(finished) = finished else {
+                    return;
+                };
+                queue_playback_event(
+                    "video_duration_watched",
+                    json!({
+                        "percentage_watched": finished.percentage_watched(),
+                        "absolute_watched": finished.watched_secs,
+                        "unique_watched": finished.intervals.unique_secs(),
+                        "video_duration": finished.duration,
+                        "loop_count": finished.loop_count,
+                        "stall_count": finished.stall_count,
+                        "stall_duration": finished.stall_secs,
+                        "muted_duration": finished.muted_secs,
+                        "is_muted": finished.muted,
+                    }),
+                );
+            };
+
+            // a new post starts a new session, reporting the previous one
+            Effect::new(move |_| {
+                let post = vid_details();
+                if session_post
+                    .with_value(|p| p.as_ref().map(|p| &p.uid) == post.as_ref().map(|p| &p.uid))
+                {
+                    return;
+                }
+                finish_session();
+                session_post.set_value(post);
+                session.set_value(PlaybackSession::default());
+                set_video_watched.set(false);
+            });
+
+            let _ = use_event_listener(container_ref, ev::timeupdate, move |evt| {
                 let target = evt.target().unwrap();
                 let video = target.unchecked_into::<web_sys::HtmlVideoElement>();
                 let duration = video.duration();
                 let current_time = video.current_time();
-                let nsfw_probability = post.map(|p| p.nsfw_probability);
-                let is_nsfw = nsfw_probability.map(|prob| prob > 0.5);
-                if current_time < 0.95 * duration {
-                    set_full_video_watched.set(false);
-                }
 
-                // send bigquery event when video is watched > 95%
-                if current_time >= 0.95 * duration && !full_video_watched.get() {
-                    send_event_warehouse_ssr_spawn(
-                        "video_duration_watched".to_string(),
+                let mut reached = vec![];
+                session.update_value(|s| {
+                    reached = s.on_time_update(current_time, duration, video.muted())
+                });
+                for quartile in reached {
+                    let (loop_count, muted) = session.with_value(|s| (s.loop_count, s.muted));
+                    queue_playback_event(
+                        "video_quartile_watched",
                         json!({
-                            "publisher_user_id": post.map(|p| p.poster_principal),
-                            "user_id": user.details.principal,
-                            "is_loggedIn": is_connected(),
-                            "display_name": user.details.display_name.clone(),
-                            "canister_id": user.canister_id,
-                            "video_id": post.map(|p| p.uid.clone()),
-                            "video_category": "NA",
-                            "creator_category": "NApublisher_canister_id(",
-                            "hashtag_count": post.map(|p| p.hastags.len()),
-                            "is_NSFW": is_nsfw,
-                            "is_hotorNot": post.map(|p| p.is_hot_or_not()),
-                            "feed_type": "NA",
-                            "view_count": post.map(|p| p.views),
-                            "like_count": post.map(|p| p.likes),
-                            "share_count": 0,
-                            "percentage_watched": 100.0,
-                            "absolute_watched": duration,
+                            "quartile": quartile,
+                            "loop_count": loop_count,
+                            "is_muted": muted,
                             "video_duration": duration,
-                            "post_id": post.map(|p| p.post_id),
-                            "publisher_canister_id": post.map(|p| p.canister_id),
-                            "nsfw_probability": nsfw_probability,
-                        })
-                        .to_string(),
+                        }),
                     );
-
-                    set_full_video_watched.set(true);
                 }
 
-                if video_watched.get() {
+                if video_watched.get_untracked() || current_time < 3.0 {
                     return;
                 }
 
-                if current_time >= 3.0 {
-                    send_event_ssr_spawn(
-                        "video_viewed".to_string(),
-                        json!({
-                            "publisher_user_id": post.map(|p| p.poster_principal),
-                            "user_id": user.details.principal,
-                            "is_loggedIn": is_connected(),
-                            "display_name": user.details.display_name,
-                            "canister_id": user.canister_id,
-                            "video_id": post.map(|p| p.uid.clone()),
-                            "video_category": "NA",
-                            "creator_category": "NA",
-                            "hashtag_count": post.map(|p| p.hastags.len()),
-                            "is_NSFW": is_nsfw,
-                            "is_hotorNot": post.map(|p| p.is_hot_or_not()),
-                            "feed_type": "NA",
-                            "view_count": post.map(|p| p.views),
-                            "like_count": post.map(|p| p.likes),
-                            "share_count": 0,
-                            "post_id": post.map(|p| p.post_id),
-                            "publisher_canister_id": post.map(|p| p.canister_id),
-                            "nsfw_probability": nsfw_probability,
-                        })
-                        .to_string(),
-                    );
-                    set_video_watched.set(true);
-                }
-            });
-
-            // video duration watched - warehousing
-            let post_for_warehouse = vid_details;
-            let _ = use_event_listener(container_ref, ev::pause, move |evt| {
                 let user = user_details_can_store_or_ret!(cans_store);
-                let post_o = post_for_warehouse();
+                let post_o = vid_details.get_untracked();
                 let post = post_o.as_ref();
                 let nsfw_probability = post.map(|p| p.nsfw_probability);
                 let is_nsfw = nsfw_probability.map(|prob| prob > 0.5);
-                let target = evt.target().unwrap();
-                let video = target.unchecked_into::<web_sys::HtmlVideoElement>();
-                let duration = video.duration();
-                let current_time = video.current_time();
-                if current_time < 0.1 {
-                    return;
-                }
-
-                let percentage_watched = (current_time / duration) * 100.0;
-
-                send_event_warehouse_ssr_spawn(
-                    "video_duration_watched".to_string(),
+                send_event_ssr_spawn(
+                    "video_viewed".to_string(),
                     json!({
                         "publisher_user_id": post.map(|p| p.poster_principal),
                         "user_id": user.details.principal,
-                        "is_loggedIn": is_connected(),
-                        "display_name": user.details.display_name.clone(),
+                        "is_loggedIn": is_connected.get_untracked(),
+                        "display_name": user.details.display_name,
                         "canister_id": user.canister_id,
                         "video_id": post.map(|p| p.uid.clone()),
                         "video_category": "NA",
@@ -291,15 +300,27 @@ impl VideoWatched {
                         "view_count": post.map(|p| p.views),
                         "like_count": post.map(|p| p.likes),
                         "share_count": 0,
-                        "percentage_watched": percentage_watched,
-                        "absolute_watched": current_time,
-                        "video_duration": duration,
                         "post_id": post.map(|p| p.post_id),
                         "publisher_canister_id": post.map(|p| p.canister_id),
                         "nsfw_probability": nsfw_probability,
                     })
                     .to_string(),
                 );
+                set_video_watched.set(true);
+            });
+
+            let _ = use_event_listener(container_ref, ev::waiting, move |_| {
+                session.update_value(|s| s.on_waiting());
+            });
+            let _ = use_event_listener(container_ref, ev::playing, move |_| {
+                session.update_value(|s| s.on_playing());
+            });
+
+            // video duration watched - one segment per pause
+            let _ = use_event_listener(container_ref, ev::pause, move |_| finish_session());
+            let _ = use_event_listener(leptos_use::use_window(), ev::pagehide, move |_| {
+                finish_session();
+                flush_before_unload();
             });
         }
     }
@@ -942,6 +963,7 @@ impl TokenCreationCompleted {
                     "csam_detected": nsfw_info.csam_detected,
                 })
                 .to_string(),
+                ga4_client_id(),
             )
             .await;
         }
@@ -976,6 +998,7 @@ impl TokenCreationFailed {
                     "error": error_str
                 })
                 .to_string(),
+                ga4_client_id(),
             )
             .await;
         }
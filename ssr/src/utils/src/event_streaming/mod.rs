@@ -2,13 +2,19 @@ use std::env;
 
 use gloo_utils::format::JsValueSerdeExt;
 use leptos::prelude::*;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use wasm_bindgen::prelude::*;
 
 use consts::GTAG_MEASUREMENT_ID;
 
+#[cfg(all(feature = "ga4", feature = "ssr"))]
+use crate::consent::Consent;
+
 pub mod events;
+pub mod playback;
+#[cfg(all(feature = "ga4", feature = "hydrate"))]
+pub mod queue;
 
 #[derive(Debug, Serialize)]
 struct GA4Event {
@@ -39,51 +45,171 @@ pub struct EventHistory {
     pub event_name: RwSignal<String>,
 }
 
+/// Upper bound on events accepted in a single batch
+pub const MAX_EVENT_BATCH: usize = 50;
+
+/// An analytics event waiting on the client to be sent
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueuedEvent {
+    pub event_name: String,
+    /// JSON encoded params
+    pub params: String,
+    /// Only sent to the warehouse, not GA4
+    pub warehouse_only: bool,
+    /// GA4 client id of the browser the event came from
+    pub client_id: Option<String>,
+}
+
+/// Pseudonymous GA4 client id of this browser, in the `<random>.<timestamp>` format gtag uses
+/// only available once the user has agreed to analytics
+pub fn ga4_client_id() -> Option<String> {
+    #[cfg(feature = "hydrate")]
+    {
+        use crate::consent::consent_from_document;
+        use consts::GA4_CLIENT_ID_STORE;
+        use gloo::storage::{LocalStorage, Storage};
+
+        if !consent_from_document().is_some_and(|c| c.analytics) {
+            return None;
+        }
+        if let Ok(id) = LocalStorage::get::<String>(GA4_CLIENT_ID_STORE) {
+            return Some(id);
+        }
+        let id = format!(
+            "{}.{}",
+            (js_sys::Math::random() * u32::MAX as f64) as u32,
+            (js_sys::Date::now() / 1000.0) as u64
+        );
+        _ = LocalStorage::set(GA4_CLIENT_ID_STORE, &id);
+        Some(id)
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        None
+    }
+}
+
 #[cfg(feature = "ga4")]
 #[server]
-pub async fn send_event_ssr(event_name: String, params: String) -> Result<(), ServerFnError> {
-    use super::host::get_host;
+pub async fn send_event_ssr(
+    event_name: String,
+    params: String,
+    client_id: Option<String>,
+) -> Result<(), ServerFnError> {
+    use crate::consent::consent_from_request;
 
-    let params = serde_json::from_str::<serde_json::Value>(&params).unwrap();
+    // events without consent are dropped
+    let Some(consent) = consent_from_request().await else {
+        return Ok(());
+    };
+    let params = serde_json::from_str::<serde_json::Value>(&params)?;
+    fan_out_event(&event_name, params, client_id, consent).await;
+
+    Ok(())
+}
+
+/// Sends a batch of events, each to GA4 and the warehouse concurrently
+#[cfg(feature = "ga4")]
+#[server]
+pub async fn send_events_batch_ssr(events: Vec<QueuedEvent>) -> Result<(), ServerFnError> {
+    use crate::consent::consent_from_request;
+    use futures::future::join_all;
+
+    if events.len() > MAX_EVENT_BATCH {
+        return Err(ServerFnError::new("too many events in batch"));
+    }
+    // events without consent are dropped
+    let Some(consent) = consent_from_request().await else {
+        return Ok(());
+    };
+    // a malformed event is dropped on its own, rejecting the batch would retry it forever
+    let events = events.into_iter().filter_map(|event| {
+        match serde_json::from_str::<serde_json::Value>(&event.params) {
+            Ok(params) => Some((event, params)),
+            Err(e) => {
+                log::warn!("dropping malformed {} event: {e}", event.event_name);
+                None
+            }
+        }
+    });
+
+    join_all(events.map(|(event, params)| async move {
+        if !event.warehouse_only {
+            fan_out_event(&event.event_name, params, event.client_id, consent).await;
+        } else if consent.personalization {
+            send_event_warehouse(&event.event_name, &params).await;
+        }
+    }))
+    .await;
+
+    Ok(())
+}
+
+#[cfg(all(feature = "ga4", feature = "ssr"))]
+async fn fan_out_event(
+    event_name: &str,
+    mut params: serde_json::Value,
+    client_id: Option<String>,
+    consent: Consent,
+) {
+    use super::host::get_host;
 
     let host_str = get_host();
-    let mut params = params.clone();
     params["host"] = json!(host_str);
 
     if params["page_location"].is_null() {
         params["page_location"] = json!(format!("https://{}", host_str));
     }
 
-    // Warehouse
-    send_event_warehouse(&event_name, &params).await;
-
-    // GA4
-    // get client_id as user_id from params
     let user_id = params["user_id"].as_str().unwrap_or("0");
-    let res = send_event_ga4(user_id, &event_name, &params).await;
+    let warehouse = async {
+        if consent.personalization {
+            send_event_warehouse(event_name, &params).await;
+        }
+    };
+    // the GA4 error isn't `Send`, so it can't be held inside the joined future
+    let ga4 = async {
+        if !consent.analytics {
+            return Ok(());
+        }
+        // events sent from the server have no browser to identify
+        let client_id = client_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        send_event_ga4(&client_id, user_id, event_name, &params)
+            .await
+            .map_err(|e| e.to_string())
+    };
+    let (_, res) = futures::join!(warehouse, ga4);
 
     if let Err(e) = res {
         log::error!("Error sending event to GA4: {:?}", e);
     }
-
-    Ok(())
 }
 
 #[cfg(feature = "ga4")]
 pub fn send_event_ssr_spawn(event_name: String, params: String) {
-    use leptos::task::spawn_local;
-
     let mut params = serde_json::from_str::<serde_json::Value>(&params).unwrap();
     params["page_location"] = json!(window().location().href().unwrap().to_string());
     let params = serde_json::to_string(&params).unwrap();
 
-    spawn_local(async move {
-        let _ = send_event_ssr(event_name, params).await;
+    #[cfg(feature = "hydrate")]
+    queue::queue_event(QueuedEvent {
+        event_name,
+        params,
+        warehouse_only: false,
+        client_id: None,
     });
+    // events are only queued in the browser
+    #[cfg(not(feature = "hydrate"))]
+    _ = (event_name, params);
 }
 
 #[cfg(feature = "ga4")]
 pub fn send_user_id(user_id: String) {
+    // gtag is only loaded once the user agrees to analytics
+    #[cfg(feature = "hydrate")]
+    if !crate::consent::consent_from_document().is_some_and(|c| c.analytics) {
+        return;
+    }
     let gtag_measurement_id = GTAG_MEASUREMENT_ID.as_ref();
 
     gtag(
@@ -120,6 +246,14 @@ pub async fn send_event_warehouse_ssr(
     event_name: String,
     params: String,
 ) -> Result<(), ServerFnError> {
+    use crate::consent::consent_from_request;
+
+    if !consent_from_request()
+        .await
+        .is_some_and(|c| c.personalization)
+    {
+        return Ok(());
+    }
     let params = serde_json::from_str::<serde_json::Value>(&params).unwrap();
     send_event_warehouse(&event_name, &params).await;
 
@@ -128,11 +262,16 @@ pub async fn send_event_warehouse_ssr(
 
 #[cfg(feature = "ga4")]
 pub fn send_event_warehouse_ssr_spawn(event_name: String, params: String) {
-    use leptos::task::spawn_local;
-
-    spawn_local(async move {
-        let _ = send_event_warehouse_ssr(event_name, params).await;
+    #[cfg(feature = "hydrate")]
+    queue::queue_event(QueuedEvent {
+        event_name,
+        params,
+        warehouse_only: true,
+        client_id: None,
     });
+    // events are only queued in the browser
+    #[cfg(not(feature = "hydrate"))]
+    _ = (event_name, params);
 }
 
 #[cfg(all(feature = "ga4", feature = "ssr"))]
@@ -192,6 +331,7 @@ fn convert_leaf_values_to_string(value: serde_json::Value) -> serde_json::Value
 
 #[cfg(all(feature = "ga4", feature = "ssr"))]
 pub async fn send_event_ga4(
+    client_id: &str,
     user_id: &str,
     event_name: &str,
     params: &serde_json::Value,
@@ -210,7 +350,7 @@ pub async fn send_event_ga4(
     let params = convert_leaf_values_to_string(params.clone());
 
     let payload = GA4Event {
-        client_id: "12345".to_string(), // Should be some unique id
+        client_id: client_id.to_string(),
         user_id: Some(user_id.to_string()),
         events: vec![Event {
             name: event_name.to_string(),
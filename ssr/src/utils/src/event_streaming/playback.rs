@@ -0,0 +1,232 @@
+use web_time::Instant;
+
+/// Quartiles of a video reported once per post view
+pub const QUARTILES: [u8; 4] = [25, 50, 75, 100];
+/// Jumps between `timeupdate`s larger than this are seeks, not playback
+const MAX_PROGRESS_STEP_SECS: f64 = 1.5;
+/// A jump back to the start from this close to the end is counted as a loop
+const LOOP_END_WINDOW_SECS: f64 = 1.0;
+/// `timeupdate` fires every ~250ms, so the end of a video is rarely seen exactly
+const QUARTILE_TOLERANCE_SECS: f64 = 0.5;
+
+/// Sorted, non-overlapping ranges of a video that were actually played
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WatchedIntervals(Vec<(f64, f64)>);
+
+impl WatchedIntervals {
+    pub fn add(&mut self, start: f64, end: f64) {
+        if end <= start {
+            return;
+        }
+        let (mut start, mut end) = (start, end);
+        self.0.retain(|&(s, e)| {
+            if e < start || s > end {
+                return true;
+            }
+            start = start.min(s);
+            end = end.max(e);
+            false
+        });
+        let idx = self.0.partition_point(|&(s, _)| s < start);
+        self.0.insert(idx, (start, end));
+    }
+
+    /// Seconds of the video seen at least once
+    pub fn unique_secs(&self) -> f64 {
+        self.0.iter().map(|(s, e)| e - s).sum()
+    }
+}
+
+/// Playback of a single post, from the first frame until the viewer moves on
+#[derive(Clone, Debug, Default)]
+pub struct PlaybackSession {
+    pub duration: f64,
+    pub intervals: WatchedIntervals,
+    /// Total seconds played, including loops and replays
+    pub watched_secs: f64,
+    pub muted_secs: f64,
+    pub loop_count: u32,
+    pub stall_count: u32,
+    pub stall_secs: f64,
+    pub muted: bool,
+    stall_started: Option<Instant>,
+    last_time: Option<f64>,
+    quartiles_reached: Vec<u8>,
+}
+
+impl PlaybackSession {
+    /// Records progress up to `current_time`
+    /// returns the quartiles that were reached for the first time
+    pub fn on_time_update(&mut self, current_time: f64, duration: f64, muted: bool) -> Vec<u8> {
+        if duration.is_finite() && duration > 0.0 {
+            self.duration = duration;
+        }
+        self.muted = muted;
+
+        let mut looped = false;
+        if let Some(last) = self.last_time {
+            let step = current_time - last;
+            if step > 0.0 && step <= MAX_PROGRESS_STEP_SECS {
+                self.intervals.add(last, current_time);
+                self.watched_secs += step;
+                if muted {
+                    self.muted_secs += step;
+                }
+            } else if step < 0.0
+                && self.duration > 0.0
+                && last >= self.duration - LOOP_END_WINDOW_SECS
+            {
+                self.intervals.add(last, self.duration);
+                self.loop_count += 1;
+                looped = true;
+            }
+        }
+        self.last_time = Some(current_time);
+
+        if self.duration <= 0.0 {
+            return vec![];
+        }
+        let unique = self.intervals.unique_secs() + QUARTILE_TOLERANCE_SECS;
+        let reached = QUARTILES
+            .into_iter()
+            .filter(|q| !self.quartiles_reached.contains(q))
+            .filter(|&q| looped || unique >= self.duration * q as f64 / 100.0)
+            .collect::<Vec<_>>();
+        self.quartiles_reached.extend_from_slice(&reached);
+        reached
+    }
+
+    /// Playback stopped to buffer
+    pub fn on_waiting(&mut self) {
+        self.stall_started.get_or_insert_with(Instant::now);
+    }
+
+    /// Playback resumed, closing any open stall
+    pub fn on_playing(&mut self) {
+        if let Some(started) = self.stall_started.take() {
+            self.stall_count += 1;
+            self.stall_secs += started.elapsed().as_secs_f64();
+        }
+    }
+
+    /// Ends the current watch segment, keeping the quartiles already reported
+    /// returns the finished segment if anything was played
+    pub fn finish(&mut self) -> Option<PlaybackSession> {
+        self.on_playing();
+        let next = PlaybackSession {
+            duration: self.duration,
+            muted: self.muted,
+            quartiles_reached: std::mem::take(&mut self.quartiles_reached),
+            ..Default::default()
+        };
+        let finished = std::mem::replace(self, next);
+        (finished.watched_secs > 0.0).then_some(finished)
+    }
+
+    /// Percentage of the video seen at least once
+    pub fn percentage_watched(&self) -> f64 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        (self.intervals.unique_secs() / self.duration * 100.0).min(100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_merge_overlapping_and_touching_ranges() {
+        let cases: &[(&[(f64, f64)], &[(f64, f64)])] = &[
+            (&[(0.0, 2.0), (4.0, 5.0)], &[(0.0, 2.0), (4.0, 5.0)]),
+            (&[(0.0, 2.0), (1.0, 3.0)], &[(0.0, 3.0)]),
+            (&[(0.0, 2.0), (2.0, 3.0)], &[(0.0, 3.0)]),
+            (&[(4.0, 5.0), (0.0, 1.0), (0.5, 4.5)], &[(0.0, 5.0)]),
+            (&[(3.0, 4.0), (0.0, 1.0)], &[(0.0, 1.0), (3.0, 4.0)]),
+            // empty and backwards ranges are ignored
+            (&[(2.0, 2.0), (3.0, 1.0)], &[]),
+        ];
+        for (added, expected) in cases {
+            let mut intervals = WatchedIntervals::default();
+            for &(start, end) in *added {
+                intervals.add(start, end);
+            }
+            assert_eq!(intervals.0, *expected, "{added:?}");
+        }
+    }
+
+    #[test]
+    fn unique_secs_ignore_rewatched_parts() {
+        let mut intervals = WatchedIntervals::default();
+        intervals.add(0.0, 4.0);
+        intervals.add(2.0, 6.0);
+        intervals.add(8.0, 9.0);
+        assert_eq!(intervals.unique_secs(), 7.0);
+    }
+
+    /// Plays from `from` to `to` in `timeupdate` sized steps
+    fn play(session: &mut PlaybackSession, from: f64, to: f64, muted: bool) -> Vec<u8> {
+        let mut reached = vec![];
+        let mut t = from;
+        while t <= to {
+            reached.extend(session.on_time_update(t, 10.0, muted));
+            t += 0.25;
+        }
+        reached
+    }
+
+    #[test]
+    fn quartiles_are_reported_once_as_the_video_plays() {
+        let mut session = PlaybackSession::default();
+        assert_eq!(play(&mut session, 0.0, 5.0, false), [25, 50]);
+        assert_eq!(play(&mut session, 5.25, 10.0, true), [75, 100]);
+        assert_eq!(session.watched_secs, 10.0);
+        assert_eq!(session.muted_secs, 5.0);
+        assert_eq!(session.percentage_watched(), 100.0);
+
+        // looping back doesn't report them again
+        assert_eq!(play(&mut session, 0.0, 10.0, false), Vec::<u8>::new());
+        assert_eq!(session.loop_count, 1);
+    }
+
+    #[test]
+    fn seeks_are_not_counted_as_watched() {
+        let mut session = PlaybackSession::default();
+        play(&mut session, 0.0, 1.0, false);
+        // skip ahead
+        assert_eq!(session.on_time_update(8.0, 10.0, false), Vec::<u8>::new());
+        assert_eq!(session.watched_secs, 1.0);
+        assert_eq!(session.percentage_watched(), 10.0);
+        // jumping back from mid video is a seek, not a loop
+        session.on_time_update(5.0, 10.0, false);
+        assert_eq!(session.loop_count, 0);
+    }
+
+    #[test]
+    fn looping_from_the_end_reaches_every_quartile() {
+        let mut session = PlaybackSession::default();
+        session.on_time_update(8.0, 10.0, false);
+        session.on_time_update(9.5, 10.0, false);
+        assert_eq!(session.on_time_update(0.0, 10.0, false), QUARTILES);
+        assert_eq!(session.loop_count, 1);
+    }
+
+    #[test]
+    fn finish_starts_a_new_segment_keeping_reported_quartiles() {
+        let mut session = PlaybackSession::default();
+        assert_eq!(session.finish().map(|s| s.watched_secs), None);
+
+        play(&mut session, 0.0, 3.0, false);
+        session.on_waiting();
+        session.on_playing();
+        let finished = session.finish().unwrap();
+        assert_eq!(finished.watched_secs, 3.0);
+        assert_eq!(finished.stall_count, 1);
+
+        assert_eq!(session.watched_secs, 0.0);
+        assert_eq!(session.duration, 10.0);
+        // 25% was reported in the finished segment
+        assert_eq!(play(&mut session, 3.0, 10.0, false), [50, 75]);
+    }
+}
@@ -0,0 +1,179 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use consts::ANALYTICS_QUEUE_STORE;
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use gloo::timers::future::TimeoutFuture;
+use leptos::{prelude::*, task::spawn_local};
+
+use super::{ga4_client_id, send_events_batch_ssr, QueuedEvent, MAX_EVENT_BATCH};
+use crate::consent::consent_from_document;
+
+const FLUSH_DELAY_MS: u32 = 5_000;
+const RETRY_BASE_MS: u32 = 2_000;
+const RETRY_MAX_MS: u32 = 5 * 60 * 1000;
+/// Oldest events are dropped past this, so a device that stays offline
+/// doesn't fill up its storage
+const MAX_PERSISTED_EVENTS: usize = 500;
+
+thread_local! {
+    static QUEUE: RefCell<VecDeque<QueuedEvent>> = const { RefCell::new(VecDeque::new()) };
+    /// Batch being sent, kept in local storage until the server acknowledges it
+    static IN_FLIGHT: RefCell<Option<Vec<QueuedEvent>>> = const { RefCell::new(None) };
+    static INITIALIZED: Cell<bool> = const { Cell::new(false) };
+    static FLUSH_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+    static FAILED_ATTEMPTS: Cell<u32> = const { Cell::new(0) };
+}
+
+fn is_online() -> bool {
+    window().navigator().on_line()
+}
+
+fn init() {
+    if INITIALIZED.replace(true) {
+        return;
+    }
+
+    // events left over from an earlier offline session
+    if let Ok(persisted) = LocalStorage::get::<Vec<QueuedEvent>>(ANALYTICS_QUEUE_STORE) {
+        LocalStorage::delete(ANALYTICS_QUEUE_STORE);
+        QUEUE.with_borrow_mut(|queue| queue.extend(persisted));
+        schedule_flush(FLUSH_DELAY_MS);
+    }
+
+    EventListener::new(&document(), "visibilitychange", |_| {
+        if document().hidden() {
+            flush_events();
+        }
+    })
+    .forget();
+    EventListener::new(&window(), "pagehide", |_| flush_before_unload()).forget();
+    EventListener::new(&window(), "online", |_| {
+        FAILED_ATTEMPTS.set(0);
+        flush_events();
+    })
+    .forget();
+}
+
+/// Mirrors the batch in flight and the queue to local storage,
+/// so they're sent on the next visit if the page goes away first
+/// events are sent at least once, a batch acknowledged as the page closes is sent again
+fn persist() {
+    let in_flight = IN_FLIGHT.with_borrow(|batch| batch.clone().unwrap_or_default());
+    QUEUE.with_borrow_mut(|queue| {
+        let capacity = MAX_PERSISTED_EVENTS.saturating_sub(in_flight.len());
+        if queue.len() > capacity {
+            queue.drain(..queue.len() - capacity);
+        }
+        if in_flight.is_empty() && queue.is_empty() {
+            LocalStorage::delete(ANALYTICS_QUEUE_STORE);
+            return;
+        }
+        let pending = in_flight.iter().chain(queue.iter()).collect::<Vec<_>>();
+        if let Err(e) = LocalStorage::set(ANALYTICS_QUEUE_STORE, &pending) {
+            log::warn!("failed to persist analytics events: {e}");
+        }
+    });
+}
+
+fn schedule_flush(delay_ms: u32) {
+    if FLUSH_SCHEDULED.replace(true) {
+        return;
+    }
+    spawn_local(async move {
+        TimeoutFuture::new(delay_ms).await;
+        FLUSH_SCHEDULED.set(false);
+        flush_events();
+    });
+}
+
+/// Queues an analytics event, sent in a batch after a short delay,
+/// once the batch is full or when the page is hidden
+/// events are kept in local storage while offline
+/// and dropped if the user hasn't agreed to any of their destinations
+pub fn queue_event(mut event: QueuedEvent) {
+    let consent = consent_from_document().unwrap_or_default();
+    if !(consent.personalization || consent.analytics && !event.warehouse_only) {
+        return;
+    }
+    if !event.warehouse_only {
+        event.client_id = ga4_client_id();
+    }
+    init();
+
+    let queued = QUEUE.with_borrow_mut(|queue| {
+        queue.push_back(event);
+        queue.len()
+    });
+    if !is_online() {
+        persist();
+        return;
+    }
+    if queued >= MAX_EVENT_BATCH {
+        flush_events();
+    } else {
+        schedule_flush(FLUSH_DELAY_MS);
+    }
+}
+
+/// Sends the next batch of queued events right away
+/// failed batches are retried with exponential backoff
+pub fn flush_events() {
+    if IN_FLIGHT.with_borrow(Option::is_some) {
+        return;
+    }
+    if !is_online() {
+        persist();
+        return;
+    }
+
+    let batch = QUEUE.with_borrow_mut(|queue| {
+        let len = queue.len().min(MAX_EVENT_BATCH);
+        queue.drain(..len).collect::<Vec<_>>()
+    });
+    if batch.is_empty() {
+        return;
+    }
+
+    IN_FLIGHT.set(Some(batch.clone()));
+    persist();
+    spawn_local(async move {
+        let res = send_events_batch_ssr(batch).await;
+        let batch = IN_FLIGHT.take().unwrap_or_default();
+
+        match res {
+            Ok(()) => {
+                FAILED_ATTEMPTS.set(0);
+                persist();
+                if QUEUE.with_borrow(|queue| !queue.is_empty()) {
+                    flush_events();
+                }
+            }
+            Err(e) => {
+                log::warn!("failed to send analytics events: {e}");
+                QUEUE.with_borrow_mut(|queue| {
+                    for event in batch.into_iter().rev() {
+                        queue.push_front(event);
+                    }
+                });
+                persist();
+
+                let attempts = FAILED_ATTEMPTS.get() + 1;
+                FAILED_ATTEMPTS.set(attempts);
+                let delay = RETRY_BASE_MS
+                    .saturating_mul(1 << attempts.min(16))
+                    .min(RETRY_MAX_MS);
+                schedule_flush(delay);
+            }
+        }
+    });
+}
+
+/// Last chance flush as the page goes away
+/// everything not yet acknowledged is persisted first, then sent on the next visit
+/// if the final batch doesn't make it
+pub fn flush_before_unload() {
+    persist();
+    flush_events();
+}
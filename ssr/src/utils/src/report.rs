@@ -2,6 +2,9 @@ use std::{env, fmt::Display};
 
 use leptos::prelude::*;
 use leptos::server;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReportOption {
     Nudity,
     Violence,
@@ -11,6 +14,20 @@ pub enum ReportOption {
 }
 
 impl ReportOption {
+    pub const ALL: [Self; 5] = [
+        Self::Nudity,
+        Self::Violence,
+        Self::Offensive,
+        Self::Spam,
+        Self::Other,
+    ];
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|opt| opt.as_str().to_string() == label)
+    }
+
     pub fn as_str(&self) -> impl Display {
         match self {
             ReportOption::Nudity => "Nudity/Porn",
@@ -1,3 +1,4 @@
+#[cfg(all(feature = "ssr", not(feature = "local-bin")))]
 use std::env;
 
 use leptos::prelude::*;
@@ -22,15 +23,20 @@ pub struct NSFWInfo {
     pub csam_detected: bool,
 }
 
-#[cfg(feature = "local-bin")]
 #[server]
-pub async fn get_nsfw_info(_base64_image: String) -> Result<NSFWInfo, ServerFnError> {
+pub async fn get_nsfw_info(base64_image: String) -> Result<NSFWInfo, ServerFnError> {
+    detect_nsfw_img(base64_image).await
+}
+
+/// Local builds don't run the detector, everything is considered safe
+#[cfg(all(feature = "ssr", feature = "local-bin"))]
+pub async fn detect_nsfw_img(_base64_image: String) -> Result<NSFWInfo, ServerFnError> {
     Ok(Default::default())
 }
 
-#[cfg(not(feature = "local-bin"))]
-#[server]
-pub async fn get_nsfw_info(base64_image: String) -> Result<NSFWInfo, ServerFnError> {
+/// Runs a base64 encoded image through the NSFW detector
+#[cfg(all(feature = "ssr", not(feature = "local-bin")))]
+pub async fn detect_nsfw_img(base64_image: String) -> Result<NSFWInfo, ServerFnError> {
     use tonic::metadata::MetadataValue;
     use tonic::Request;
 